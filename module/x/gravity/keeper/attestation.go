@@ -214,6 +214,23 @@ func (k Keeper) GetAttestationMapping(ctx sdk.Context) (out map[uint64][]types.A
 	return
 }
 
+// GetAttestationsByNonce returns every attestation for the given event nonce
+func (k Keeper) GetAttestationsByNonce(ctx sdk.Context, eventNonce uint64) []*types.Attestation {
+	store := ctx.KVStore(k.storeKey)
+	// the attestation key without a claim hash is the prefix for every claim at this nonce
+	prefix := types.GetAttestationKey(eventNonce, []byte{})
+	iter := store.Iterator(prefixRange(prefix))
+	defer iter.Close()
+
+	var out []*types.Attestation
+	for ; iter.Valid(); iter.Next() {
+		var att types.Attestation
+		k.cdc.MustUnmarshalBinaryBare(iter.Value(), &att)
+		out = append(out, &att)
+	}
+	return out
+}
+
 // IterateAttestaions iterates through all attestations
 func (k Keeper) IterateAttestaions(ctx sdk.Context, cb func([]byte, types.Attestation) bool) {
 	store := ctx.KVStore(k.storeKey)
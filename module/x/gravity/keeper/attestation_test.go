@@ -51,3 +51,42 @@ func TestGetMostRecentAttestations(t *testing.T) {
 			"The %vth claim does not match our message: claim %v\n message %v", n, attest.Claim, msgs[n])
 	}
 }
+
+// Checks that querying by nonce returns only the attestations for that nonce
+func TestGetAttestationsByNonce(t *testing.T) {
+	input := CreateTestEnv(t)
+	k := input.GravityKeeper
+	ctx := input.Context
+
+	for i := 0; i < 3; i++ {
+		nonce := uint64(1 + i)
+		msg := types.MsgSendToCosmosClaim{
+			EventNonce:     nonce,
+			BlockHeight:    1,
+			TokenContract:  "0x00000000000000000001",
+			Amount:         sdktypes.NewInt(10000000000 + int64(i)),
+			EthereumSender: "0x00000000000000000002",
+			CosmosReceiver: "0x00000000000000000003",
+			Orchestrator:   "0x00000000000000000004",
+		}
+		any, _ := codectypes.NewAnyWithValue(&msg)
+		hash, err := msg.ClaimHash()
+		require.NoError(t, err)
+		k.SetAttestation(ctx, nonce, hash, &types.Attestation{
+			Observed: false,
+			Height:   uint64(ctx.BlockHeight()),
+			Claim:    any,
+		})
+	}
+
+	res, err := k.GetAttestations(sdktypes.WrapSDKContext(ctx), &types.QueryAttestationsRequest{Nonce: 2})
+	require.NoError(t, err)
+	require.Len(t, res.Attestations, 1)
+	claim, err := k.UnpackAttestationClaim(res.Attestations[0])
+	require.NoError(t, err)
+	require.Equal(t, uint64(2), claim.GetEventNonce())
+
+	res, err = k.GetAttestations(sdktypes.WrapSDKContext(ctx), &types.QueryAttestationsRequest{Nonce: 4})
+	require.NoError(t, err)
+	require.Empty(t, res.Attestations)
+}
@@ -161,6 +161,46 @@ func (k Keeper) LastPendingLogicCallByAddr(
 	return &types.QueryLastPendingLogicCallByAddrResponse{Call: pendingLogicReq}, nil
 }
 
+// UnsignedBatchesByAddr returns every outgoing batch the given orchestrator address has not confirmed
+func (k Keeper) UnsignedBatchesByAddr(
+	c context.Context,
+	req *types.QueryLastPendingBatchRequestByAddrRequest) (*types.QueryOutgoingTxBatchesResponse, error) {
+	ctx := sdk.UnwrapSDKContext(c)
+	addr, err := sdk.AccAddressFromBech32(req.Address)
+	if err != nil {
+		return nil, sdkerrors.Wrap(sdkerrors.ErrInvalidRequest, "address invalid")
+	}
+
+	var batches []*types.OutgoingTxBatch
+	k.IterateOutgoingTXBatches(ctx, func(_ []byte, batch *types.InternalOutgoingTxBatch) bool {
+		if k.GetBatchConfirm(ctx, batch.BatchNonce, batch.TokenContract, addr) == nil {
+			batches = append(batches, batch.ToExternal())
+		}
+		return len(batches) == MaxResults
+	})
+	return &types.QueryOutgoingTxBatchesResponse{Batches: batches}, nil
+}
+
+// UnsignedLogicCallsByAddr returns every outgoing logic call the given orchestrator address has not confirmed
+func (k Keeper) UnsignedLogicCallsByAddr(
+	c context.Context,
+	req *types.QueryLastPendingLogicCallByAddrRequest) (*types.QueryOutgoingLogicCallsResponse, error) {
+	ctx := sdk.UnwrapSDKContext(c)
+	addr, err := sdk.AccAddressFromBech32(req.Address)
+	if err != nil {
+		return nil, sdkerrors.Wrap(sdkerrors.ErrInvalidRequest, "address invalid")
+	}
+
+	var calls []*types.OutgoingLogicCall
+	k.IterateOutgoingLogicCalls(ctx, func(_ []byte, call *types.OutgoingLogicCall) bool {
+		if k.GetLogicCallConfirm(ctx, call.InvalidationId, call.InvalidationNonce, addr) == nil {
+			calls = append(calls, call)
+		}
+		return len(calls) == MaxResults
+	})
+	return &types.QueryOutgoingLogicCallsResponse{Calls: calls}, nil
+}
+
 // OutgoingTxBatches queries the OutgoingTxBatches of the gravity module
 func (k Keeper) OutgoingTxBatches(
 	c context.Context,
@@ -281,11 +321,14 @@ func (k Keeper) ERC20ToDenom(
 	return &ret, nil
 }
 
-// GetAttestations queries the attestation map
+// GetAttestations queries the attestation map, or only the attestations for one event nonce if a nonce is given
 func (k Keeper) GetAttestations(
 	c context.Context,
 	req *types.QueryAttestationsRequest) (*types.QueryAttestationsResponse, error) {
 	ctx := sdk.UnwrapSDKContext(c)
+	if req.Nonce != 0 {
+		return &types.QueryAttestationsResponse{Attestations: k.GetAttestationsByNonce(ctx, req.Nonce)}, nil
+	}
 	limit := req.Limit
 	if limit > QUERY_ATTESTATIONS_LIMIT {
 		limit = QUERY_ATTESTATIONS_LIMIT
@@ -0,0 +1,93 @@
+package keeper
+
+import (
+	"bytes"
+	"encoding/hex"
+	"testing"
+
+	sdk "github.com/cosmos/cosmos-sdk/types"
+	"github.com/stretchr/testify/require"
+
+	"github.com/althea-net/cosmos-gravity-bridge/module/x/gravity/types"
+)
+
+//nolint: exhaustivestruct
+func TestUnsignedBatchesByAddr(t *testing.T) {
+	input := CreateTestEnv(t)
+	tokenContract := "0xAb5801a7D398351b8bE11C439e05C5B3259aeC9B"
+	var orchestrator sdk.AccAddress = bytes.Repeat([]byte{byte(1)}, sdk.AddrLen)
+	var other sdk.AccAddress = bytes.Repeat([]byte{byte(2)}, sdk.AddrLen)
+
+	createTestBatch(t, input)
+	ctx := input.Context
+	k := input.GravityKeeper
+	wctx := sdk.WrapSDKContext(ctx)
+
+	res, err := k.UnsignedBatchesByAddr(wctx, &types.QueryLastPendingBatchRequestByAddrRequest{Address: orchestrator.String()})
+	require.NoError(t, err)
+	require.Len(t, res.Batches, 1)
+	require.Equal(t, uint64(1), res.Batches[0].BatchNonce)
+
+	k.SetBatchConfirm(ctx, &types.MsgConfirmBatch{
+		Nonce:         1,
+		TokenContract: tokenContract,
+		EthSigner:     "0xf35e2cc8e6523d683ed44870f5b7cc785051a77d",
+		Orchestrator:  orchestrator.String(),
+		Signature:     "signature",
+	})
+	res, err = k.UnsignedBatchesByAddr(wctx, &types.QueryLastPendingBatchRequestByAddrRequest{Address: orchestrator.String()})
+	require.NoError(t, err)
+	require.Empty(t, res.Batches)
+	// confirms are tracked per orchestrator
+	res, err = k.UnsignedBatchesByAddr(wctx, &types.QueryLastPendingBatchRequestByAddrRequest{Address: other.String()})
+	require.NoError(t, err)
+	require.Len(t, res.Batches, 1)
+
+	_, err = k.UnsignedBatchesByAddr(wctx, &types.QueryLastPendingBatchRequestByAddrRequest{Address: "invalid"})
+	require.Error(t, err)
+}
+
+//nolint: exhaustivestruct
+func TestUnsignedLogicCallsByAddr(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	k := input.GravityKeeper
+	wctx := sdk.WrapSDKContext(ctx)
+	var orchestrator sdk.AccAddress = bytes.Repeat([]byte{byte(1)}, sdk.AddrLen)
+	tokenContract := "0x7580bfe88dd3d07947908fae12d95872a260f2d8"
+	invalidationId := []byte("GravityTesting")
+	token := []*types.ERC20Token{{
+		Contract: tokenContract,
+		Amount:   sdk.NewIntFromUint64(5000),
+	}}
+	for nonce := uint64(1); nonce <= 2; nonce++ {
+		k.SetOutgoingLogicCall(ctx, &types.OutgoingLogicCall{
+			Transfers:            token,
+			Fees:                 token,
+			LogicContractAddress: "0x510ab76899430424d209a6c9a5b9951fb8a6f47d",
+			Payload:              []byte("fake bytes"),
+			Timeout:              10000,
+			InvalidationId:       invalidationId,
+			InvalidationNonce:    nonce,
+		})
+	}
+
+	res, err := k.UnsignedLogicCallsByAddr(wctx, &types.QueryLastPendingLogicCallByAddrRequest{Address: orchestrator.String()})
+	require.NoError(t, err)
+	require.Len(t, res.Calls, 2)
+
+	k.SetLogicCallConfirm(ctx, &types.MsgConfirmLogicCall{
+		InvalidationId:    hex.EncodeToString(invalidationId),
+		InvalidationNonce: 1,
+		EthSigner:         "0xf35e2cc8e6523d683ed44870f5b7cc785051a77d",
+		Orchestrator:      orchestrator.String(),
+		Signature:         "signature",
+	})
+	res, err = k.UnsignedLogicCallsByAddr(wctx, &types.QueryLastPendingLogicCallByAddrRequest{Address: orchestrator.String()})
+	require.NoError(t, err)
+	require.Len(t, res.Calls, 1)
+	require.Equal(t, uint64(2), res.Calls[0].InvalidationNonce)
+
+	_, err = k.UnsignedLogicCallsByAddr(wctx, &types.QueryLastPendingLogicCallByAddrRequest{Address: "invalid"})
+	require.Error(t, err)
+}
@@ -1635,6 +1635,7 @@ func (m *QueryDenomToERC20Response) GetCosmosOriginated() bool {
 
 type QueryAttestationsRequest struct {
 	Limit uint64 `protobuf:"varint,1,opt,name=limit,proto3" json:"limit,omitempty"`
+	Nonce uint64 `protobuf:"varint,2,opt,name=nonce,proto3" json:"nonce,omitempty"`
 }
 
 func (m *QueryAttestationsRequest) Reset()         { *m = QueryAttestationsRequest{} }
@@ -1677,6 +1678,13 @@ func (m *QueryAttestationsRequest) GetLimit() uint64 {
 	return 0
 }
 
+func (m *QueryAttestationsRequest) GetNonce() uint64 {
+	if m != nil {
+		return m.Nonce
+	}
+	return 0
+}
+
 type QueryAttestationsResponse struct {
 	Attestations []*Attestation `protobuf:"bytes,1,rep,name=attestations,proto3" json:"attestations,omitempty"`
 }
@@ -2167,124 +2175,127 @@ func init() {
 func init() { proto.RegisterFile("gravity/v1/query.proto", fileDescriptor_29a9d4192703013c) }
 
 var fileDescriptor_29a9d4192703013c = []byte{
-	// 1871 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xac, 0x99, 0x49, 0x6f, 0x1b, 0xc9,
-	0x15, 0xc7, 0xd5, 0x8a, 0x25, 0xd9, 0xcf, 0x7b, 0x89, 0x72, 0xe4, 0x96, 0x45, 0x4a, 0x6d, 0xcb,
-	0xb6, 0xd6, 0xd6, 0x12, 0x2f, 0x89, 0x83, 0x20, 0xa6, 0x2c, 0x3b, 0x86, 0xed, 0xc8, 0x61, 0x14,
-	0x67, 0xb1, 0xe1, 0x46, 0x93, 0x5d, 0x22, 0x1b, 0xa1, 0xba, 0xe5, 0xee, 0x12, 0x21, 0xc2, 0xb0,
-	0x81, 0xe4, 0x90, 0x00, 0x39, 0x05, 0xc8, 0x06, 0xe4, 0x34, 0xb7, 0x99, 0xd3, 0x1c, 0x67, 0x8e,
-	0x03, 0xcc, 0xc9, 0xc0, 0x5c, 0x0c, 0xcc, 0x65, 0x4e, 0x83, 0x81, 0x3d, 0x1f, 0x61, 0x3e, 0xc0,
-	0xa0, 0xab, 0x5e, 0x37, 0x7b, 0xa9, 0x26, 0x29, 0x61, 0x4e, 0x22, 0x5f, 0xbf, 0xe5, 0xf7, 0xaa,
-	0xaa, 0xab, 0xea, 0x2f, 0xc2, 0xb9, 0xba, 0x67, 0xb6, 0x6c, 0xd6, 0xd6, 0x5b, 0x2b, 0xfa, 0x8b,
-	0x3d, 0xea, 0xb5, 0x97, 0x76, 0x3d, 0x97, 0xb9, 0x04, 0xd0, 0xbe, 0xd4, 0x5a, 0x51, 0xc7, 0x63,
-	0x3e, 0x75, 0xea, 0x50, 0xdf, 0xf6, 0x85, 0x97, 0x1a, 0x8f, 0x66, 0xed, 0x5d, 0x1a, 0xda, 0xc7,
-	0x62, 0xf6, 0x1d, 0xbf, 0x2e, 0x33, 0xef, 0xba, 0x6e, 0x53, 0x92, 0xa5, 0x6a, 0xb2, 0x5a, 0x03,
-	0xed, 0x17, 0x62, 0x76, 0x93, 0x31, 0xea, 0x33, 0x93, 0xd9, 0xae, 0x13, 0x3d, 0x75, 0xdd, 0x7a,
-	0x93, 0xea, 0xe6, 0xae, 0xad, 0x9b, 0x8e, 0xe3, 0x8a, 0x87, 0x61, 0xa9, 0x42, 0xdd, 0xad, 0xbb,
-	0xfc, 0xa3, 0x1e, 0x7c, 0x12, 0x56, 0xad, 0x00, 0xe4, 0x37, 0x41, 0x93, 0x8f, 0x4d, 0xcf, 0xdc,
-	0xf1, 0x2b, 0xf4, 0xc5, 0x1e, 0xf5, 0x99, 0x76, 0x0f, 0x46, 0x13, 0x56, 0x7f, 0xd7, 0x75, 0x7c,
-	0x4a, 0x96, 0x61, 0x78, 0x97, 0x5b, 0xc6, 0x95, 0x29, 0xe5, 0xea, 0xf1, 0x55, 0xb2, 0xd4, 0x19,
-	0x93, 0x25, 0xe1, 0x5b, 0x3e, 0xf2, 0xe6, 0xeb, 0xd2, 0x40, 0x05, 0xfd, 0xb4, 0x09, 0x38, 0xcf,
-	0x13, 0xad, 0xef, 0x79, 0x1e, 0x75, 0xd8, 0x13, 0xb3, 0xe9, 0x53, 0x16, 0x56, 0xf9, 0x15, 0xa8,
-	0xb2, 0x87, 0x58, 0x6c, 0x0e, 0x86, 0x5b, 0xdc, 0x22, 0x2b, 0x86, 0xbe, 0xe8, 0xa1, 0xad, 0x60,
-	0x99, 0x44, 0x7e, 0xfc, 0x43, 0x0a, 0x30, 0xe4, 0xb8, 0x4e, 0x8d, 0xf2, 0x3c, 0x47, 0x2a, 0xe2,
-	0x4b, 0x54, 0x3c, 0x15, 0x72, 0x88, 0xe2, 0x0f, 0x12, 0xc5, 0xd7, 0x5d, 0x67, 0xdb, 0xf6, 0x76,
-	0xba, 0x16, 0x27, 0xe3, 0x30, 0x62, 0x5a, 0x96, 0x47, 0x7d, 0x7f, 0x7c, 0x70, 0x4a, 0xb9, 0x7a,
-	0xac, 0x12, 0x7e, 0xd5, 0xb6, 0x12, 0x58, 0x51, 0x32, 0xc4, 0xba, 0x0e, 0x23, 0x35, 0x61, 0x42,
-	0xae, 0x0b, 0x71, 0xae, 0x47, 0x7e, 0x3d, 0x19, 0x16, 0x3a, 0x6b, 0x3f, 0x85, 0xe9, 0x6c, 0x56,
-	0xbf, 0xdc, 0xfe, 0x75, 0x40, 0xd3, 0x7d, 0x9c, 0x9e, 0x83, 0xd6, 0x2d, 0x14, 0xc1, 0x6e, 0xc2,
-	0x51, 0xac, 0x15, 0xac, 0x8d, 0x1f, 0xf5, 0x24, 0x8b, 0xbc, 0xb5, 0x29, 0x28, 0xf2, 0xfc, 0x0f,
-	0x4d, 0x3f, 0xb9, 0x3c, 0xa2, 0xc5, 0xb8, 0x09, 0xa5, 0x5c, 0x0f, 0x2c, 0xbf, 0x00, 0x23, 0x62,
-	0x32, 0xc2, 0xea, 0xb2, 0xf9, 0x0a, 0x5d, 0xb4, 0xbb, 0x30, 0x17, 0x25, 0x7c, 0x4c, 0x1d, 0xcb,
-	0x76, 0xea, 0x89, 0xbc, 0xe5, 0xf6, 0x6d, 0xcb, 0xf2, 0xc2, 0x61, 0x89, 0xcd, 0x95, 0x92, 0x9c,
-	0xab, 0xa7, 0x30, 0xdf, 0x57, 0x9e, 0x43, 0x41, 0x9e, 0x83, 0x02, 0x4f, 0x5e, 0x0e, 0x5e, 0xff,
-	0xbb, 0x34, 0x9c, 0x25, 0xed, 0x11, 0x8c, 0xa5, 0xec, 0x98, 0xfe, 0x27, 0x00, 0x7c, 0xab, 0x30,
-	0xb6, 0x29, 0x0d, 0x2b, 0x8c, 0xc5, 0x2b, 0x84, 0x11, 0x7e, 0xe5, 0x58, 0x35, 0xfc, 0xa8, 0x6d,
-	0xc0, 0x6c, 0xba, 0x07, 0xee, 0x77, 0xc0, 0xa1, 0x30, 0xb2, 0x43, 0x2a, 0x4b, 0x83, 0xa8, 0x2b,
-	0x30, 0xc4, 0x09, 0x70, 0x11, 0x4f, 0xc4, 0x29, 0x37, 0xf7, 0x58, 0xdd, 0xb5, 0x9d, 0xfa, 0xd6,
-	0xbe, 0x48, 0x20, 0x3c, 0xb5, 0x32, 0x5c, 0x4e, 0x17, 0x78, 0xe8, 0xd6, 0xed, 0xda, 0xba, 0xd9,
-	0x6c, 0xf6, 0x0b, 0xf9, 0x0c, 0xae, 0xf4, 0xcc, 0x11, 0x11, 0x1e, 0xa9, 0x99, 0xcd, 0x26, 0x02,
-	0x4e, 0xca, 0x00, 0xa3, 0xd0, 0x0a, 0x77, 0xd5, 0x4a, 0x30, 0xc9, 0xb3, 0xa7, 0x1a, 0xa0, 0xd1,
-	0x3a, 0xfe, 0x3d, 0xae, 0x74, 0x89, 0x03, 0x56, 0xbd, 0x06, 0x23, 0x55, 0x61, 0xc2, 0xf9, 0xeb,
-	0x3a, 0x32, 0xa1, 0x6f, 0xf4, 0x0a, 0x65, 0xc8, 0xa2, 0xd2, 0x4f, 0xf0, 0x15, 0x92, 0x79, 0x60,
-	0xed, 0x35, 0x18, 0x0a, 0xda, 0x08, 0x2b, 0xf7, 0x68, 0x59, 0xf8, 0x6a, 0x55, 0xcc, 0x9b, 0x9c,
-	0xeb, 0xde, 0xbb, 0x0a, 0x99, 0x85, 0x33, 0x35, 0xd7, 0x61, 0x9e, 0x59, 0x63, 0x46, 0x72, 0x27,
-	0x3c, 0x1d, 0xda, 0x6f, 0xe3, 0xac, 0xfd, 0x0e, 0xa6, 0xf2, 0x6b, 0x1c, 0x7e, 0x41, 0x3d, 0xc3,
-	0x5d, 0x9b, 0x1b, 0xc3, 0x6d, 0xed, 0x07, 0x84, 0x56, 0x65, 0xd9, 0x11, 0xf7, 0x46, 0x66, 0xb7,
-	0x9c, 0x48, 0xed, 0x96, 0x18, 0x22, 0x88, 0x3b, 0x9b, 0xa5, 0x8f, 0xd0, 0x62, 0x22, 0x52, 0xd0,
-	0x57, 0xe0, 0xb4, 0xed, 0xb4, 0xcc, 0xa6, 0x6d, 0xf1, 0x73, 0xdf, 0xb0, 0x2d, 0x8e, 0x7f, 0xa2,
-	0x72, 0x2a, 0x6e, 0xbe, 0x6f, 0x91, 0x45, 0x20, 0x09, 0x47, 0xd1, 0xea, 0x20, 0x6f, 0xf5, 0x6c,
-	0xfc, 0x09, 0x1f, 0x64, 0xed, 0x8f, 0xd8, 0x4b, 0xaa, 0x28, 0xf6, 0x72, 0x2b, 0xd3, 0x4b, 0x49,
-	0xde, 0x4b, 0x67, 0xf1, 0x74, 0xfa, 0xf9, 0x39, 0xce, 0x6d, 0xf0, 0x46, 0x6e, 0xb4, 0xa8, 0xc3,
-	0x78, 0xc5, 0x7e, 0xdf, 0xe7, 0x3b, 0x78, 0xaa, 0xc9, 0xa3, 0x91, 0xaf, 0x04, 0xc7, 0x69, 0xf0,
-	0xcc, 0x88, 0x4f, 0x28, 0xd0, 0xc8, 0x5d, 0x5b, 0x86, 0x71, 0x9e, 0x65, 0xa3, 0xb2, 0xbe, 0xba,
-	0xbc, 0xe5, 0xde, 0xa1, 0x8e, 0x1b, 0x3f, 0xbd, 0xa9, 0x57, 0x5b, 0x5d, 0xc6, 0xca, 0xe2, 0x8b,
-	0xf6, 0x1c, 0x67, 0x21, 0x19, 0x81, 0xf5, 0x0a, 0x30, 0x64, 0x05, 0x86, 0x30, 0x84, 0x7f, 0x21,
-	0xf3, 0x70, 0xb6, 0xe6, 0xfa, 0x3b, 0xae, 0x6f, 0xb8, 0x9e, 0x5d, 0xb7, 0x1d, 0x93, 0x51, 0x8b,
-	0x8f, 0xf8, 0xd1, 0xca, 0x19, 0xf1, 0x60, 0x33, 0xb2, 0x47, 0x44, 0x3c, 0xf1, 0x96, 0xcb, 0xcb,
-	0xc4, 0x88, 0xb2, 0xe9, 0x23, 0xa2, 0x64, 0x44, 0x87, 0x28, 0xdb, 0xc4, 0xe1, 0x88, 0x6e, 0x77,
-	0xee, 0x9c, 0xf1, 0x77, 0xa5, 0x69, 0xef, 0xd8, 0x2c, 0x7c, 0x57, 0xf8, 0x17, 0xed, 0x0f, 0x48,
-	0x94, 0x8c, 0x88, 0xd6, 0xcc, 0x89, 0xd8, 0xed, 0x35, 0x5c, 0x37, 0x3f, 0x8e, 0xaf, 0x9b, 0x58,
-	0x5c, 0x25, 0xe1, 0xac, 0x55, 0xe0, 0x22, 0xf6, 0xda, 0xa4, 0x75, 0x93, 0xd1, 0x07, 0xb4, 0xed,
-	0x97, 0x83, 0xeb, 0x49, 0xb0, 0x68, 0x5d, 0x0f, 0xdf, 0xc0, 0xa0, 0xbf, 0x56, 0x68, 0x33, 0x92,
-	0x0b, 0xe8, 0x4c, 0x2b, 0xe5, 0xac, 0xfd, 0x45, 0xc1, 0xa3, 0xbc, 0x7b, 0xd2, 0xc4, 0xa2, 0x62,
-	0x8d, 0x54, 0x5a, 0xa0, 0xac, 0x11, 0x56, 0x5f, 0x81, 0x82, 0xeb, 0x05, 0x9b, 0x33, 0xf3, 0x12,
-	0x00, 0x62, 0xbb, 0x18, 0x8d, 0x3f, 0x0b, 0x19, 0x7e, 0x89, 0xe7, 0x47, 0x12, 0x61, 0xa3, 0x93,
-	0xb3, 0x57, 0x51, 0xed, 0xef, 0x0a, 0xcc, 0x74, 0x4d, 0x11, 0xf1, 0x1f, 0x64, 0x70, 0x0e, 0xd3,
-	0xcb, 0x53, 0x3c, 0xad, 0x93, 0x20, 0x9b, 0x59, 0xcf, 0xdc, 0xe4, 0x4a, 0x7e, 0xf2, 0xd7, 0xb0,
-	0xd4, 0x5f, 0xf2, 0xc3, 0xb5, 0x9b, 0x1a, 0xe6, 0xc1, 0xcc, 0x30, 0xff, 0x02, 0x6f, 0x60, 0x78,
-	0x85, 0xf8, 0x2d, 0x75, 0xac, 0x2d, 0x77, 0x83, 0x35, 0xc8, 0x0c, 0x9c, 0xf2, 0xa9, 0x63, 0xd1,
-	0x74, 0x8d, 0x93, 0xc2, 0x1a, 0xc6, 0x7f, 0xae, 0xe0, 0x4c, 0xa7, 0x13, 0x44, 0xbc, 0x8f, 0xa1,
-	0xc0, 0x3c, 0xd3, 0xf1, 0xb7, 0xa9, 0xe7, 0x1b, 0xb6, 0x63, 0x24, 0x2f, 0x05, 0x45, 0xe9, 0xe9,
-	0x86, 0xfe, 0x5b, 0xfb, 0x15, 0x12, 0xc5, 0xde, 0x77, 0xf0, 0x86, 0x41, 0x36, 0x61, 0x74, 0xcf,
-	0x11, 0x69, 0x2c, 0x23, 0x7a, 0x3e, 0x3e, 0xd8, 0x5f, 0xc2, 0x28, 0x34, 0x34, 0xfa, 0xab, 0xdf,
-	0x4d, 0xc0, 0x10, 0x6f, 0x82, 0xd8, 0x30, 0x2c, 0xa4, 0x1f, 0x49, 0xe4, 0xc9, 0xaa, 0x4a, 0xb5,
-	0x94, 0xfb, 0x5c, 0xf4, 0xad, 0x15, 0xff, 0xfa, 0xe5, 0xb7, 0xff, 0x1a, 0x1c, 0x27, 0xe7, 0xf4,
-	0x8e, 0xce, 0xad, 0x52, 0x66, 0xea, 0x42, 0x4d, 0x92, 0xbf, 0x29, 0x70, 0x32, 0x21, 0x16, 0xc9,
-	0x4c, 0x26, 0xa5, 0x4c, 0x69, 0xaa, 0x97, 0x7b, 0xb9, 0x21, 0xc0, 0x65, 0x0e, 0x30, 0x45, 0x8a,
-	0x69, 0x00, 0x71, 0x2b, 0xd7, 0x6b, 0x22, 0x8a, 0xbc, 0x86, 0x93, 0x89, 0x02, 0x12, 0x0e, 0x99,
-	0x14, 0x95, 0x70, 0x48, 0xe5, 0x67, 0xfe, 0x40, 0x08, 0x0e, 0x3e, 0x10, 0x09, 0x41, 0x95, 0x0b,
-	0x90, 0x94, 0xa3, 0xb9, 0x00, 0x29, 0xa1, 0xd9, 0x7b, 0x20, 0xb0, 0xec, 0x07, 0x0a, 0x8c, 0x49,
-	0x95, 0x21, 0x59, 0xec, 0x5e, 0x29, 0x25, 0x3e, 0xd5, 0xa5, 0x7e, 0xdd, 0x11, 0xf0, 0x2a, 0x07,
-	0xd4, 0xc8, 0x54, 0x1a, 0x30, 0xbc, 0x5b, 0xe8, 0x2f, 0xf9, 0x81, 0xff, 0x8a, 0xfc, 0x57, 0x01,
-	0x92, 0x95, 0x8e, 0x64, 0x2e, 0x53, 0x30, 0x57, 0x81, 0xaa, 0xf3, 0x7d, 0xf9, 0x22, 0xd9, 0x15,
-	0x4e, 0x36, 0x4d, 0x4a, 0x39, 0x43, 0xe7, 0x85, 0x04, 0x9f, 0x28, 0x50, 0xec, 0x2e, 0x1d, 0xc9,
-	0x75, 0x69, 0xe1, 0x9e, 0x9a, 0x55, 0xbd, 0x71, 0xe0, 0x38, 0x84, 0xbf, 0xc8, 0xe1, 0x27, 0xc9,
-	0x44, 0x0e, 0x7c, 0xd3, 0xf4, 0x19, 0xf9, 0x54, 0x81, 0xc9, 0xae, 0x42, 0x8f, 0x5c, 0xeb, 0x56,
-	0x3f, 0x57, 0x5f, 0xaa, 0xd7, 0x0f, 0x1a, 0xd6, 0x6b, 0xc8, 0xf9, 0xb6, 0xa5, 0xbf, 0xc4, 0xed,
-	0xf8, 0x15, 0xf9, 0x58, 0x01, 0x35, 0x5f, 0xfd, 0x91, 0xd5, 0x6e, 0xf5, 0xe5, 0x72, 0x53, 0x5d,
-	0x3b, 0x50, 0x4c, 0x2f, 0xe0, 0x66, 0x10, 0x10, 0x03, 0xfe, 0x48, 0x81, 0x82, 0xec, 0x7a, 0x4b,
-	0x16, 0xa4, 0x65, 0x73, 0xee, 0xd0, 0xea, 0x62, 0x9f, 0xde, 0x88, 0xb7, 0xc6, 0xf1, 0x16, 0xc9,
-	0x7c, 0x1a, 0xcf, 0xf5, 0xcc, 0x5a, 0x93, 0xea, 0xfc, 0xf6, 0xcc, 0x5f, 0xaf, 0x18, 0xaa, 0x0f,
-	0xc7, 0xa2, 0xff, 0x30, 0x90, 0xa9, 0x4c, 0xc1, 0xd4, 0xff, 0x31, 0xd4, 0xe9, 0x2e, 0x1e, 0x88,
-	0x31, 0xcd, 0x31, 0x26, 0xc8, 0x79, 0xe9, 0xb4, 0x6e, 0x07, 0x75, 0xfe, 0xad, 0xc0, 0xd9, 0x8c,
-	0x9e, 0x26, 0xb3, 0x99, 0xdc, 0x79, 0xa2, 0x5c, 0x9d, 0xeb, 0xc7, 0xb5, 0xd7, 0x9e, 0x23, 0x96,
-	0x99, 0x8b, 0x81, 0x6c, 0x9f, 0xfc, 0x5f, 0x01, 0x92, 0xd5, 0xda, 0x24, 0xbf, 0x58, 0x46, 0xb2,
-	0x4b, 0xf6, 0x9c, 0x7c, 0xf1, 0xae, 0xcd, 0x73, 0xb2, 0x19, 0x72, 0xb1, 0x3b, 0x19, 0x5f, 0x5d,
-	0xe4, 0x7f, 0x0a, 0x8c, 0x4a, 0xc4, 0x34, 0x99, 0x97, 0xcf, 0x88, 0x54, 0xd6, 0xab, 0x0b, 0xfd,
-	0x39, 0x23, 0xdf, 0x0c, 0xe7, 0x2b, 0x91, 0xc9, 0x9c, 0x17, 0x14, 0xb7, 0xea, 0xe0, 0x58, 0x4b,
-	0x28, 0x66, 0xc9, 0xb1, 0x26, 0xd3, 0xeb, 0x92, 0x63, 0x4d, 0x2a, 0xbc, 0xf3, 0x8f, 0x35, 0xc1,
-	0x11, 0x9e, 0x1d, 0x1c, 0x24, 0x21, 0x77, 0x25, 0x20, 0x32, 0x0d, 0x2e, 0x01, 0x91, 0xaa, 0xe6,
-	0x7c, 0x10, 0xb1, 0x01, 0x44, 0x20, 0xff, 0x51, 0xe0, 0x44, 0x5c, 0x66, 0x92, 0x4b, 0x99, 0x02,
-	0x12, 0xdd, 0xaa, 0xce, 0xf4, 0xf0, 0x42, 0x8a, 0x9b, 0x9c, 0x62, 0x95, 0x2c, 0x67, 0x0f, 0xd1,
-	0x94, 0x32, 0xd4, 0xb9, 0x68, 0x34, 0x98, 0x6b, 0x08, 0x3d, 0x1b, 0x70, 0xc5, 0xc5, 0xa6, 0x84,
-	0x4b, 0xa2, 0x5e, 0x25, 0x5c, 0x32, 0xc5, 0x7a, 0x10, 0x2e, 0x8e, 0x13, 0x70, 0x09, 0x55, 0xfb,
-	0x0f, 0x05, 0x4e, 0xdf, 0xa3, 0x2c, 0xae, 0x3a, 0x25, 0x68, 0x12, 0x19, 0x2b, 0x41, 0x93, 0x49,
-	0x57, 0x6d, 0x8e, 0xa3, 0x5d, 0x22, 0x5a, 0x1a, 0x8d, 0xff, 0x54, 0x64, 0xc4, 0x95, 0x2a, 0xf9,
-	0x4c, 0x81, 0xf3, 0xf7, 0x28, 0x8b, 0xe9, 0x94, 0x98, 0xa4, 0x24, 0xba, 0x64, 0x2c, 0xba, 0x89,
-	0x4f, 0xc9, 0xa1, 0xde, 0x9f, 0x5a, 0xcd, 0x1f, 0x4e, 0xc1, 0x6c, 0x61, 0x16, 0xe3, 0xcf, 0xb4,
-	0xed, 0x1b, 0xd5, 0xb6, 0x11, 0x49, 0x22, 0xf2, 0xa1, 0x02, 0xa3, 0xe9, 0x0e, 0x02, 0xa5, 0x33,
-	0xdb, 0x03, 0xa5, 0x23, 0x39, 0xd5, 0x95, 0xbe, 0x5d, 0x23, 0xde, 0x55, 0xce, 0xbb, 0x40, 0xe6,
-	0xfa, 0xe4, 0xa5, 0xac, 0x41, 0xbe, 0x50, 0xe0, 0x42, 0x9a, 0x34, 0x2e, 0x09, 0x25, 0x67, 0x7b,
-	0x4f, 0xfd, 0xa8, 0xfe, 0xec, 0xe0, 0x31, 0x51, 0x13, 0xb7, 0x78, 0x13, 0xd7, 0xc8, 0x5a, 0x9f,
-	0x4d, 0xc4, 0x95, 0x6e, 0x70, 0x67, 0x0d, 0xc6, 0x3d, 0xa3, 0x30, 0xb3, 0x87, 0x66, 0xda, 0x45,
-	0x9d, 0xed, 0xe9, 0x12, 0x21, 0xae, 0x70, 0xc4, 0x79, 0x32, 0x2b, 0x47, 0xdc, 0x15, 0x71, 0x46,
-	0xa0, 0x5e, 0xf9, 0x1b, 0xc6, 0x1a, 0xe5, 0x67, 0x6f, 0xde, 0x15, 0x95, 0xb7, 0xef, 0x8a, 0xca,
-	0x37, 0xef, 0x8a, 0xca, 0x3f, 0xdf, 0x17, 0x07, 0xde, 0xbe, 0x2f, 0x0e, 0x7c, 0xf5, 0xbe, 0x38,
-	0xf0, 0xa7, 0x72, 0xdd, 0x66, 0x8d, 0xbd, 0xea, 0x52, 0xcd, 0xdd, 0xd1, 0xcd, 0x26, 0x6b, 0x50,
-	0x73, 0xd1, 0xe1, 0x42, 0x21, 0x78, 0x63, 0x17, 0xb1, 0xc0, 0x62, 0xd5, 0xb3, 0xad, 0x3a, 0xd5,
-	0x77, 0x5c, 0x6b, 0xaf, 0x49, 0xf5, 0xfd, 0xa8, 0x30, 0xff, 0xa9, 0xb4, 0x3a, 0xcc, 0x7f, 0x93,
-	0x5c, 0xfb, 0x3e, 0x00, 0x00, 0xff, 0xff, 0x9c, 0x23, 0x8f, 0x54, 0x83, 0x1d, 0x00, 0x00,
+	// 1918 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xad, 0x99, 0xc9, 0x6f, 0xdc, 0x54,
+	0x18, 0xc0, 0xeb, 0xd0, 0x34, 0xed, 0xd7, 0xfd, 0x25, 0x29, 0x89, 0xd3, 0xcc, 0x24, 0x6e, 0xd3,
+	0x36, 0x99, 0x24, 0x93, 0x85, 0x2e, 0x50, 0x84, 0x68, 0xd2, 0xb4, 0x54, 0x6d, 0x49, 0x19, 0xd2,
+	0xb2, 0xb4, 0xc2, 0xf2, 0xcc, 0xbc, 0x4e, 0x2c, 0x26, 0x76, 0xb0, 0x9d, 0x28, 0x51, 0xd5, 0x4a,
+	0x70, 0x00, 0x89, 0x13, 0x12, 0x9b, 0xc4, 0x89, 0x1b, 0x1c, 0x80, 0x23, 0x1c, 0x91, 0x38, 0x55,
+	0xe2, 0x52, 0xa9, 0x17, 0x4e, 0x08, 0x01, 0x7f, 0x08, 0xcf, 0xef, 0x7d, 0xf6, 0x78, 0x79, 0x9e,
+	0x25, 0xe5, 0x30, 0x9a, 0xf1, 0xe7, 0x6f, 0xf9, 0x7d, 0x6f, 0xff, 0xde, 0xc0, 0xb1, 0x9a, 0x63,
+	0x6c, 0x9a, 0xde, 0x76, 0x71, 0x73, 0xb6, 0xf8, 0xc1, 0x06, 0x75, 0xb6, 0xa7, 0xd7, 0x1d, 0xdb,
+	0xb3, 0x09, 0xa0, 0x7c, 0x7a, 0x73, 0x56, 0x1d, 0x88, 0xe8, 0xd4, 0xa8, 0x45, 0x5d, 0xd3, 0x15,
+	0x5a, 0x6a, 0xd4, 0xda, 0xdb, 0x5e, 0xa7, 0x81, 0xbc, 0x3f, 0x22, 0x5f, 0x73, 0x6b, 0x32, 0xf1,
+	0xba, 0x6d, 0xd7, 0x25, 0x5e, 0xca, 0x86, 0x57, 0x59, 0x45, 0xf9, 0xf1, 0x88, 0xdc, 0xf0, 0x3c,
+	0xea, 0x7a, 0x86, 0x67, 0xda, 0x56, 0xf8, 0xd6, 0xb6, 0x6b, 0x75, 0x5a, 0x34, 0xd6, 0xcd, 0xa2,
+	0x61, 0x59, 0xb6, 0x78, 0x19, 0x84, 0xea, 0xab, 0xd9, 0x35, 0x9b, 0xff, 0x2c, 0xfa, 0xbf, 0x84,
+	0x54, 0xeb, 0x03, 0xf2, 0x86, 0x9f, 0xe4, 0x2d, 0xc3, 0x31, 0xd6, 0xdc, 0x12, 0x65, 0x19, 0xbb,
+	0x9e, 0x76, 0x15, 0x7a, 0x63, 0x52, 0x77, 0x9d, 0xf9, 0xa1, 0x64, 0x06, 0xf6, 0xac, 0x73, 0xc9,
+	0x80, 0x32, 0xa2, 0x9c, 0xd9, 0x3f, 0x47, 0xa6, 0x1b, 0x6d, 0x32, 0x2d, 0x74, 0x17, 0x76, 0x3f,
+	0xfe, 0x33, 0xbf, 0xab, 0x84, 0x7a, 0xda, 0x10, 0x0c, 0x72, 0x47, 0x8b, 0x1b, 0x8e, 0x43, 0x2d,
+	0xef, 0x8e, 0x51, 0x77, 0xa9, 0x17, 0x44, 0x79, 0x0d, 0x54, 0xd9, 0x4b, 0x0c, 0x36, 0x01, 0x7b,
+	0x36, 0xb9, 0x44, 0x16, 0x0c, 0x75, 0x51, 0x43, 0x9b, 0xc5, 0x30, 0x31, 0xff, 0xf8, 0x45, 0xfa,
+	0xa0, 0xdb, 0xb2, 0xad, 0x0a, 0xe5, 0x7e, 0x76, 0x97, 0xc4, 0x43, 0x18, 0x3c, 0x61, 0xb2, 0x83,
+	0xe0, 0xd7, 0x63, 0xc1, 0x17, 0x6d, 0xeb, 0xbe, 0xe9, 0xac, 0x35, 0x0d, 0x4e, 0x06, 0xa0, 0xc7,
+	0xa8, 0x56, 0x1d, 0xea, 0xba, 0x03, 0x5d, 0x4c, 0xbe, 0xaf, 0x14, 0x3c, 0x6a, 0x2b, 0x31, 0xac,
+	0xd0, 0x19, 0x62, 0x9d, 0x83, 0x9e, 0x8a, 0x10, 0x21, 0xd7, 0xf1, 0x28, 0xd7, 0x4d, 0xb7, 0x16,
+	0x37, 0x0b, 0x94, 0xb5, 0x17, 0x61, 0x34, 0xed, 0xd5, 0x5d, 0xd8, 0x7e, 0xdd, 0xa7, 0x69, 0xde,
+	0x4e, 0xef, 0x81, 0xd6, 0xcc, 0x14, 0xc1, 0x2e, 0xc0, 0x5e, 0x8c, 0xe5, 0x8f, 0x8d, 0xe7, 0x5a,
+	0x92, 0x85, 0xda, 0xda, 0x08, 0xe4, 0xb8, 0xff, 0x1b, 0x86, 0x1b, 0x1f, 0x1e, 0xe1, 0x60, 0x5c,
+	0x86, 0x7c, 0xa6, 0x06, 0x86, 0x9f, 0x84, 0x1e, 0xd1, 0x19, 0x41, 0x74, 0x59, 0x7f, 0x05, 0x2a,
+	0xda, 0x15, 0x98, 0x08, 0x1d, 0xde, 0xa2, 0x56, 0xd5, 0xb4, 0x6a, 0x31, 0xbf, 0x0b, 0xdb, 0x97,
+	0x58, 0x6f, 0x04, 0xcd, 0x12, 0xe9, 0x2b, 0x25, 0xde, 0x57, 0x77, 0xa1, 0xd0, 0x96, 0x9f, 0x1d,
+	0x41, 0x1e, 0x83, 0x3e, 0xee, 0x7c, 0xc1, 0x9f, 0xfe, 0x57, 0x68, 0xd0, 0x4b, 0xda, 0x4d, 0xe8,
+	0x4f, 0xc8, 0xd1, 0xfd, 0x0b, 0x00, 0x7c, 0xa9, 0xd0, 0xef, 0x53, 0x1a, 0x44, 0xe8, 0x8f, 0x46,
+	0x08, 0x2c, 0xdc, 0xd2, 0xbe, 0x72, 0xf0, 0x53, 0x5b, 0x82, 0xf1, 0x64, 0x0e, 0x5c, 0xaf, 0xc3,
+	0xa6, 0xd0, 0xd3, 0x4d, 0x2a, 0x73, 0x83, 0xa8, 0xb3, 0xd0, 0xcd, 0x09, 0x70, 0x10, 0x0f, 0x45,
+	0x29, 0x97, 0x37, 0xbc, 0x9a, 0xcd, 0xcc, 0x57, 0xb6, 0x84, 0x03, 0xa1, 0xa9, 0x2d, 0xc0, 0xa9,
+	0x64, 0x80, 0x1b, 0x76, 0xcd, 0xac, 0x2c, 0x1a, 0xf5, 0x7a, 0xbb, 0x90, 0xf7, 0xe0, 0x74, 0x4b,
+	0x1f, 0x21, 0xe1, 0xee, 0x0a, 0x93, 0x22, 0xe0, 0xb0, 0x0c, 0x30, 0x34, 0x2d, 0x71, 0x55, 0x2d,
+	0x0f, 0xc3, 0xdc, 0x7b, 0x22, 0x01, 0x1a, 0x8e, 0xe3, 0xb7, 0x70, 0xa4, 0x4b, 0x14, 0x30, 0xea,
+	0x59, 0xe8, 0x29, 0x0b, 0x11, 0xf6, 0x5f, 0xd3, 0x96, 0x09, 0x74, 0xc3, 0x29, 0x94, 0x22, 0x0b,
+	0x43, 0xdf, 0xc1, 0x29, 0x24, 0xd3, 0xc0, 0xd8, 0xf3, 0xd0, 0xed, 0xa7, 0x11, 0x44, 0x6e, 0x91,
+	0xb2, 0xd0, 0xd5, 0xca, 0xe8, 0x37, 0xde, 0xd7, 0xad, 0x57, 0x15, 0x32, 0x0e, 0x47, 0xd8, 0x0a,
+	0xe0, 0x39, 0x46, 0xc5, 0xd3, 0xe3, 0x2b, 0xe1, 0xe1, 0x40, 0x7e, 0x09, 0x7b, 0xed, 0x36, 0x8c,
+	0x64, 0xc7, 0xd8, 0xf9, 0x80, 0xba, 0x87, 0xab, 0x36, 0x17, 0x06, 0xcb, 0xda, 0xff, 0x08, 0xad,
+	0xca, 0xbc, 0x23, 0xee, 0xf9, 0xd4, 0x6a, 0x39, 0x94, 0x58, 0x2d, 0xd1, 0x44, 0x10, 0x37, 0x16,
+	0x4b, 0x17, 0xa1, 0x45, 0x47, 0x24, 0xa0, 0x4f, 0xc3, 0x61, 0xd3, 0x62, 0xcb, 0x87, 0x59, 0xe5,
+	0xfb, 0xbe, 0x6e, 0x56, 0x39, 0xfe, 0x81, 0xd2, 0xa1, 0xa8, 0xf8, 0x5a, 0x95, 0x4c, 0x01, 0x89,
+	0x29, 0x8a, 0x54, 0xbb, 0x78, 0xaa, 0x47, 0xa3, 0x6f, 0x78, 0x23, 0x6b, 0xef, 0x60, 0x2e, 0x89,
+	0xa0, 0x98, 0xcb, 0xc5, 0x54, 0x2e, 0x79, 0x79, 0x2e, 0x8d, 0xc1, 0xd3, 0xc8, 0xe7, 0x65, 0xec,
+	0x5b, 0x7f, 0x46, 0x2e, 0x6d, 0xb2, 0x33, 0x00, 0x8f, 0xd8, 0xee, 0x7c, 0xbe, 0x8c, 0xbb, 0x9a,
+	0xdc, 0x1a, 0xf9, 0xf2, 0xb0, 0x9f, 0xfa, 0xef, 0xf4, 0x68, 0x87, 0x02, 0x0d, 0xd5, 0xb5, 0x19,
+	0x18, 0xe0, 0x5e, 0x96, 0x4a, 0x8b, 0x73, 0x33, 0x2b, 0xf6, 0x65, 0x6a, 0xd9, 0xd1, 0xdd, 0x9b,
+	0x3a, 0x95, 0xb9, 0x19, 0x8c, 0x2c, 0x1e, 0xd8, 0x96, 0x38, 0x28, 0xb1, 0xc0, 0x78, 0xcc, 0xa4,
+	0xea, 0x0b, 0x02, 0x13, 0xfe, 0x40, 0x0a, 0x70, 0xb4, 0x62, 0xbb, 0x6b, 0xb6, 0xab, 0xdb, 0x8e,
+	0x59, 0x33, 0x2d, 0xc3, 0xa3, 0x55, 0xde, 0xe2, 0x7b, 0x4b, 0x47, 0xc4, 0x8b, 0xe5, 0x50, 0x1e,
+	0x12, 0x71, 0xc7, 0x2b, 0x36, 0x0f, 0x13, 0x21, 0x4a, 0xbb, 0x0f, 0x89, 0xe2, 0x16, 0x0d, 0xa2,
+	0x74, 0x12, 0x9d, 0x11, 0x5d, 0x41, 0xa2, 0x4b, 0x8d, 0x33, 0x67, 0x74, 0xae, 0xd4, 0xcd, 0x35,
+	0xd3, 0x0b, 0xe6, 0x0a, 0x7f, 0x68, 0xcc, 0xa0, 0xae, 0xe8, 0x61, 0xe2, 0x6d, 0xe4, 0x8c, 0xfb,
+	0x09, 0x47, 0xd2, 0x81, 0xc8, 0x99, 0x36, 0x18, 0x4d, 0xcf, 0x47, 0x47, 0x53, 0xc4, 0xae, 0x14,
+	0x53, 0xd6, 0x4a, 0x70, 0x02, 0x5b, 0xa0, 0x4e, 0x6b, 0x8c, 0xf9, 0x3a, 0xdd, 0x66, 0x87, 0x94,
+	0x3b, 0x62, 0x28, 0xdb, 0x0e, 0xce, 0x4b, 0x3f, 0xeb, 0xcd, 0x40, 0xa6, 0xc7, 0x87, 0xd5, 0x91,
+	0xcd, 0x84, 0xb2, 0xf6, 0xa1, 0x82, 0x1b, 0x7c, 0x73, 0xa7, 0xb1, 0xa1, 0xe6, 0xad, 0x26, 0xdc,
+	0x02, 0x13, 0x05, 0xd1, 0x67, 0xa1, 0xcf, 0x76, 0xfc, 0x25, 0x9b, 0xad, 0x15, 0x51, 0x00, 0xb1,
+	0x88, 0xf4, 0x46, 0xdf, 0x05, 0x0c, 0xaf, 0xe2, 0xae, 0x12, 0x47, 0x58, 0x6a, 0xf8, 0x6c, 0x15,
+	0x54, 0xfb, 0x44, 0x81, 0xb1, 0xa6, 0x2e, 0x42, 0xfe, 0x4e, 0x1a, 0x67, 0x27, 0xb9, 0xdc, 0xc5,
+	0x3d, 0x3c, 0x0e, 0xb2, 0x9c, 0xd6, 0xcc, 0x74, 0xae, 0x64, 0x3b, 0x7f, 0x04, 0xd3, 0xed, 0x39,
+	0xdf, 0x59, 0xba, 0x89, 0x66, 0xee, 0x4a, 0x35, 0xf3, 0x2b, 0x78, 0x2e, 0xc3, 0x83, 0xc5, 0x9b,
+	0xec, 0x8b, 0x4d, 0x44, 0x6f, 0x95, 0x8c, 0xc1, 0x21, 0x97, 0x3d, 0xd0, 0x64, 0x8c, 0x83, 0x42,
+	0x1a, 0xd8, 0xff, 0xa6, 0x60, 0x4f, 0x27, 0x1d, 0x84, 0xbc, 0xb7, 0xa0, 0x8f, 0x25, 0x62, 0xb9,
+	0xf7, 0xa9, 0xe3, 0xea, 0xa6, 0xa5, 0xc7, 0x8f, 0x0a, 0x39, 0xe9, 0x9e, 0x87, 0xfa, 0x2b, 0x5b,
+	0x25, 0x12, 0xda, 0x5e, 0xb3, 0xf0, 0xdc, 0x41, 0x96, 0xa1, 0x77, 0xc3, 0x12, 0x6e, 0xaa, 0x7a,
+	0xf8, 0x9e, 0x25, 0xd7, 0x96, 0xc3, 0xd0, 0x34, 0x10, 0xba, 0x73, 0x4f, 0x73, 0xd0, 0xcd, 0x93,
+	0x20, 0x26, 0xec, 0x11, 0x05, 0x21, 0x89, 0xf9, 0x49, 0xd7, 0x9a, 0x6a, 0x3e, 0xf3, 0xbd, 0xc8,
+	0x5b, 0xcb, 0x7d, 0xf4, 0xf4, 0xdf, 0xcf, 0xbb, 0x06, 0xc8, 0xb1, 0x62, 0xa3, 0xfa, 0x2d, 0x53,
+	0xcf, 0x28, 0x8a, 0x1a, 0x93, 0x7c, 0xac, 0xc0, 0xc1, 0x58, 0x09, 0x49, 0xc6, 0x52, 0x2e, 0x65,
+	0xf5, 0xa7, 0x7a, 0xaa, 0x95, 0x1a, 0x02, 0x9c, 0xe2, 0x00, 0x23, 0x24, 0x97, 0x04, 0x10, 0x67,
+	0xf5, 0x62, 0x45, 0x58, 0x91, 0x47, 0x70, 0x30, 0x16, 0x40, 0xc2, 0x21, 0x2b, 0x50, 0x25, 0x1c,
+	0xd2, 0xa2, 0x34, 0xbb, 0x21, 0x04, 0x07, 0x6f, 0x88, 0x58, 0x99, 0x95, 0x09, 0x10, 0x2f, 0x52,
+	0x33, 0x01, 0x12, 0xe5, 0x67, 0xeb, 0x86, 0xc0, 0xb0, 0xdf, 0x2a, 0xd0, 0x2f, 0xad, 0x17, 0xc9,
+	0x54, 0xf3, 0x48, 0x89, 0x92, 0x54, 0x9d, 0x6e, 0x57, 0x1d, 0x01, 0xcf, 0x70, 0x40, 0x8d, 0x8c,
+	0x24, 0x01, 0x83, 0x13, 0x47, 0xf1, 0x01, 0xdf, 0x88, 0x1e, 0x92, 0xaf, 0x14, 0x20, 0xe9, 0x82,
+	0x92, 0x4c, 0xa4, 0x02, 0x66, 0xd6, 0xa5, 0x6a, 0xa1, 0x2d, 0x5d, 0x24, 0x3b, 0xcd, 0xc9, 0x46,
+	0x49, 0x3e, 0xa3, 0xe9, 0x9c, 0x80, 0xe0, 0x67, 0x05, 0x72, 0xcd, 0x0b, 0x4a, 0x72, 0x4e, 0x1a,
+	0xb8, 0x65, 0x25, 0xab, 0x9e, 0xef, 0xd8, 0x0e, 0xe1, 0x4f, 0x70, 0xf8, 0x61, 0x32, 0x94, 0x01,
+	0x5f, 0x67, 0x6e, 0xc8, 0x2f, 0x6c, 0x01, 0x6b, 0x5a, 0xfe, 0x91, 0xb3, 0xcd, 0xe2, 0x67, 0x56,
+	0x9d, 0xea, 0xb9, 0x4e, 0xcd, 0x5a, 0x35, 0x39, 0x5f, 0xb6, 0x8a, 0x0f, 0x70, 0x39, 0x7e, 0x48,
+	0x7e, 0x52, 0x40, 0xcd, 0xae, 0x09, 0xc9, 0x5c, 0xb3, 0xf8, 0xf2, 0x22, 0x54, 0x9d, 0xef, 0xc8,
+	0xa6, 0x15, 0x70, 0xdd, 0x37, 0x88, 0x00, 0xff, 0xc0, 0xe6, 0xd7, 0x6d, 0xcb, 0x35, 0x6b, 0x16,
+	0xad, 0xe2, 0x5a, 0xfe, 0x6c, 0x4d, 0x9c, 0x1e, 0xf6, 0x99, 0x45, 0xaa, 0x36, 0xc3, 0x29, 0x27,
+	0xc8, 0x19, 0x79, 0xb3, 0x6e, 0x20, 0x57, 0x04, 0xf7, 0x47, 0x05, 0x06, 0x02, 0xdc, 0x46, 0xe5,
+	0xf9, 0x0c, 0xad, 0x5b, 0xc8, 0xc4, 0x4d, 0x17, 0xb6, 0xd9, 0xbc, 0xa2, 0x55, 0x25, 0xbc, 0xdf,
+	0x2b, 0xd0, 0x27, 0xab, 0x29, 0xc8, 0xa4, 0x94, 0x35, 0xa3, 0x70, 0x51, 0xa7, 0xda, 0xd4, 0x46,
+	0xce, 0x79, 0xce, 0x39, 0x45, 0x0a, 0x49, 0x4e, 0x9b, 0x15, 0x96, 0x75, 0x5a, 0xe4, 0x25, 0x0b,
+	0x5f, 0xbd, 0x22, 0xa8, 0x2e, 0xec, 0x0b, 0xaf, 0x75, 0xc8, 0x48, 0x2a, 0x60, 0xe2, 0xf2, 0x48,
+	0x1d, 0x6d, 0xa2, 0x81, 0x18, 0xa3, 0x1c, 0x63, 0x88, 0x0c, 0x4a, 0xbb, 0xd7, 0xbf, 0x5b, 0x22,
+	0x5f, 0x28, 0x70, 0x34, 0x35, 0x3e, 0xc8, 0x78, 0x3b, 0x63, 0xa8, 0xf3, 0xe1, 0x96, 0xb9, 0xa4,
+	0x8b, 0xe1, 0x66, 0xa3, 0xa1, 0xb7, 0x45, 0xbe, 0x61, 0x4b, 0x7a, 0x7a, 0x1c, 0x90, 0x89, 0xb6,
+	0x06, 0xcb, 0x0e, 0x06, 0x56, 0x81, 0x93, 0x8d, 0x91, 0x13, 0xcd, 0xc9, 0xf8, 0x30, 0x23, 0x5f,
+	0x2b, 0xd0, 0x2b, 0xb9, 0xc1, 0x20, 0x05, 0x79, 0x8f, 0x48, 0xef, 0x52, 0xd4, 0xc9, 0xf6, 0x94,
+	0x91, 0x6f, 0x8c, 0xf3, 0xe5, 0xc9, 0x70, 0xc6, 0xfa, 0x87, 0x3b, 0xa1, 0x7f, 0x6a, 0x88, 0x5d,
+	0x53, 0x48, 0x4e, 0x0d, 0xb2, 0x4b, 0x12, 0xc9, 0xa9, 0x41, 0x7a, 0xdb, 0x91, 0x7d, 0x6a, 0x10,
+	0x1c, 0xc1, 0xd6, 0xcc, 0x41, 0x62, 0x77, 0x0c, 0x12, 0x10, 0xd9, 0xc5, 0x87, 0x04, 0x44, 0x7a,
+	0x55, 0x91, 0x0d, 0x22, 0x56, 0x82, 0x10, 0xe4, 0x4b, 0x05, 0x0e, 0x44, 0x6b, 0x7b, 0x72, 0x32,
+	0x15, 0x40, 0x72, 0x59, 0xa0, 0x8e, 0xb5, 0xd0, 0x42, 0x8a, 0x0b, 0x9c, 0x62, 0x8e, 0xcc, 0xa4,
+	0xcf, 0x28, 0x89, 0x72, 0xbc, 0xc8, 0x2b, 0x75, 0xdd, 0xb3, 0x75, 0x71, 0x89, 0xe0, 0x73, 0x45,
+	0x2b, 0x7c, 0x09, 0x97, 0xe4, 0xca, 0x40, 0xc2, 0x25, 0xbb, 0x26, 0xe8, 0x84, 0x8b, 0xe3, 0xf8,
+	0x5c, 0xe2, 0x2a, 0xe1, 0x53, 0x05, 0x0e, 0x5f, 0xa5, 0x5e, 0xb4, 0xa8, 0x97, 0xa0, 0x49, 0xee,
+	0x0e, 0x24, 0x68, 0xb2, 0x9b, 0x01, 0x6d, 0x82, 0xa3, 0x9d, 0x24, 0x5a, 0x12, 0x8d, 0xff, 0x3f,
+	0xa7, 0x47, 0x2f, 0x02, 0xc8, 0xaf, 0x0a, 0x0c, 0x32, 0x98, 0x48, 0x19, 0x18, 0xa9, 0xd8, 0x49,
+	0x51, 0xd2, 0x16, 0xcd, 0x6a, 0x7b, 0xc9, 0x99, 0xa9, 0xbd, 0xcb, 0x80, 0xec, 0xe6, 0x14, 0xcc,
+	0x55, 0xf4, 0xa2, 0xbf, 0xcf, 0xdc, 0xe8, 0xe5, 0x6d, 0x3d, 0xac, 0x38, 0xc9, 0x77, 0x6c, 0xa9,
+	0x48, 0x66, 0xe0, 0x17, 0x92, 0xe3, 0x2d, 0x50, 0x1a, 0x15, 0xbd, 0x3a, 0xdb, 0xb6, 0x6a, 0xc8,
+	0x3b, 0xc7, 0x79, 0x27, 0xc9, 0x44, 0x9b, 0xbc, 0xac, 0xf4, 0x25, 0xbf, 0x2b, 0x70, 0x3c, 0x49,
+	0x1a, 0xad, 0xb8, 0x25, 0x9b, 0x7b, 0xcb, 0xf2, 0x5c, 0x7d, 0xa9, 0x73, 0x9b, 0x30, 0x89, 0x8b,
+	0x3c, 0x89, 0xb3, 0x64, 0xbe, 0xcd, 0x24, 0xa2, 0x17, 0x09, 0x7e, 0x49, 0xe0, 0xb7, 0x7b, 0xaa,
+	0x80, 0x4f, 0x6f, 0x9a, 0x49, 0x15, 0x75, 0xbc, 0xa5, 0x4a, 0x88, 0x38, 0xcb, 0x11, 0x0b, 0x64,
+	0x5c, 0x8e, 0xb8, 0x2e, 0xec, 0x74, 0xff, 0x72, 0x80, 0xcf, 0x30, 0x6f, 0x75, 0xe1, 0xde, 0xe3,
+	0xbf, 0x73, 0xca, 0x13, 0xf6, 0xf9, 0x8b, 0x7d, 0x3e, 0xfb, 0x27, 0xb7, 0xeb, 0x09, 0xfb, 0xfc,
+	0xc1, 0x3e, 0xef, 0x2e, 0xd4, 0x4c, 0x6f, 0x75, 0xa3, 0x3c, 0x5d, 0xb1, 0xd7, 0x8a, 0x46, 0xdd,
+	0x5b, 0xa5, 0xc6, 0x94, 0xc5, 0xeb, 0x30, 0x7f, 0xc6, 0x4e, 0x61, 0x80, 0xa9, 0xb2, 0x63, 0x56,
+	0x6b, 0xb4, 0xb8, 0x66, 0x57, 0x37, 0xd8, 0x39, 0x62, 0x2b, 0x0c, 0xcc, 0xff, 0x9f, 0x2e, 0xef,
+	0xe1, 0x7f, 0x04, 0xcf, 0xff, 0x07, 0x9f, 0xea, 0xfc, 0x9e, 0xf8, 0x1e, 0x00, 0x00,
 }
 
 // Reference imports to suppress errors if they are not otherwise used.
@@ -2309,6 +2320,10 @@ type QueryClient interface {
 	LastPendingValsetRequestByAddr(ctx context.Context, in *QueryLastPendingValsetRequestByAddrRequest, opts ...grpc.CallOption) (*QueryLastPendingValsetRequestByAddrResponse, error)
 	LastPendingBatchRequestByAddr(ctx context.Context, in *QueryLastPendingBatchRequestByAddrRequest, opts ...grpc.CallOption) (*QueryLastPendingBatchRequestByAddrResponse, error)
 	LastPendingLogicCallByAddr(ctx context.Context, in *QueryLastPendingLogicCallByAddrRequest, opts ...grpc.CallOption) (*QueryLastPendingLogicCallByAddrResponse, error)
+	// UnsignedBatchesByAddr returns every outgoing batch the given orchestrator has not confirmed
+	UnsignedBatchesByAddr(ctx context.Context, in *QueryLastPendingBatchRequestByAddrRequest, opts ...grpc.CallOption) (*QueryOutgoingTxBatchesResponse, error)
+	// UnsignedLogicCallsByAddr returns every outgoing logic call the given orchestrator has not confirmed
+	UnsignedLogicCallsByAddr(ctx context.Context, in *QueryLastPendingLogicCallByAddrRequest, opts ...grpc.CallOption) (*QueryOutgoingLogicCallsResponse, error)
 	LastEventNonceByAddr(ctx context.Context, in *QueryLastEventNonceByAddrRequest, opts ...grpc.CallOption) (*QueryLastEventNonceByAddrResponse, error)
 	BatchFees(ctx context.Context, in *QueryBatchFeeRequest, opts ...grpc.CallOption) (*QueryBatchFeeResponse, error)
 	OutgoingTxBatches(ctx context.Context, in *QueryOutgoingTxBatchesRequest, opts ...grpc.CallOption) (*QueryOutgoingTxBatchesResponse, error)
@@ -2414,6 +2429,24 @@ func (c *queryClient) LastPendingLogicCallByAddr(ctx context.Context, in *QueryL
 	return out, nil
 }
 
+func (c *queryClient) UnsignedBatchesByAddr(ctx context.Context, in *QueryLastPendingBatchRequestByAddrRequest, opts ...grpc.CallOption) (*QueryOutgoingTxBatchesResponse, error) {
+	out := new(QueryOutgoingTxBatchesResponse)
+	err := c.cc.Invoke(ctx, "/gravity.v1.Query/UnsignedBatchesByAddr", in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
+func (c *queryClient) UnsignedLogicCallsByAddr(ctx context.Context, in *QueryLastPendingLogicCallByAddrRequest, opts ...grpc.CallOption) (*QueryOutgoingLogicCallsResponse, error) {
+	out := new(QueryOutgoingLogicCallsResponse)
+	err := c.cc.Invoke(ctx, "/gravity.v1.Query/UnsignedLogicCallsByAddr", in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
 func (c *queryClient) LastEventNonceByAddr(ctx context.Context, in *QueryLastEventNonceByAddrRequest, opts ...grpc.CallOption) (*QueryLastEventNonceByAddrResponse, error) {
 	out := new(QueryLastEventNonceByAddrResponse)
 	err := c.cc.Invoke(ctx, "/gravity.v1.Query/LastEventNonceByAddr", in, out, opts...)
@@ -2552,6 +2585,10 @@ type QueryServer interface {
 	LastPendingValsetRequestByAddr(context.Context, *QueryLastPendingValsetRequestByAddrRequest) (*QueryLastPendingValsetRequestByAddrResponse, error)
 	LastPendingBatchRequestByAddr(context.Context, *QueryLastPendingBatchRequestByAddrRequest) (*QueryLastPendingBatchRequestByAddrResponse, error)
 	LastPendingLogicCallByAddr(context.Context, *QueryLastPendingLogicCallByAddrRequest) (*QueryLastPendingLogicCallByAddrResponse, error)
+	// UnsignedBatchesByAddr returns every outgoing batch the given orchestrator has not confirmed
+	UnsignedBatchesByAddr(context.Context, *QueryLastPendingBatchRequestByAddrRequest) (*QueryOutgoingTxBatchesResponse, error)
+	// UnsignedLogicCallsByAddr returns every outgoing logic call the given orchestrator has not confirmed
+	UnsignedLogicCallsByAddr(context.Context, *QueryLastPendingLogicCallByAddrRequest) (*QueryOutgoingLogicCallsResponse, error)
 	LastEventNonceByAddr(context.Context, *QueryLastEventNonceByAddrRequest) (*QueryLastEventNonceByAddrResponse, error)
 	BatchFees(context.Context, *QueryBatchFeeRequest) (*QueryBatchFeeResponse, error)
 	OutgoingTxBatches(context.Context, *QueryOutgoingTxBatchesRequest) (*QueryOutgoingTxBatchesResponse, error)
@@ -2599,6 +2636,12 @@ func (*UnimplementedQueryServer) LastPendingBatchRequestByAddr(ctx context.Conte
 func (*UnimplementedQueryServer) LastPendingLogicCallByAddr(ctx context.Context, req *QueryLastPendingLogicCallByAddrRequest) (*QueryLastPendingLogicCallByAddrResponse, error) {
 	return nil, status.Errorf(codes.Unimplemented, "method LastPendingLogicCallByAddr not implemented")
 }
+func (*UnimplementedQueryServer) UnsignedBatchesByAddr(ctx context.Context, req *QueryLastPendingBatchRequestByAddrRequest) (*QueryOutgoingTxBatchesResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method UnsignedBatchesByAddr not implemented")
+}
+func (*UnimplementedQueryServer) UnsignedLogicCallsByAddr(ctx context.Context, req *QueryLastPendingLogicCallByAddrRequest) (*QueryOutgoingLogicCallsResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method UnsignedLogicCallsByAddr not implemented")
+}
 func (*UnimplementedQueryServer) LastEventNonceByAddr(ctx context.Context, req *QueryLastEventNonceByAddrRequest) (*QueryLastEventNonceByAddrResponse, error) {
 	return nil, status.Errorf(codes.Unimplemented, "method LastEventNonceByAddr not implemented")
 }
@@ -2808,6 +2851,42 @@ func _Query_LastPendingLogicCallByAddr_Handler(srv interface{}, ctx context.Cont
 	return interceptor(ctx, in, info, handler)
 }
 
+func _Query_UnsignedBatchesByAddr_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(QueryLastPendingBatchRequestByAddrRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(QueryServer).UnsignedBatchesByAddr(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: "/gravity.v1.Query/UnsignedBatchesByAddr",
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(QueryServer).UnsignedBatchesByAddr(ctx, req.(*QueryLastPendingBatchRequestByAddrRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
+func _Query_UnsignedLogicCallsByAddr_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(QueryLastPendingLogicCallByAddrRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(QueryServer).UnsignedLogicCallsByAddr(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: "/gravity.v1.Query/UnsignedLogicCallsByAddr",
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(QueryServer).UnsignedLogicCallsByAddr(ctx, req.(*QueryLastPendingLogicCallByAddrRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
 func _Query_LastEventNonceByAddr_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
 	in := new(QueryLastEventNonceByAddrRequest)
 	if err := dec(in); err != nil {
@@ -3100,6 +3179,14 @@ var _Query_serviceDesc = grpc.ServiceDesc{
 			MethodName: "LastPendingLogicCallByAddr",
 			Handler:    _Query_LastPendingLogicCallByAddr_Handler,
 		},
+		{
+			MethodName: "UnsignedBatchesByAddr",
+			Handler:    _Query_UnsignedBatchesByAddr_Handler,
+		},
+		{
+			MethodName: "UnsignedLogicCallsByAddr",
+			Handler:    _Query_UnsignedLogicCallsByAddr_Handler,
+		},
 		{
 			MethodName: "LastEventNonceByAddr",
 			Handler:    _Query_LastEventNonceByAddr_Handler,
@@ -4342,6 +4429,11 @@ func (m *QueryAttestationsRequest) MarshalToSizedBuffer(dAtA []byte) (int, error
 	_ = i
 	var l int
 	_ = l
+	if m.Nonce != 0 {
+		i = encodeVarintQuery(dAtA, i, uint64(m.Nonce))
+		i--
+		dAtA[i] = 0x10
+	}
 	if m.Limit != 0 {
 		i = encodeVarintQuery(dAtA, i, uint64(m.Limit))
 		i--
@@ -5162,6 +5254,9 @@ func (m *QueryAttestationsRequest) Size() (n int) {
 	if m.Limit != 0 {
 		n += 1 + sovQuery(uint64(m.Limit))
 	}
+	if m.Nonce != 0 {
+		n += 1 + sovQuery(uint64(m.Nonce))
+	}
 	return n
 }
 
@@ -8346,6 +8441,25 @@ func (m *QueryAttestationsRequest) Unmarshal(dAtA []byte) error {
 					break
 				}
 			}
+		case 2:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Nonce", wireType)
+			}
+			m.Nonce = 0
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowQuery
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				m.Nonce |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
 		default:
 			iNdEx = preIndex
 			skippy, err := skipQuery(dAtA[iNdEx:])
@@ -0,0 +1,305 @@
+//! Attestations are the Gravity module's record of validator votes on Ethereum events, this
+//! decodes their claims into typed values and matches the votes against the bonded validators
+
+use crate::query::get_attestations;
+use clarity::utils::bytes_to_hex_str;
+use clarity::Uint256;
+use gravity_proto::cosmos_sdk_proto::cosmos::base::query::v1beta1::PageRequest;
+use gravity_proto::cosmos_sdk_proto::cosmos::staking::v1beta1::query_client::QueryClient as StakingQueryClient;
+use gravity_proto::cosmos_sdk_proto::cosmos::staking::v1beta1::QueryValidatorsRequest;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_proto::gravity::{
+    Attestation, MsgBatchSendToEthClaim, MsgErc20DeployedClaim, MsgLogicCallExecutedClaim,
+    MsgSendToCosmosClaim, MsgValsetUpdatedClaim,
+};
+use gravity_utils::error::GravityError;
+use prost::Message;
+use std::convert::TryFrom;
+use tonic::transport::Channel;
+
+const BOND_STATUS_BONDED: &str = "BOND_STATUS_BONDED";
+/// Tokens per unit of consensus voting power, the Cosmos SDK default
+const POWER_REDUCTION: u64 = 1_000_000;
+
+/// The Ethereum event an attestation is voting on
+#[derive(Debug, Clone, PartialEq)]
+pub enum Claim {
+    SendToCosmos(MsgSendToCosmosClaim),
+    BatchSendToEth(MsgBatchSendToEthClaim),
+    Erc20Deployed(MsgErc20DeployedClaim),
+    LogicCallExecuted(MsgLogicCallExecutedClaim),
+    ValsetUpdated(MsgValsetUpdatedClaim),
+}
+
+impl Claim {
+    pub fn decode(any: &prost_types::Any) -> Result<Claim, GravityError> {
+        let bytes = any.value.as_slice();
+        Ok(match any.type_url.as_str() {
+            "/gravity.v1.MsgSendToCosmosClaim" => Claim::SendToCosmos(decode_claim(bytes)?),
+            "/gravity.v1.MsgBatchSendToEthClaim" => Claim::BatchSendToEth(decode_claim(bytes)?),
+            "/gravity.v1.MsgERC20DeployedClaim" => Claim::Erc20Deployed(decode_claim(bytes)?),
+            "/gravity.v1.MsgLogicCallExecutedClaim" => {
+                Claim::LogicCallExecuted(decode_claim(bytes)?)
+            }
+            "/gravity.v1.MsgValsetUpdatedClaim" => Claim::ValsetUpdated(decode_claim(bytes)?),
+            other => {
+                return Err(GravityError::InvalidBridgeStateError(format!(
+                    "Unknown claim type {}",
+                    other
+                )))
+            }
+        })
+    }
+
+    pub fn event_nonce(&self) -> u64 {
+        match self {
+            Claim::SendToCosmos(c) => c.event_nonce,
+            Claim::BatchSendToEth(c) => c.event_nonce,
+            Claim::Erc20Deployed(c) => c.event_nonce,
+            Claim::LogicCallExecuted(c) => c.event_nonce,
+            Claim::ValsetUpdated(c) => c.event_nonce,
+        }
+    }
+
+    /// The Ethereum block the event happened in
+    pub fn block_height(&self) -> u64 {
+        match self {
+            Claim::SendToCosmos(c) => c.block_height,
+            Claim::BatchSendToEth(c) => c.block_height,
+            Claim::Erc20Deployed(c) => c.block_height,
+            Claim::LogicCallExecuted(c) => c.block_height,
+            Claim::ValsetUpdated(c) => c.block_height,
+        }
+    }
+
+    pub fn claim_type(&self) -> &'static str {
+        match self {
+            Claim::SendToCosmos(_) => "SendToCosmos",
+            Claim::BatchSendToEth(_) => "BatchSendToEth",
+            Claim::Erc20Deployed(_) => "ERC20Deployed",
+            Claim::LogicCallExecuted(_) => "LogicCallExecuted",
+            Claim::ValsetUpdated(_) => "ValsetUpdated",
+        }
+    }
+
+    /// A one line summary of the event
+    pub fn describe(&self) -> String {
+        match self {
+            Claim::SendToCosmos(c) => format!(
+                "{} of {} from {} to {}",
+                c.amount, c.token_contract, c.ethereum_sender, c.cosmos_receiver
+            ),
+            Claim::BatchSendToEth(c) => {
+                format!("batch {} of {}", c.batch_nonce, c.token_contract)
+            }
+            Claim::Erc20Deployed(c) => format!(
+                "{} deployed as {} {} with {} decimals",
+                c.cosmos_denom, c.symbol, c.token_contract, c.decimals
+            ),
+            Claim::LogicCallExecuted(c) => format!(
+                "logic call 0x{} nonce {}",
+                bytes_to_hex_str(&c.invalidation_id),
+                c.invalidation_nonce
+            ),
+            Claim::ValsetUpdated(c) => {
+                format!("valset {} with {} members", c.valset_nonce, c.members.len())
+            }
+        }
+    }
+}
+
+fn decode_claim<M: Message + Default>(bytes: &[u8]) -> Result<M, GravityError> {
+    M::decode(bytes)
+        .map_err(|e| GravityError::InvalidBridgeStateError(format!("Invalid claim {:?}", e)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedAttestation {
+    pub claim: Claim,
+    /// If enough voting power has voted for the event to take effect
+    pub observed: bool,
+    /// The Cosmos block height the attestation was created at
+    pub height: u64,
+    /// Operator addresses of the validators that voted for the claim
+    pub votes: Vec<String>,
+}
+
+impl TryFrom<Attestation> for TypedAttestation {
+    type Error = GravityError;
+
+    fn try_from(input: Attestation) -> Result<Self, Self::Error> {
+        let claim = match &input.claim {
+            Some(claim) => Claim::decode(claim)?,
+            None => {
+                return Err(GravityError::InvalidBridgeStateError(
+                    "Attestation has no claim".to_string(),
+                ))
+            }
+        };
+        Ok(TypedAttestation {
+            claim,
+            observed: input.observed,
+            height: input.height,
+            votes: input.votes,
+        })
+    }
+}
+
+/// Gets the most recent attestations with their claims decoded, ordered by event nonce.
+/// Attestations that can not be decoded are skipped
+pub async fn get_typed_attestations(
+    client: &mut GravityQueryClient<Channel>,
+    limit: Option<u64>,
+) -> Result<Vec<TypedAttestation>, GravityError> {
+    let mut out = Vec::new();
+    for attestation in get_attestations(client, limit).await? {
+        match TypedAttestation::try_from(attestation) {
+            Ok(attestation) => out.push(attestation),
+            Err(e) => warn!("Skipping attestation {}", e),
+        }
+    }
+    out.sort_by_key(|a| a.claim.event_nonce());
+    Ok(out)
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorInfo {
+    pub operator_address: String,
+    pub moniker: String,
+    /// Consensus voting power
+    pub power: u64,
+}
+
+/// Gets every bonded validator along with its moniker and voting power, highest power first
+pub async fn get_bonded_validators(
+    client: &mut StakingQueryClient<Channel>,
+) -> Result<Vec<ValidatorInfo>, GravityError> {
+    let mut out = Vec::new();
+    let mut key = Vec::new();
+    loop {
+        let response = client
+            .validators(QueryValidatorsRequest {
+                status: BOND_STATUS_BONDED.to_string(),
+                pagination: Some(PageRequest {
+                    key,
+                    offset: 0,
+                    limit: 0,
+                    count_total: false,
+                }),
+            })
+            .await?
+            .into_inner();
+        for validator in response.validators {
+            let tokens: Uint256 = validator.tokens.parse()?;
+            let power = tokens / Uint256::from(POWER_REDUCTION);
+            out.push(ValidatorInfo {
+                operator_address: validator.operator_address,
+                moniker: validator.description.map(|d| d.moniker).unwrap_or_default(),
+                power: power.to_string().parse().unwrap_or(u64::MAX),
+            });
+        }
+        match response.pagination {
+            Some(page) if !page.next_key.is_empty() => key = page.next_key,
+            _ => break,
+        }
+    }
+    out.sort_by(|a, b| b.power.cmp(&a.power));
+    Ok(out)
+}
+
+/// An attestation with its votes matched against the bonded validators
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttestationVotes {
+    pub attestation: TypedAttestation,
+    pub voted: Vec<ValidatorInfo>,
+    /// Bonded validators that have not voted
+    pub not_voted: Vec<ValidatorInfo>,
+    pub voted_power: u64,
+    pub total_power: u64,
+}
+
+/// Splits the bonded validators into those that voted for the attestation and those that
+/// did not, votes from validators that are no longer bonded count with zero power
+pub fn resolve_votes(
+    attestation: TypedAttestation,
+    validators: &[ValidatorInfo],
+) -> AttestationVotes {
+    let voted: Vec<ValidatorInfo> = attestation
+        .votes
+        .iter()
+        .map(|vote| {
+            validators
+                .iter()
+                .find(|v| v.operator_address == *vote)
+                .cloned()
+                .unwrap_or_else(|| ValidatorInfo {
+                    operator_address: vote.clone(),
+                    moniker: String::new(),
+                    power: 0,
+                })
+        })
+        .collect();
+    let not_voted = validators
+        .iter()
+        .filter(|v| !attestation.votes.contains(&v.operator_address))
+        .cloned()
+        .collect();
+    AttestationVotes {
+        voted_power: voted.iter().map(|v| v.power).sum(),
+        total_power: validators.iter().map(|v| v.power).sum(),
+        attestation,
+        voted,
+        not_voted,
+    }
+}
+
+#[test]
+fn test_claim_decode_and_votes() {
+    let claim = MsgBatchSendToEthClaim {
+        event_nonce: 7,
+        block_height: 100,
+        batch_nonce: 3,
+        token_contract: "0xD50c0953a99325d01cca655E57070F1be4983b6b".to_string(),
+        orchestrator: String::new(),
+    };
+    let mut value = Vec::new();
+    claim.encode(&mut value).unwrap();
+    let attestation = Attestation {
+        observed: false,
+        votes: vec!["val1".to_string(), "gone".to_string()],
+        height: 50,
+        claim: Some(prost_types::Any {
+            type_url: "/gravity.v1.MsgBatchSendToEthClaim".to_string(),
+            value,
+        }),
+    };
+    let attestation = TypedAttestation::try_from(attestation).unwrap();
+    assert_eq!(attestation.claim, Claim::BatchSendToEth(claim));
+    assert_eq!(attestation.claim.event_nonce(), 7);
+
+    let validator = |address: &str, power| ValidatorInfo {
+        operator_address: address.to_string(),
+        moniker: address.to_uppercase(),
+        power,
+    };
+    let votes = resolve_votes(attestation, &[validator("val1", 60), validator("val2", 40)]);
+    assert_eq!(
+        votes.voted,
+        vec![
+            validator("val1", 60),
+            ValidatorInfo {
+                operator_address: "gone".to_string(),
+                moniker: String::new(),
+                power: 0,
+            }
+        ]
+    );
+    assert_eq!(votes.not_voted, vec![validator("val2", 40)]);
+    assert_eq!((votes.voted_power, votes.total_power), (60, 100));
+
+    let unknown = prost_types::Any {
+        type_url: "/gravity.v1.MsgSomethingElse".to_string(),
+        value: Vec::new(),
+    };
+    assert!(Claim::decode(&unknown).is_err());
+}
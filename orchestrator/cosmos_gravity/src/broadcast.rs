@@ -0,0 +1,433 @@
+//! The Orchestrator runs several loops that all broadcast transactions from the same Cosmos key.
+//! Cosmos accounts have a strictly incrementing sequence number, if two loops query the chain for the
+//! current sequence and broadcast in the same block one of the transactions will be rejected with a
+//! sequence mismatch. The TxBroadcaster is shared between these loops, it serializes all submissions,
+//! tracks the sequence of transactions still in the mempool locally and retries on mismatch. Optionally
+//! submissions that arrive while a transaction is being broadcast are merged into a single transaction.
+//! If the node rejects a transaction for insufficient fees the gas price is raised to the node's minimum,
+//! up to the configured ceiling, and the transaction is retried.
+
+use crate::send::{estimate_fee, simulation_fee, MEMO};
+use deep_space::error::CosmosGrpcError;
+use deep_space::private_key::PrivateKey;
+use deep_space::utils::FeeInfo;
+use deep_space::Contact;
+use deep_space::Msg;
+use deep_space::{Address, Coin};
+use gravity_proto::cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::BroadcastMode;
+use gravity_utils::types::{FeeSettings, GasPrice};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::sync::Mutex;
+
+/// The Cosmos SDK error code for ErrWrongSequence
+const SEQUENCE_MISMATCH_CODE: u32 = 32;
+/// How many times we will rebroadcast a transaction that failed due to a sequence mismatch
+/// and how many times we will raise our gas price and rebroadcast a transaction rejected for
+/// insufficient fees
+const SEQUENCE_RETRIES: usize = 3;
+
+/// Locally tracked account state, only modified while holding the broadcast lock
+#[derive(Debug, Default)]
+struct SequenceState {
+    /// The sequence to use for our next transaction, None if we should take
+    /// the value reported by the chain
+    next_sequence: Option<u64>,
+}
+
+/// A set of messages waiting to be included in the next merged transaction
+struct PendingSubmission {
+    id: u64,
+    msgs: Vec<Msg>,
+    result: oneshot::Sender<Result<TxResponse, CosmosGrpcError>>,
+}
+
+/// Serializes transaction submission for a single Cosmos key, this struct is cheap to clone
+/// and all clones share the same sequence state.
+#[derive(Clone)]
+pub struct TxBroadcaster {
+    private_key: PrivateKey,
+    fee: Arc<RwLock<FeeSettings>>,
+    merge_messages: bool,
+    state: Arc<Mutex<SequenceState>>,
+    pending: Arc<StdMutex<Vec<PendingSubmission>>>,
+    next_id: Arc<AtomicU64>,
+    fee_adjustments: Arc<AtomicU64>,
+}
+
+impl TxBroadcaster {
+    /// Creates a new broadcaster for the given key, if merge_messages is set then messages
+    /// submitted while another transaction is being broadcast are combined into one transaction
+    pub fn new(private_key: PrivateKey, fee: FeeSettings, merge_messages: bool) -> TxBroadcaster {
+        TxBroadcaster {
+            private_key,
+            fee: Arc::new(RwLock::new(fee)),
+            merge_messages,
+            state: Arc::new(Mutex::new(SequenceState::default())),
+            pending: Arc::new(StdMutex::new(Vec::new())),
+            next_id: Arc::new(AtomicU64::new(0)),
+            fee_adjustments: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The Cosmos address this broadcaster sends from
+    pub fn get_address(&self, prefix: &str) -> Address {
+        self.private_key.to_address(prefix).unwrap()
+    }
+
+    /// The private key this broadcaster signs with
+    pub fn get_private_key(&self) -> PrivateKey {
+        self.private_key
+    }
+
+    /// The fee settings used for each transaction sent by this broadcaster, including
+    /// any automatic gas price adjustment
+    pub fn get_fee(&self) -> FeeSettings {
+        self.fee.read().unwrap().clone()
+    }
+
+    /// Signs and broadcasts the provided messages, waiting for them to enter a block if a
+    /// timeout is provided. Messages passed in a single call are always kept together and
+    /// in order, even when merged with messages from other callers.
+    pub async fn send_messages(
+        &self,
+        contact: &Contact,
+        msgs: Vec<Msg>,
+        timeout: Option<Duration>,
+    ) -> Result<TxResponse, CosmosGrpcError> {
+        let response = if self.merge_messages {
+            self.send_merged(contact, msgs).await?
+        } else {
+            let mut state = self.state.lock().await;
+            self.broadcast(contact, &mut state, &msgs).await?
+        };
+
+        match timeout {
+            Some(duration) => contact.wait_for_tx(response, duration).await,
+            None => Ok(response),
+        }
+    }
+
+    /// Queues our messages and then either broadcasts everything in the queue or, if another caller
+    /// has already broadcast our messages while we waited for the lock, returns their result
+    async fn send_merged(
+        &self,
+        contact: &Contact,
+        msgs: Vec<Msg>,
+    ) -> Result<TxResponse, CosmosGrpcError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().push(PendingSubmission {
+            id,
+            msgs,
+            result: sender,
+        });
+
+        let mut state = self.state.lock().await;
+        let submissions: Option<Vec<PendingSubmission>> = {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.iter().any(|s| s.id == id) {
+                Some(pending.drain(..).collect())
+            } else {
+                None
+            }
+        };
+        let mut submissions = match submissions {
+            Some(v) => v,
+            None => {
+                // our messages where included in a transaction sent while we were waiting
+                drop(state);
+                return match receiver.await {
+                    Ok(res) => res,
+                    Err(_) => Err(CosmosGrpcError::BadInput(
+                        "Merged transaction result was lost".to_string(),
+                    )),
+                };
+            }
+        };
+
+        let mut all_msgs = Vec::new();
+        for submission in submissions.iter_mut() {
+            all_msgs.append(&mut submission.msgs);
+        }
+        if submissions.len() > 1 {
+            info!(
+                "Merging {} submissions into a single transaction with {} messages",
+                submissions.len(),
+                all_msgs.len()
+            );
+        }
+        let res = self.broadcast(contact, &mut state, &all_msgs).await;
+        drop(state);
+
+        for submission in submissions {
+            if submission.id == id {
+                continue;
+            }
+            let shared = match &res {
+                Ok(response) => Ok(response.clone()),
+                Err(e) => Err(share_error(e)),
+            };
+            // the receiver may have been dropped, that's not our concern
+            let _ = submission.result.send(shared);
+        }
+        res
+    }
+
+    /// Signs and broadcasts the messages using our locally tracked sequence, must be called
+    /// while holding the state lock
+    async fn broadcast(
+        &self,
+        contact: &Contact,
+        state: &mut SequenceState,
+        msgs: &[Msg],
+    ) -> Result<TxResponse, CosmosGrpcError> {
+        let private_key = self.private_key;
+        let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
+        let mut last_error = None;
+        for _ in 0..SEQUENCE_RETRIES {
+            let mut args = contact
+                .get_message_args(our_address, simulation_fee())
+                .await?;
+            // the chain only reports the sequence of committed transactions, anything we have
+            // sent that is still in the mempool is only tracked locally
+            if let Some(sequence) = state.next_sequence {
+                if sequence > args.sequence {
+                    args.sequence = sequence;
+                }
+            }
+            let sequence = args.sequence;
+            trace!(
+                "Broadcasting {} messages with sequence {}",
+                msgs.len(),
+                sequence
+            );
+
+            // simulation runs against the node's mempool state, so the locally
+            // tracked sequence is valid here as well
+            let tx_fee = match estimate_fee(
+                contact,
+                private_key,
+                msgs,
+                args.clone(),
+                &self.get_fee(),
+            )
+            .await
+            {
+                Ok(tx_fee) => tx_fee,
+                // simulation errors carry no ABCI code, if we overrode the sequence the chain
+                // reported it may be the cause so we retry once with the chain's sequence
+                Err(e) if state.next_sequence.is_some() => {
+                    warn!(
+                            "Simulation failed with locally tracked sequence {}, retrying with the chain sequence",
+                            sequence
+                        );
+                    state.next_sequence = None;
+                    last_error = Some(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let gas_limit = tx_fee.gas_limit;
+            args.fee = tx_fee;
+            let msg_bytes = private_key.sign_std_msg(msgs, args, MEMO)?;
+            match contact
+                .send_transaction(msg_bytes, BroadcastMode::Sync)
+                .await
+            {
+                Ok(response) if response.code == SEQUENCE_MISMATCH_CODE => {
+                    warn!(
+                        "Sequence mismatch broadcasting with sequence {}, retrying {}",
+                        sequence, response.raw_log
+                    );
+                    state.next_sequence = parse_expected_sequence(&response.raw_log);
+                    last_error = Some(CosmosGrpcError::BadInput(response.raw_log));
+                }
+                Ok(response) => {
+                    // a non zero code means the tx was rejected by CheckTx and the
+                    // sequence was not consumed
+                    state.next_sequence = if response.code == 0 {
+                        Some(sequence + 1)
+                    } else {
+                        None
+                    };
+                    return Ok(response);
+                }
+                Err(CosmosGrpcError::InsufficientFees {
+                    fee_info: FeeInfo::InsufficientFees { min_fees },
+                }) => {
+                    // rejected by CheckTx, the sequence was not consumed
+                    if !self.adjust_gas_price(&min_fees, gas_limit) {
+                        return Err(CosmosGrpcError::InsufficientFees {
+                            fee_info: FeeInfo::InsufficientFees { min_fees },
+                        });
+                    }
+                    last_error = Some(CosmosGrpcError::InsufficientFees {
+                        fee_info: FeeInfo::InsufficientFees { min_fees },
+                    });
+                }
+                Err(e) => {
+                    state.next_sequence = None;
+                    return Err(e);
+                }
+            }
+        }
+        error!("Failed to broadcast after {} retries", SEQUENCE_RETRIES);
+        Err(last_error.unwrap())
+    }
+
+    /// Raises our gas price such that a transaction with the given gas limit pays the minimum fee
+    /// reported by the node. Returns false if no adjustment is configured, the node does not accept
+    /// our fee denom, or the required gas price is above the configured ceiling
+    fn adjust_gas_price(&self, min_fees: &[Coin], gas_limit: u64) -> bool {
+        let mut fee = self.fee.write().unwrap();
+        let max_gas_price = match &fee.max_gas_price {
+            Some(v) => v.clone(),
+            None => return false,
+        };
+        if fee.fixed_fee.is_some() {
+            // a fixed fee does not depend on the gas price
+            return false;
+        }
+        let min_fee = match min_fees.iter().find(|c| c.denom == fee.gas_price.denom) {
+            Some(v) => v,
+            None => {
+                error!(
+                    "The node requires fees of {} which can not be paid in {}",
+                    Coin::display_list(min_fees),
+                    fee.gas_price.denom
+                );
+                return false;
+            }
+        };
+        let required_gas_price = GasPrice::for_fee(min_fee, gas_limit);
+        if required_gas_price.amount <= fee.gas_price.amount {
+            // the node is not asking for more than we already pay, adjusting won't help
+            return false;
+        }
+        if required_gas_price.amount > max_gas_price.amount {
+            error!(
+                "The node requires a gas price of at least {} which is above your max_gas_price of {}",
+                required_gas_price, max_gas_price
+            );
+            return false;
+        }
+
+        let count = self.fee_adjustments.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            "INSUFFICIENT FEES! Raising gas price from {} to {}, {} automatic adjustments since startup",
+            fee.gas_price, required_gas_price, count
+        );
+        warn!("Update the gas price in your config to at least this value, if the node minimum rises above your max_gas_price the Orchestrator will exit");
+        fee.gas_price = required_gas_price;
+        true
+    }
+}
+
+/// CosmosGrpcError is not Clone, this copies a merged transaction's error for every caller whose
+/// messages were part of it. Variants callers act on, such as insufficient fees, are copied exactly
+fn share_error(error: &CosmosGrpcError) -> CosmosGrpcError {
+    match error {
+        CosmosGrpcError::InsufficientFees {
+            fee_info: FeeInfo::InsufficientFees { min_fees },
+        } => CosmosGrpcError::InsufficientFees {
+            fee_info: FeeInfo::InsufficientFees {
+                min_fees: min_fees.clone(),
+            },
+        },
+        CosmosGrpcError::NodeNotSynced => CosmosGrpcError::NodeNotSynced,
+        CosmosGrpcError::ChainNotRunning => CosmosGrpcError::ChainNotRunning,
+        CosmosGrpcError::BadInput(v) => CosmosGrpcError::BadInput(v.clone()),
+        CosmosGrpcError::BadResponse(v) => CosmosGrpcError::BadResponse(v.clone()),
+        other => CosmosGrpcError::BadResponse(format!("Merged transaction failed {:?}", other)),
+    }
+}
+
+/// Parses the sequence the chain expected out of a Cosmos SDK sequence mismatch error
+/// which takes the form 'account sequence mismatch, expected 5, got 4: incorrect account sequence'
+fn parse_expected_sequence(log: &str) -> Option<u64> {
+    let start = log.find("expected ")? + "expected ".len();
+    let rest = &log[start..];
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+#[test]
+fn test_parse_expected_sequence() {
+    assert_eq!(
+        parse_expected_sequence(
+            "account sequence mismatch, expected 5, got 4: incorrect account sequence"
+        ),
+        Some(5)
+    );
+    assert_eq!(parse_expected_sequence("expected 1234"), Some(1234));
+    assert_eq!(parse_expected_sequence("insufficient fees"), None);
+}
+
+#[test]
+fn test_share_error() {
+    let min_fees = vec![Coin {
+        denom: "footoken".to_string(),
+        amount: 500u64.into(),
+    }];
+    let error = CosmosGrpcError::InsufficientFees {
+        fee_info: FeeInfo::InsufficientFees {
+            min_fees: min_fees.clone(),
+        },
+    };
+    match share_error(&error) {
+        CosmosGrpcError::InsufficientFees {
+            fee_info: FeeInfo::InsufficientFees { min_fees: shared },
+        } => assert_eq!(shared, min_fees),
+        other => panic!("Expected insufficient fees, got {:?}", other),
+    }
+    match share_error(&CosmosGrpcError::NodeNotSynced) {
+        CosmosGrpcError::NodeNotSynced => {}
+        other => panic!("Expected node not synced, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_adjust_gas_price() {
+    let fee = FeeSettings {
+        gas_price: "0.01ugraviton".parse().unwrap(),
+        gas_adjustment: 1.3,
+        max_gas_price: Some("0.05ugraviton".parse().unwrap()),
+        fixed_fee: None,
+    };
+    let broadcaster = TxBroadcaster::new(PrivateKey::from_secret(&[7u8; 32]), fee, false);
+    let min_fees = |amount: u64| {
+        vec![Coin {
+            denom: "ugraviton".to_string(),
+            amount: amount.into(),
+        }]
+    };
+    // 5000 for 200k gas is a price of 0.025, within the ceiling
+    assert!(broadcaster.adjust_gas_price(&min_fees(5000), 200_000));
+    assert_eq!(
+        broadcaster.get_fee().gas_price.to_string(),
+        "0.025ugraviton"
+    );
+    // already paying enough
+    assert!(!broadcaster.adjust_gas_price(&min_fees(4000), 200_000));
+    // above the ceiling
+    assert!(!broadcaster.adjust_gas_price(&min_fees(20_000), 200_000));
+    assert_eq!(
+        broadcaster.get_fee().gas_price.to_string(),
+        "0.025ugraviton"
+    );
+    // the node wants a denom we do not pay in
+    let other = vec![Coin {
+        denom: "footoken".to_string(),
+        amount: 1u64.into(),
+    }];
+    assert!(!broadcaster.adjust_gas_price(&other, 200_000));
+    assert_eq!(broadcaster.fee_adjustments.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,800 @@
+//! Signing with the Cosmos app of a Ledger hardware wallet, so that validator operations such
+//! as setting delegate keys never handle the validator key in software. The Ledger Cosmos app
+//! only signs legacy Amino JSON sign docs, so transactions signed here use the
+//! SIGN_MODE_LEGACY_AMINO_JSON sign mode. The device is reached over USB HID when built with
+//! the `ledger` feature, or over TCP when talking to the Speculos emulator. Registering
+//! delegate keys is the only operation signed here
+
+use crate::send::{simulate_gas, simulation_fee, MEMO};
+use deep_space::address::Address;
+use deep_space::error::CosmosGrpcError;
+use deep_space::private_key::MessageArgs;
+use deep_space::{Contact, Fee, Msg};
+use gravity_proto::cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use gravity_proto::cosmos_sdk_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+use gravity_proto::cosmos_sdk_proto::cosmos::crypto::secp256k1::PubKey;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::signing::v1beta1::SignMode;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::{
+    mode_info, AuthInfo, BroadcastMode, Fee as ProtoFee, ModeInfo, SignerInfo, TxBody, TxRaw,
+};
+use gravity_proto::gravity::MsgSetOrchestratorAddress;
+use gravity_utils::hd_wallet::parse_hd_path;
+use gravity_utils::types::FeeSettings;
+use prost::Message;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The APDU class of the Ledger Cosmos app
+const CLA: u8 = 0x55;
+const INS_GET_VERSION: u8 = 0x00;
+const INS_SIGN_SECP256K1: u8 = 0x02;
+const INS_GET_ADDR_SECP256K1: u8 = 0x04;
+/// Sign payload chunk markers, sent as P1
+const PAYLOAD_INIT: u8 = 0x00;
+const PAYLOAD_ADD: u8 = 0x01;
+const PAYLOAD_LAST: u8 = 0x02;
+/// The largest payload sent in a single APDU
+const CHUNK_SIZE: usize = 250;
+
+const SW_OK: u16 = 0x9000;
+const SW_REJECTED: u16 = 0x6986;
+const SW_DATA_INVALID: u16 = 0x6984;
+const SW_APP_NOT_OPEN: u16 = 0x6e00;
+const SW_LOCKED: u16 = 0x5515;
+
+/// The USB vendor id of Ledger devices
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// USB HID packets are always 64 bytes
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+/// How long to wait for the device to answer, signing waits for the user to confirm
+const DEVICE_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    /// Failed to talk to the device
+    Transport(String),
+    /// The device returned an error status word
+    Status(u16),
+    InvalidResponse(String),
+    Unsupported(String),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LedgerError::Transport(val) => write!(f, "Ledger transport error {}", val),
+            LedgerError::Status(SW_REJECTED) => write!(f, "Rejected on the Ledger"),
+            LedgerError::Status(SW_DATA_INVALID) => {
+                write!(f, "The Ledger Cosmos app could not parse the transaction")
+            }
+            LedgerError::Status(SW_APP_NOT_OPEN) => {
+                write!(f, "Open the Cosmos app on your Ledger")
+            }
+            LedgerError::Status(SW_LOCKED) => write!(f, "Unlock your Ledger"),
+            LedgerError::Status(val) => write!(f, "Ledger returned status {:#06x}", val),
+            LedgerError::InvalidResponse(val) => write!(f, "Invalid Ledger response {}", val),
+            LedgerError::Unsupported(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+impl From<LedgerError> for CosmosGrpcError {
+    fn from(error: LedgerError) -> Self {
+        CosmosGrpcError::BadInput(error.to_string())
+    }
+}
+
+/// Sends a single APDU command to the device and returns the response, including the
+/// trailing two byte status word. Exchanges block, so they are run off the async executor
+pub trait LedgerTransport: Send {
+    fn exchange(&mut self, command: &[u8]) -> Result<Vec<u8>, LedgerError>;
+}
+
+/// Talks to the Speculos emulator over it's APDU TCP port, commands and responses are
+/// prefixed with their big endian length, the response length excludes the status word
+pub struct SpeculosTransport {
+    stream: TcpStream,
+}
+
+impl SpeculosTransport {
+    pub fn connect(address: &str) -> Result<SpeculosTransport, LedgerError> {
+        let transport_error =
+            |e: std::io::Error| LedgerError::Transport(format!("{} {:?}", address, e));
+        let stream = TcpStream::connect(address).map_err(transport_error)?;
+        stream
+            .set_read_timeout(Some(DEVICE_TIMEOUT))
+            .map_err(transport_error)?;
+        Ok(SpeculosTransport { stream })
+    }
+}
+
+impl LedgerTransport for SpeculosTransport {
+    fn exchange(&mut self, command: &[u8]) -> Result<Vec<u8>, LedgerError> {
+        let transport_error = |e: std::io::Error| LedgerError::Transport(format!("{:?}", e));
+        let mut request = (command.len() as u32).to_be_bytes().to_vec();
+        request.extend_from_slice(command);
+        self.stream.write_all(&request).map_err(transport_error)?;
+        let mut length = [0u8; 4];
+        self.stream
+            .read_exact(&mut length)
+            .map_err(transport_error)?;
+        let mut response = vec![0u8; u32::from_be_bytes(length) as usize + 2];
+        self.stream
+            .read_exact(&mut response)
+            .map_err(transport_error)?;
+        Ok(response)
+    }
+}
+
+/// Splits an APDU into the 64 byte packets of the Ledger USB HID protocol, each packet
+/// starts with the channel, tag and sequence number and the first also carries the APDU length
+#[cfg_attr(not(feature = "ledger"), allow(dead_code))]
+fn hid_frames(command: &[u8]) -> Vec<[u8; HID_PACKET_SIZE]> {
+    let mut data = (command.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(command);
+    data.chunks(HID_PACKET_SIZE - 5)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            packet[0..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+            packet[2] = HID_TAG_APDU;
+            packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            packet[5..5 + chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Reassembles a response from USB HID packets
+#[derive(Default)]
+#[cfg_attr(not(feature = "ledger"), allow(dead_code))]
+struct HidResponse {
+    length: Option<usize>,
+    data: Vec<u8>,
+    sequence: u16,
+}
+
+impl HidResponse {
+    /// Adds a packet, returns the response once it is complete
+    fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>, LedgerError> {
+        if packet.len() < 7
+            || packet[0..2] != HID_CHANNEL.to_be_bytes()
+            || packet[2] != HID_TAG_APDU
+            || packet[3..5] != self.sequence.to_be_bytes()
+        {
+            return Err(LedgerError::InvalidResponse(
+                "Unexpected USB HID packet".to_string(),
+            ));
+        }
+        let payload = match self.length {
+            None => {
+                self.length = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+                &packet[7..]
+            }
+            Some(_) => &packet[5..],
+        };
+        self.data.extend_from_slice(payload);
+        self.sequence += 1;
+        let length = self.length.unwrap();
+        if self.data.len() >= length {
+            self.data.truncate(length);
+            Ok(Some(self.data.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use super::*;
+    use hidapi::{HidApi, HidDevice};
+
+    /// Talks to a Ledger connected over USB
+    pub struct HidTransport {
+        device: HidDevice,
+    }
+
+    impl HidTransport {
+        pub fn open() -> Result<HidTransport, LedgerError> {
+            let api = HidApi::new().map_err(|e| LedgerError::Transport(format!("{:?}", e)))?;
+            // the APDU interface is the first one, or the one with the Ledger usage page
+            let info = api
+                .device_list()
+                .find(|d| {
+                    d.vendor_id() == LEDGER_VENDOR_ID
+                        && (d.usage_page() == 0xffa0 || d.interface_number() == 0)
+                })
+                .ok_or_else(|| {
+                    LedgerError::Transport("No Ledger found, is it connected?".to_string())
+                })?;
+            let device = info
+                .open_device(&api)
+                .map_err(|e| LedgerError::Transport(format!("{:?}", e)))?;
+            Ok(HidTransport { device })
+        }
+    }
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&mut self, command: &[u8]) -> Result<Vec<u8>, LedgerError> {
+            let transport_error = |e: hidapi::HidError| LedgerError::Transport(format!("{:?}", e));
+            for frame in hid_frames(command) {
+                // the first byte is the HID report id
+                let mut report = vec![0u8];
+                report.extend_from_slice(&frame);
+                self.device.write(&report).map_err(transport_error)?;
+            }
+            let mut response = HidResponse::default();
+            loop {
+                let mut packet = [0u8; HID_PACKET_SIZE];
+                // blocks until the user has confirmed or rejected on the device
+                let read = self
+                    .device
+                    .read_timeout(&mut packet, DEVICE_TIMEOUT.as_millis() as i32)
+                    .map_err(transport_error)?;
+                if read == 0 {
+                    return Err(LedgerError::Transport(
+                        "Timed out waiting for the Ledger".to_string(),
+                    ));
+                }
+                if let Some(data) = response.push(&packet[..read])? {
+                    return Ok(data);
+                }
+            }
+        }
+    }
+}
+
+/// Opens the first Ledger connected over USB
+#[cfg(feature = "ledger")]
+pub fn open_usb_transport() -> Result<Box<dyn LedgerTransport>, LedgerError> {
+    Ok(Box::new(hid::HidTransport::open()?))
+}
+
+/// Opens the first Ledger connected over USB
+#[cfg(not(feature = "ledger"))]
+pub fn open_usb_transport() -> Result<Box<dyn LedgerTransport>, LedgerError> {
+    Err(LedgerError::Unsupported(
+        "Built without USB Ledger support, rebuild with the `ledger` feature".to_string(),
+    ))
+}
+
+/// Splits the status word off a response
+fn check_status(mut response: Vec<u8>) -> Result<Vec<u8>, LedgerError> {
+    if response.len() < 2 {
+        return Err(LedgerError::InvalidResponse(
+            "Response has no status word".to_string(),
+        ));
+    }
+    let status = response.split_off(response.len() - 2);
+    match u16::from_be_bytes([status[0], status[1]]) {
+        SW_OK => Ok(response),
+        status => Err(LedgerError::Status(status)),
+    }
+}
+
+fn apdu(ins: u8, p1: u8, p2: u8, data: &[u8]) -> Vec<u8> {
+    let mut command = vec![CLA, ins, p1, p2, data.len() as u8];
+    command.extend_from_slice(data);
+    command
+}
+
+/// A key held by the Cosmos app of a Ledger, clones share the same device connection
+#[derive(Clone)]
+pub struct LedgerSigner {
+    transport: Arc<Mutex<Box<dyn LedgerTransport>>>,
+    /// The HD path serialized as the app expects it, five little endian u32s
+    path: Vec<u8>,
+}
+
+impl LedgerSigner {
+    /// Uses the key at the given HD path, the Cosmos app only signs with five level
+    /// m/44'/118'/account'/0/index paths
+    pub fn new(transport: Box<dyn LedgerTransport>, hd_path: &str) -> Result<Self, LedgerError> {
+        let path = parse_hd_path(hd_path).map_err(LedgerError::Unsupported)?;
+        if path.len() != 5 {
+            return Err(LedgerError::Unsupported(format!(
+                "Ledger HD path {} must have five levels",
+                hd_path
+            )));
+        }
+        Ok(LedgerSigner {
+            transport: Arc::new(Mutex::new(transport)),
+            path: path.iter().flat_map(|i| i.to_le_bytes().to_vec()).collect(),
+        })
+    }
+
+    fn exchange(&mut self, command: &[u8]) -> Result<Vec<u8>, LedgerError> {
+        let mut transport = self
+            .transport
+            .lock()
+            .map_err(|_| LedgerError::Transport("A previous Ledger exchange failed".to_string()))?;
+        check_status(transport.exchange(command)?)
+    }
+
+    /// The version of the Cosmos app as (major, minor, patch)
+    pub fn version(&mut self) -> Result<(u8, u8, u8), LedgerError> {
+        let response = self.exchange(&apdu(INS_GET_VERSION, 0, 0, &[]))?;
+        if response.len() < 4 {
+            return Err(LedgerError::InvalidResponse(
+                "Version response too short".to_string(),
+            ));
+        }
+        Ok((response[1], response[2], response[3]))
+    }
+
+    /// Gets the compressed public key and address of our key, if show is set the address
+    /// is displayed on the device for the user to compare
+    pub fn public_key(
+        &mut self,
+        prefix: &str,
+        show: bool,
+    ) -> Result<([u8; 33], Address), LedgerError> {
+        let mut data = vec![prefix.len() as u8];
+        data.extend_from_slice(prefix.as_bytes());
+        data.extend_from_slice(&self.path);
+        let response = self.exchange(&apdu(INS_GET_ADDR_SECP256K1, show as u8, 0, &data))?;
+        if response.len() <= 33 {
+            return Err(LedgerError::InvalidResponse(
+                "Address response too short".to_string(),
+            ));
+        }
+        let mut public_key = [0u8; 33];
+        public_key.copy_from_slice(&response[..33]);
+        let address = String::from_utf8(response[33..].to_vec())
+            .ok()
+            .and_then(|a| a.parse().ok())
+            .ok_or_else(|| LedgerError::InvalidResponse("Invalid address".to_string()))?;
+        Ok((public_key, address))
+    }
+
+    /// Signs an Amino JSON sign doc, the user must confirm on the device. Returns the
+    /// 64 byte r || s signature Cosmos expects
+    pub fn sign(&mut self, sign_doc: &[u8]) -> Result<[u8; 64], LedgerError> {
+        let path = self.path.clone();
+        self.exchange(&apdu(INS_SIGN_SECP256K1, PAYLOAD_INIT, 0, &path))?;
+        let chunks: Vec<&[u8]> = sign_doc.chunks(CHUNK_SIZE).collect();
+        let mut response = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let p1 = if i == chunks.len() - 1 {
+                PAYLOAD_LAST
+            } else {
+                PAYLOAD_ADD
+            };
+            response = self.exchange(&apdu(INS_SIGN_SECP256K1, p1, 0, chunk))?;
+        }
+        der_to_compact(&response)
+    }
+}
+
+/// Runs blocking device exchanges on the blocking thread pool, a HID read or the wait for
+/// the user to confirm would otherwise stall every other task on the executor
+async fn on_device<T, F>(signer: &LedgerSigner, exchange: F) -> Result<T, LedgerError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut LedgerSigner) -> Result<T, LedgerError> + Send + 'static,
+{
+    let mut device = signer.clone();
+    tokio::task::spawn_blocking(move || exchange(&mut device))
+        .await
+        .map_err(|e| LedgerError::Transport(format!("{:?}", e)))?
+}
+
+/// The Ledger returns DER signatures, Cosmos requires the compact form with a low S value
+fn der_to_compact(der: &[u8]) -> Result<[u8; 64], LedgerError> {
+    let mut signature = secp256k1::Signature::from_der(der)
+        .map_err(|e| LedgerError::InvalidResponse(format!("Invalid signature {:?}", e)))?;
+    signature.normalize_s();
+    Ok(signature.serialize_compact())
+}
+
+/// A message along with it's legacy Amino JSON form, which is what the Ledger displays and signs
+#[derive(Debug, Clone)]
+pub struct AminoMsg {
+    pub msg: Msg,
+    pub amino: Value,
+}
+
+impl AminoMsg {
+    pub fn set_orchestrator_address(msg: MsgSetOrchestratorAddress) -> AminoMsg {
+        AminoMsg {
+            amino: json!({
+                "type": "gravity/MsgSetOrchestratorAddress",
+                "value": {
+                    "validator": msg.validator,
+                    "orchestrator": msg.orchestrator,
+                    "eth_address": msg.eth_address,
+                }
+            }),
+            msg: Msg::new("/gravity.v1.MsgSetOrchestratorAddress", msg),
+        }
+    }
+}
+
+/// Builds the Amino JSON sign doc, keys are sorted and there is no whitespace as the
+/// Cosmos SDK requires
+pub fn amino_sign_doc(msgs: &[AminoMsg], args: &MessageArgs, memo: &str) -> Vec<u8> {
+    let amount: Vec<Value> = args
+        .fee
+        .amount
+        .iter()
+        .map(|c| json!({"amount": c.amount.to_string(), "denom": c.denom}))
+        .collect();
+    let mut doc = json!({
+        "account_number": args.account_number.to_string(),
+        "chain_id": args.chain_id,
+        "fee": {"amount": amount, "gas": args.fee.gas_limit.to_string()},
+        "memo": memo,
+        "msgs": msgs.iter().map(|m| m.amino.clone()).collect::<Vec<Value>>(),
+        "sequence": args.sequence.to_string(),
+    });
+    if args.timeout_height != 0 {
+        doc["timeout_height"] = json!(args.timeout_height.to_string());
+    }
+    // serde_json maps are ordered by key, so this is already sorted
+    serde_json::to_vec(&doc).unwrap()
+}
+
+/// Builds the signed transaction bytes for a single Amino JSON signer
+fn build_tx(
+    msgs: &[AminoMsg],
+    args: &MessageArgs,
+    memo: &str,
+    public_key: &[u8; 33],
+    signature: Vec<u8>,
+) -> Vec<u8> {
+    let body = TxBody {
+        messages: msgs.iter().map(|m| m.msg.clone().into()).collect(),
+        memo: memo.to_string(),
+        timeout_height: args.timeout_height,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+    };
+    let mut key_bytes = Vec::new();
+    PubKey {
+        key: public_key.to_vec(),
+    }
+    .encode(&mut key_bytes)
+    .unwrap();
+    let auth_info = AuthInfo {
+        signer_infos: vec![SignerInfo {
+            public_key: Some(prost_types::Any {
+                type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(),
+                value: key_bytes,
+            }),
+            mode_info: Some(ModeInfo {
+                sum: Some(mode_info::Sum::Single(mode_info::Single {
+                    mode: SignMode::LegacyAminoJson as i32,
+                })),
+            }),
+            sequence: args.sequence,
+        }],
+        fee: Some(ProtoFee {
+            amount: args
+                .fee
+                .amount
+                .iter()
+                .map(|c| ProtoCoin {
+                    denom: c.denom.clone(),
+                    amount: c.amount.to_string(),
+                })
+                .collect(),
+            gas_limit: args.fee.gas_limit,
+            payer: String::new(),
+            granter: String::new(),
+        }),
+    };
+    let mut tx = TxRaw {
+        body_bytes: Vec::new(),
+        auth_info_bytes: Vec::new(),
+        signatures: vec![signature],
+    };
+    body.encode(&mut tx.body_bytes).unwrap();
+    auth_info.encode(&mut tx.auth_info_bytes).unwrap();
+    let mut tx_bytes = Vec::new();
+    tx.encode(&mut tx_bytes).unwrap();
+    tx_bytes
+}
+
+/// Signs the messages on the Ledger with the account details and fee in args, returning the
+/// signed transaction bytes
+pub async fn sign_with_ledger(
+    signer: &LedgerSigner,
+    msgs: &[AminoMsg],
+    args: &MessageArgs,
+    public_key: &[u8; 33],
+) -> Result<Vec<u8>, LedgerError> {
+    let sign_doc = amino_sign_doc(msgs, args, MEMO);
+    let signature = on_device(signer, move |device| device.sign(&sign_doc)).await?;
+    Ok(build_tx(msgs, args, MEMO, public_key, signature.to_vec()))
+}
+
+/// Simulates, signs on the Ledger, and broadcasts the provided messages as a single
+/// transaction, like send_messages does with a private key
+pub async fn send_messages_with_ledger(
+    contact: &Contact,
+    signer: &LedgerSigner,
+    fee: &FeeSettings,
+    msgs: Vec<AminoMsg>,
+    timeout: Option<Duration>,
+) -> Result<TxResponse, CosmosGrpcError> {
+    let prefix = contact.get_prefix();
+    let (public_key, address) =
+        on_device(signer, move |device| device.public_key(&prefix, false)).await?;
+    let mut args = contact.get_message_args(address, simulation_fee()).await?;
+
+    // simulation does not check signatures, so the transaction is simulated unsigned
+    let simulation = build_tx(&msgs, &args, MEMO, &public_key, vec![0u8; 64]);
+    let gas_limit = fee.gas_limit(simulate_gas(contact, simulation).await?);
+    args.fee = Fee {
+        amount: vec![fee.fee_for_gas(gas_limit)],
+        gas_limit,
+        granter: None,
+        payer: None,
+    };
+
+    info!("Please confirm the transaction on your Ledger");
+    let tx_bytes = sign_with_ledger(signer, &msgs, &args, &public_key).await?;
+
+    let response = contact
+        .send_transaction(tx_bytes, BroadcastMode::Sync)
+        .await?;
+    match timeout {
+        Some(duration) => contact.wait_for_tx(response, duration).await,
+        None => Ok(response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deep_space::{Coin, PrivateKey};
+    use secp256k1::{Message as SecpMessage, PublicKey, Secp256k1, SecretKey, Signature};
+    use sha2::{Digest, Sha256};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    const SECRET: [u8; 32] = [7u8; 32];
+
+    /// A stand in for the Speculos emulator running the Cosmos app, it holds a single key
+    /// and records the last payload it signed
+    fn start_emulator(reject: bool) -> (String, Arc<Mutex<Vec<u8>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let signed = Arc::new(Mutex::new(Vec::new()));
+        let record = signed.clone();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let secp = Secp256k1::new();
+            let secret = SecretKey::from_slice(&SECRET).unwrap();
+            let mut payload = Vec::new();
+            loop {
+                let mut length = [0u8; 4];
+                if stream.read_exact(&mut length).is_err() {
+                    return;
+                }
+                let mut command = vec![0u8; u32::from_be_bytes(length) as usize];
+                stream.read_exact(&mut command).unwrap();
+                let (ins, p1, data) = (command[1], command[2], &command[5..]);
+                let (mut response, status) = match (ins, p1) {
+                    (INS_GET_VERSION, _) => (vec![0, 2, 26, 0], SW_OK),
+                    (INS_GET_ADDR_SECP256K1, _) => {
+                        let prefix = String::from_utf8(data[1..1 + data[0] as usize].to_vec());
+                        let mut response = PublicKey::from_secret_key(&secp, &secret)
+                            .serialize()
+                            .to_vec();
+                        let address = PrivateKey::from_secret(&SECRET)
+                            .to_address(&prefix.unwrap())
+                            .unwrap();
+                        response.extend_from_slice(address.to_string().as_bytes());
+                        (response, SW_OK)
+                    }
+                    (INS_SIGN_SECP256K1, PAYLOAD_INIT) => {
+                        payload.clear();
+                        (Vec::new(), SW_OK)
+                    }
+                    (INS_SIGN_SECP256K1, PAYLOAD_ADD) => {
+                        payload.extend_from_slice(data);
+                        (Vec::new(), SW_OK)
+                    }
+                    (INS_SIGN_SECP256K1, _) if reject => (Vec::new(), SW_REJECTED),
+                    (INS_SIGN_SECP256K1, _) => {
+                        payload.extend_from_slice(data);
+                        *record.lock().unwrap() = payload.clone();
+                        let hash = Sha256::digest(&payload);
+                        let message = SecpMessage::from_slice(&hash).unwrap();
+                        let signature = secp.sign(&message, &secret);
+                        (signature.serialize_der().to_vec(), SW_OK)
+                    }
+                    _ => (Vec::new(), 0x6d00),
+                };
+                let mut reply = (response.len() as u32).to_be_bytes().to_vec();
+                reply.append(&mut response);
+                reply.extend_from_slice(&status.to_be_bytes());
+                stream.write_all(&reply).unwrap();
+            }
+        });
+        (address, signed)
+    }
+
+    fn test_msg() -> AminoMsg {
+        AminoMsg::set_orchestrator_address(MsgSetOrchestratorAddress {
+            validator: "cosmosvaloper1jpz0ahls2chajf78nkqczdwwuqcu97w6j77vg6".to_string(),
+            orchestrator: "cosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4".to_string(),
+            eth_address: "0xD50c0953a99325d01cca655E57070F1be4983b6b".to_string(),
+        })
+    }
+
+    fn test_args() -> MessageArgs {
+        MessageArgs {
+            sequence: 3,
+            account_number: 12,
+            chain_id: "gravity-test".to_string(),
+            fee: Fee {
+                amount: vec![Coin {
+                    denom: "ugraviton".to_string(),
+                    amount: 5000u32.into(),
+                }],
+                gas_limit: 200_000,
+                granter: None,
+                payer: None,
+            },
+            timeout_height: 0,
+        }
+    }
+
+    #[test]
+    fn test_amino_sign_doc() {
+        let doc = amino_sign_doc(&[test_msg()], &test_args(), "memo");
+        assert_eq!(
+            String::from_utf8(doc).unwrap(),
+            concat!(
+                r#"{"account_number":"12","chain_id":"gravity-test","#,
+                r#""fee":{"amount":[{"amount":"5000","denom":"ugraviton"}],"gas":"200000"},"#,
+                r#""memo":"memo","msgs":[{"type":"gravity/MsgSetOrchestratorAddress","value":{"#,
+                r#""eth_address":"0xD50c0953a99325d01cca655E57070F1be4983b6b","#,
+                r#""orchestrator":"cosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4","#,
+                r#""validator":"cosmosvaloper1jpz0ahls2chajf78nkqczdwwuqcu97w6j77vg6"}}],"#,
+                r#""sequence":"3"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_hid_framing() {
+        let command = apdu(INS_SIGN_SECP256K1, PAYLOAD_LAST, 0, &[42u8; 200]);
+        let frames = hid_frames(&command);
+        assert_eq!(frames.len(), 4);
+        // a device response uses the same framing
+        let mut response = HidResponse::default();
+        let mut result = None;
+        for frame in frames.iter() {
+            assert!(result.is_none());
+            result = response.push(frame).unwrap();
+        }
+        assert_eq!(result.unwrap(), command);
+        assert!(HidResponse::default().push(&frames[1]).is_err());
+    }
+
+    #[test]
+    fn test_sign_with_emulator() {
+        let (address, signed) = start_emulator(false);
+        let transport = SpeculosTransport::connect(&address).unwrap();
+        let mut signer = LedgerSigner::new(Box::new(transport), "m/44'/118'/0'/0/0").unwrap();
+        assert_eq!(signer.version().unwrap(), (2, 26, 0));
+
+        let (public_key, address) = signer.public_key("cosmos", false).unwrap();
+        assert_eq!(
+            address,
+            PrivateKey::from_secret(&SECRET)
+                .to_address("cosmos")
+                .unwrap()
+        );
+
+        // long enough to need several chunks
+        let msgs = vec![test_msg(), test_msg(), test_msg()];
+        let sign_doc = amino_sign_doc(&msgs, &test_args(), MEMO);
+        assert!(sign_doc.len() > CHUNK_SIZE * 2);
+        let signature = signer.sign(&sign_doc).unwrap();
+        assert_eq!(*signed.lock().unwrap(), sign_doc);
+
+        let secp = Secp256k1::new();
+        let message = SecpMessage::from_slice(&Sha256::digest(&sign_doc)).unwrap();
+        let signature = Signature::from_compact(&signature).unwrap();
+        let public_key = PublicKey::from_slice(&public_key).unwrap();
+        assert!(secp.verify(&message, &signature, &public_key).is_ok());
+    }
+
+    #[test]
+    fn test_sign_delegate_addresses_with_emulator() {
+        let (address, signed) = start_emulator(false);
+        let transport = SpeculosTransport::connect(&address).unwrap();
+        let signer = LedgerSigner::new(Box::new(transport), "m/44'/118'/0'/0/0").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        // the same steps send_messages_with_ledger takes for set_gravity_delegate_addresses,
+        // with the exchanges run on the blocking pool
+        let (public_key, validator) = runtime
+            .block_on(on_device(&signer, |device| {
+                device.public_key("cosmos", false)
+            }))
+            .unwrap();
+        let msg = MsgSetOrchestratorAddress {
+            validator: validator.to_bech32("cosmosvaloper").unwrap().to_string(),
+            orchestrator: "cosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4".to_string(),
+            eth_address: "0xD50c0953a99325d01cca655E57070F1be4983b6b".to_string(),
+        };
+        let msgs = vec![AminoMsg::set_orchestrator_address(msg.clone())];
+        let tx_bytes = runtime
+            .block_on(sign_with_ledger(&signer, &msgs, &test_args(), &public_key))
+            .unwrap();
+        let sign_doc = amino_sign_doc(&msgs, &test_args(), MEMO);
+        assert_eq!(*signed.lock().unwrap(), sign_doc);
+
+        let tx = TxRaw::decode(tx_bytes.as_slice()).unwrap();
+        let body = TxBody::decode(tx.body_bytes.as_slice()).unwrap();
+        assert_eq!(body.messages.len(), 1);
+        assert_eq!(
+            body.messages[0].type_url,
+            "/gravity.v1.MsgSetOrchestratorAddress"
+        );
+        assert_eq!(
+            MsgSetOrchestratorAddress::decode(body.messages[0].value.as_slice()).unwrap(),
+            msg
+        );
+        let auth_info = AuthInfo::decode(tx.auth_info_bytes.as_slice()).unwrap();
+        assert_eq!(
+            auth_info.signer_infos[0].mode_info,
+            Some(ModeInfo {
+                sum: Some(mode_info::Sum::Single(mode_info::Single {
+                    mode: SignMode::LegacyAminoJson as i32,
+                })),
+            })
+        );
+
+        let secp = Secp256k1::new();
+        let message = SecpMessage::from_slice(&Sha256::digest(&sign_doc)).unwrap();
+        let signature = Signature::from_compact(&tx.signatures[0]).unwrap();
+        let public_key = PublicKey::from_slice(&public_key).unwrap();
+        assert!(secp.verify(&message, &signature, &public_key).is_ok());
+    }
+
+    #[test]
+    fn test_device_timeout() {
+        // a device that never answers, like a Ledger left waiting for confirmation
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let _stream = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(5));
+        });
+        let transport = SpeculosTransport::connect(&address).unwrap();
+        transport
+            .stream
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        let mut signer = LedgerSigner::new(Box::new(transport), "m/44'/118'/0'/0/0").unwrap();
+        assert!(matches!(signer.version(), Err(LedgerError::Transport(_))));
+    }
+
+    #[test]
+    fn test_rejected_on_device() {
+        let (address, _) = start_emulator(true);
+        let transport = SpeculosTransport::connect(&address).unwrap();
+        let mut signer = LedgerSigner::new(Box::new(transport), "m/44'/118'/0'/0/0").unwrap();
+        let sign_doc = amino_sign_doc(&[test_msg()], &test_args(), MEMO);
+        assert_eq!(
+            signer.sign(&sign_doc),
+            Err(LedgerError::Status(SW_REJECTED))
+        );
+    }
+}
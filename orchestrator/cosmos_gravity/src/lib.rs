@@ -5,7 +5,13 @@
 
 #[macro_use]
 extern crate log;
+#[macro_use]
+extern crate serde_derive;
 
+pub mod attestations;
+pub mod broadcast;
+pub mod ledger;
+pub mod offline;
 pub mod query;
 pub mod send;
 pub mod utils;
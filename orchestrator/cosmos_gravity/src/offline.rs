@@ -0,0 +1,191 @@
+//! Transactions that are generated on a machine with access to the chain, signed on an
+//! offline machine holding the key and broadcast later. Unsigned transactions are stored as
+//! JSON with the messages protobuf encoded, signing decodes every message again so that only
+//! known Gravity messages can be signed and the signer can review them first
+
+use deep_space::address::Address;
+use deep_space::error::CosmosGrpcError;
+use deep_space::private_key::{MessageArgs, PrivateKey};
+use deep_space::{Coin, Fee, Msg};
+use gravity_proto::gravity::{
+    MsgCancelSendToEth, MsgRequestBatch, MsgSendToEth, MsgSetOrchestratorAddress,
+};
+use prost::Message;
+
+/// A protobuf encoded message and its type url
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsignedMsg {
+    pub type_url: String,
+    /// The protobuf encoded message as hex
+    pub value: String,
+}
+
+impl From<Msg> for UnsignedMsg {
+    fn from(msg: Msg) -> Self {
+        let any: prost_types::Any = msg.into();
+        UnsignedMsg {
+            type_url: any.type_url,
+            value: clarity::utils::bytes_to_hex_str(&any.value),
+        }
+    }
+}
+
+impl UnsignedMsg {
+    /// Decodes the message, returning it along with a readable description. Only the
+    /// messages gbt generates are accepted
+    pub fn decode(&self) -> Result<(Msg, String), CosmosGrpcError> {
+        let bytes = clarity::utils::hex_str_to_bytes(&self.value)
+            .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid message hex {:?}", e)))?;
+        match self.type_url.as_str() {
+            "/gravity.v1.MsgSetOrchestratorAddress" => {
+                decode_as::<MsgSetOrchestratorAddress>(&self.type_url, &bytes)
+            }
+            "/gravity.v1.MsgSendToEth" => decode_as::<MsgSendToEth>(&self.type_url, &bytes),
+            "/gravity.v1.MsgCancelSendToEth" => {
+                decode_as::<MsgCancelSendToEth>(&self.type_url, &bytes)
+            }
+            "/gravity.v1.MsgRequestBatch" => decode_as::<MsgRequestBatch>(&self.type_url, &bytes),
+            _ => Err(CosmosGrpcError::BadInput(format!(
+                "Signing {} messages offline is not supported",
+                self.type_url
+            ))),
+        }
+    }
+}
+
+fn decode_as<M: Message + Default + 'static>(
+    type_url: &str,
+    bytes: &[u8],
+) -> Result<(Msg, String), CosmosGrpcError> {
+    let msg = M::decode(bytes)
+        .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid {} {:?}", type_url, e)))?;
+    let description = format!("{:?}", msg);
+    Ok((Msg::new(type_url, msg), description))
+}
+
+/// A transaction waiting to be signed by `signer`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsignedTx {
+    pub chain_id: String,
+    pub signer: String,
+    pub messages: Vec<UnsignedMsg>,
+    /// The fee amounts, eg: 5000ugraviton
+    pub fee_amount: Vec<String>,
+    pub gas_limit: u64,
+    pub memo: String,
+    pub timeout_height: u64,
+}
+
+impl UnsignedTx {
+    pub fn new(chain_id: String, signer: Address, msgs: Vec<Msg>, fee: Fee, memo: &str) -> Self {
+        UnsignedTx {
+            chain_id,
+            signer: signer.to_string(),
+            messages: msgs.into_iter().map(UnsignedMsg::from).collect(),
+            fee_amount: fee
+                .amount
+                .iter()
+                .map(|c| format!("{}{}", c.amount, c.denom))
+                .collect(),
+            gas_limit: fee.gas_limit,
+            memo: memo.to_string(),
+            timeout_height: 0,
+        }
+    }
+
+    pub fn fee(&self) -> Result<Fee, CosmosGrpcError> {
+        let mut amount = Vec::new();
+        for coin in self.fee_amount.iter() {
+            let coin: Coin = coin
+                .parse()
+                .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid fee {:?}", e)))?;
+            amount.push(coin);
+        }
+        Ok(Fee {
+            amount,
+            gas_limit: self.gas_limit,
+            granter: None,
+            payer: None,
+        })
+    }
+
+    /// Decodes every message of the transaction along with a readable description
+    pub fn decode_msgs(&self) -> Result<Vec<(Msg, String)>, CosmosGrpcError> {
+        self.messages.iter().map(|m| m.decode()).collect()
+    }
+
+    /// Signs the transaction, the account number and sequence of the signer must be provided
+    /// as there is no chain to query them from
+    pub fn sign(
+        &self,
+        private_key: PrivateKey,
+        prefix: &str,
+        account_number: u64,
+        sequence: u64,
+    ) -> Result<Vec<u8>, CosmosGrpcError> {
+        let our_address = private_key.to_address(prefix)?;
+        if our_address.to_string() != self.signer {
+            return Err(CosmosGrpcError::BadInput(format!(
+                "This transaction must be signed by {} not {}",
+                self.signer, our_address
+            )));
+        }
+        let msgs: Vec<Msg> = self.decode_msgs()?.into_iter().map(|(m, _)| m).collect();
+        let args = MessageArgs {
+            sequence,
+            account_number,
+            chain_id: self.chain_id.clone(),
+            fee: self.fee()?,
+            timeout_height: self.timeout_height,
+        };
+        Ok(private_key.sign_std_msg(&msgs, args, &self.memo)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send::{build_request_batch_msg, MEMO};
+    use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::{TxBody, TxRaw};
+
+    #[test]
+    fn test_offline_round_trip() {
+        let key = PrivateKey::from_secret(&[7u8; 32]);
+        let address = key.to_address("gravity").unwrap();
+        let msg = build_request_batch_msg(address, "ugraviton".to_string());
+        let fee = Fee {
+            amount: vec![Coin {
+                denom: "ugraviton".to_string(),
+                amount: 5000u32.into(),
+            }],
+            gas_limit: 200_000,
+            granter: None,
+            payer: None,
+        };
+        let tx = UnsignedTx::new("gravity-test".to_string(), address, vec![msg], fee, MEMO);
+
+        let json = serde_json::to_string(&tx).unwrap();
+        let tx: UnsignedTx = serde_json::from_str(&json).unwrap();
+        assert_eq!(tx.fee_amount, vec!["5000ugraviton".to_string()]);
+        let decoded = tx.decode_msgs().unwrap();
+        assert!(decoded[0].1.contains("ugraviton"));
+
+        let signed = tx.sign(key, "gravity", 3, 9).unwrap();
+        let raw = TxRaw::decode(signed.as_slice()).unwrap();
+        let body = TxBody::decode(raw.body_bytes.as_slice()).unwrap();
+        assert_eq!(body.messages[0].type_url, "/gravity.v1.MsgRequestBatch");
+        assert_eq!(raw.signatures.len(), 1);
+
+        let other = PrivateKey::from_secret(&[8u8; 32]);
+        assert!(tx.sign(other, "gravity", 3, 9).is_err());
+    }
+
+    #[test]
+    fn test_unknown_message_rejected() {
+        let msg = UnsignedMsg {
+            type_url: "/cosmos.staking.v1beta1.MsgUndelegate".to_string(),
+            value: String::new(),
+        };
+        assert!(msg.decode().is_err());
+    }
+}
@@ -1,13 +1,25 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use clarity::Address as EthAddress;
+use clarity::Uint256;
 use deep_space::address::Address;
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
 use gravity_proto::gravity::Attestation;
+use gravity_proto::gravity::MsgSendToCosmosClaim;
 use gravity_proto::gravity::Params;
 use gravity_proto::gravity::QueryAttestationsRequest;
 use gravity_proto::gravity::QueryBatchConfirmsRequest;
+use gravity_proto::gravity::QueryBatchFeeRequest;
+use gravity_proto::gravity::QueryBatchRequestByNonceRequest;
 use gravity_proto::gravity::QueryCurrentValsetRequest;
+use gravity_proto::gravity::QueryDelegateKeysByEthAddress;
+use gravity_proto::gravity::QueryDelegateKeysByOrchestratorAddress;
+use gravity_proto::gravity::QueryDelegateKeysByValidatorAddress;
+use gravity_proto::gravity::QueryDenomToErc20Request;
+use gravity_proto::gravity::QueryDenomToErc20Response;
+use gravity_proto::gravity::QueryErc20ToDenomRequest;
+use gravity_proto::gravity::QueryErc20ToDenomResponse;
 use gravity_proto::gravity::QueryLastEventNonceByAddrRequest;
 use gravity_proto::gravity::QueryLastPendingBatchRequestByAddrRequest;
 use gravity_proto::gravity::QueryLastPendingLogicCallByAddrRequest;
@@ -23,7 +35,9 @@ use gravity_proto::gravity::QueryValsetConfirmsByNonceRequest;
 use gravity_proto::gravity::QueryValsetRequestRequest;
 use gravity_utils::error::GravityError;
 use gravity_utils::types::*;
+use prost::Message;
 use tonic::transport::Channel;
+use tonic::Code;
 
 /// Gets the Gravity module parameters from the Gravity module
 pub async fn get_gravity_params(
@@ -73,6 +87,17 @@ pub async fn get_oldest_unsigned_valsets(
     address: Address,
     prefix: String,
 ) -> Result<Vec<Valset>, GravityError> {
+    let valsets = get_oldest_unsigned_valsets_with_heights(client, address, prefix).await?;
+    Ok(valsets.into_iter().map(|(valset, _)| valset).collect())
+}
+
+/// The same as get_oldest_unsigned_valsets but also returns the Cosmos block height
+/// each valset was created at, which our Valset type does not keep
+pub async fn get_oldest_unsigned_valsets_with_heights(
+    client: &mut GravityQueryClient<Channel>,
+    address: Address,
+    prefix: String,
+) -> Result<Vec<(Valset, u64)>, GravityError> {
     let request = client
         .last_pending_valset_request_by_addr(QueryLastPendingValsetRequestByAddrRequest {
             address: address.to_bech32(prefix).unwrap(),
@@ -80,7 +105,7 @@ pub async fn get_oldest_unsigned_valsets(
         .await?;
     let valsets = request.into_inner().valsets;
     // convert from proto valset type to rust valset type
-    let valsets = valsets.iter().map(|v| v.into()).collect();
+    let valsets = valsets.iter().map(|v| (v.into(), v.height)).collect();
     Ok(valsets)
 }
 
@@ -146,6 +171,37 @@ pub async fn get_latest_transaction_batches(
     Ok(out)
 }
 
+/// Gets the total fees of the unbatched transfers waiting in the pool for each token, this is
+/// the fee a relayer would earn from the next batch of that token
+pub async fn get_batch_fees(
+    client: &mut GravityQueryClient<Channel>,
+) -> Result<HashMap<EthAddress, Uint256>, GravityError> {
+    let request = client.batch_fees(QueryBatchFeeRequest {}).await?;
+    let mut out = HashMap::new();
+    for fees in request.into_inner().batch_fees {
+        out.insert(fees.token.parse()?, fees.total_fees.parse()?);
+    }
+    Ok(out)
+}
+
+/// get a single transaction batch by it's nonce and token contract
+pub async fn get_transaction_batch_by_nonce(
+    client: &mut GravityQueryClient<Channel>,
+    nonce: u64,
+    contract_address: EthAddress,
+) -> Result<Option<TransactionBatch>, GravityError> {
+    let request = client
+        .batch_request_by_nonce(QueryBatchRequestByNonceRequest {
+            nonce,
+            contract_address: contract_address.to_string(),
+        })
+        .await?;
+    match request.into_inner().batch {
+        Some(batch) => Ok(Some(TransactionBatch::try_from(batch)?)),
+        None => Ok(None),
+    }
+}
+
 /// get all batch confirmations for a given nonce and denom
 pub async fn get_transaction_batch_signatures(
     client: &mut GravityQueryClient<Channel>,
@@ -232,6 +288,66 @@ pub async fn get_oldest_unsigned_logic_call(
     }
 }
 
+/// Gets every transaction batch that the given address has not yet confirmed, oldest first.
+/// Chains that do not have the UnsignedBatchesByAddr query yet only return the oldest one
+pub async fn get_all_unsigned_transaction_batches(
+    client: &mut GravityQueryClient<Channel>,
+    address: Address,
+    prefix: String,
+) -> Result<Vec<TransactionBatch>, GravityError> {
+    let request = client
+        .unsigned_batches_by_addr(QueryLastPendingBatchRequestByAddrRequest {
+            address: address.to_bech32(prefix.clone()).unwrap(),
+        })
+        .await;
+    let batches = match request {
+        Ok(response) => response.into_inner().batches,
+        Err(e) if e.code() == Code::Unimplemented => {
+            trace!("UnsignedBatchesByAddr is not available, getting the oldest unsigned batch");
+            let batch = get_oldest_unsigned_transaction_batch(client, address, prefix).await?;
+            return Ok(batch.into_iter().collect());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut out = Vec::new();
+    for batch in batches {
+        out.push(TransactionBatch::try_from(batch)?)
+    }
+    out.sort_by_key(|b| b.nonce);
+    Ok(out)
+}
+
+/// Gets every logic call that the given address has not yet confirmed, oldest first.
+/// Chains that do not have the UnsignedLogicCallsByAddr query yet only return the oldest one
+pub async fn get_all_unsigned_logic_calls(
+    client: &mut GravityQueryClient<Channel>,
+    address: Address,
+    prefix: String,
+) -> Result<Vec<LogicCall>, GravityError> {
+    let request = client
+        .unsigned_logic_calls_by_addr(QueryLastPendingLogicCallByAddrRequest {
+            address: address.to_bech32(prefix.clone()).unwrap(),
+        })
+        .await;
+    let calls = match request {
+        Ok(response) => response.into_inner().calls,
+        Err(e) if e.code() == Code::Unimplemented => {
+            trace!(
+                "UnsignedLogicCallsByAddr is not available, getting the oldest unsigned logic call"
+            );
+            let call = get_oldest_unsigned_logic_call(client, address, prefix).await?;
+            return Ok(call.into_iter().collect());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut out = Vec::new();
+    for call in calls {
+        out.push(LogicCall::try_from(call)?)
+    }
+    out.sort_by_key(|c| c.invalidation_nonce);
+    Ok(out)
+}
+
 pub async fn get_attestations(
     client: &mut GravityQueryClient<Channel>,
     limit: Option<u64>,
@@ -239,12 +355,43 @@ pub async fn get_attestations(
     let request = client
         .get_attestations(QueryAttestationsRequest {
             limit: limit.or(Some(1000u64)).unwrap(),
+            nonce: 0,
         })
         .await?;
     let attestations = request.into_inner().attestations;
     Ok(attestations)
 }
 
+/// Finds the attestation for the deposit with the given event nonce, returns None if no
+/// validator has claimed it yet. Chains that can't query by nonce ignore it and return the
+/// oldest attestations instead, so the claims are still checked for the nonce
+pub async fn get_send_to_cosmos_attestation(
+    client: &mut GravityQueryClient<Channel>,
+    event_nonce: u64,
+) -> Result<Option<Attestation>, GravityError> {
+    let attestations = client
+        .get_attestations(QueryAttestationsRequest {
+            limit: 1000u64,
+            nonce: event_nonce,
+        })
+        .await?
+        .into_inner()
+        .attestations;
+    for attestation in attestations {
+        if let Some(claim) = &attestation.claim {
+            if claim.type_url != "/gravity.v1.MsgSendToCosmosClaim" {
+                continue;
+            }
+            if let Ok(claim) = MsgSendToCosmosClaim::decode(claim.value.as_slice()) {
+                if claim.event_nonce == event_nonce {
+                    return Ok(Some(attestation));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
 /// Get a list of transactions going to the EVM blockchain that are pending for a given user.
 pub async fn get_pending_send_to_eth(
     client: &mut GravityQueryClient<Channel>,
@@ -257,3 +404,73 @@ pub async fn get_pending_send_to_eth(
         .await?;
     Ok(request.into_inner())
 }
+
+/// Gets the delegate Orchestrator and Ethereum addresses registered by the given validator,
+/// the validator address is a valoper address
+pub async fn get_delegate_keys_by_validator(
+    client: &mut GravityQueryClient<Channel>,
+    validator_address: String,
+) -> Result<(Address, EthAddress), GravityError> {
+    let response = client
+        .get_delegate_key_by_validator(QueryDelegateKeysByValidatorAddress { validator_address })
+        .await?
+        .into_inner();
+    let orchestrator_address = response.orchestrator_address.parse()?;
+    let eth_address = response.eth_address.parse()?;
+    Ok((orchestrator_address, eth_address))
+}
+
+/// Gets the validator and Orchestrator address that registered the given delegate Ethereum address
+pub async fn get_delegate_keys_by_eth(
+    client: &mut GravityQueryClient<Channel>,
+    eth_address: EthAddress,
+) -> Result<(String, Address), GravityError> {
+    let response = client
+        .get_delegate_key_by_eth(QueryDelegateKeysByEthAddress {
+            eth_address: eth_address.to_string(),
+        })
+        .await?
+        .into_inner();
+    Ok((
+        response.validator_address,
+        response.orchestrator_address.parse()?,
+    ))
+}
+
+/// Gets the validator and delegate Ethereum address that registered the given Orchestrator address
+pub async fn get_delegate_keys_by_orchestrator(
+    client: &mut GravityQueryClient<Channel>,
+    orchestrator_address: Address,
+) -> Result<(String, EthAddress), GravityError> {
+    let response = client
+        .get_delegate_key_by_orchestrator(QueryDelegateKeysByOrchestratorAddress {
+            orchestrator_address: orchestrator_address.to_string(),
+        })
+        .await?
+        .into_inner();
+    Ok((response.validator_address, response.eth_address.parse()?))
+}
+
+/// Gets the Cosmos denom an ERC20 is represented by, and if that token originated on Cosmos
+pub async fn get_erc20_to_denom(
+    client: &mut GravityQueryClient<Channel>,
+    erc20: EthAddress,
+) -> Result<QueryErc20ToDenomResponse, GravityError> {
+    let request = client
+        .erc20_to_denom(QueryErc20ToDenomRequest {
+            erc20: erc20.to_string(),
+        })
+        .await?;
+    Ok(request.into_inner())
+}
+
+/// Gets the ERC20 a Cosmos denom is represented by, and if that token originated on Cosmos
+pub async fn get_denom_to_erc20(
+    client: &mut GravityQueryClient<Channel>,
+    denom: String,
+) -> Result<QueryDenomToErc20Response, GravityError> {
+    let request = client
+        .denom_to_erc20(QueryDenomToErc20Request { denom })
+        .await?;
+    Ok(request.into_inner())
+}
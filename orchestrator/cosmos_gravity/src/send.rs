@@ -2,7 +2,7 @@ use clarity::{constants::ZERO_ADDRESS, Address as EthAddress};
 use clarity::{PrivateKey as EthPrivateKey, Signature};
 use deep_space::address::Address;
 use deep_space::error::CosmosGrpcError;
-use deep_space::private_key::PrivateKey;
+use deep_space::private_key::{MessageArgs, PrivateKey};
 use deep_space::Contact;
 use deep_space::Fee;
 use deep_space::Msg;
@@ -12,7 +12,10 @@ use ethereum_gravity::message_signatures::{
 };
 use ethereum_gravity::utils::downcast_uint256;
 use gravity_proto::cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
-use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::BroadcastMode;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::service_client::ServiceClient as TxServiceClient;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::{
+    AuthInfo, BroadcastMode, SimulateRequest, Tx, TxBody, TxRaw,
+};
 use gravity_proto::gravity::MsgConfirmLogicCall;
 use gravity_proto::gravity::MsgErc20DeployedClaim;
 use gravity_proto::gravity::MsgLogicCallExecutedClaim;
@@ -25,35 +28,63 @@ use gravity_proto::gravity::MsgValsetUpdatedClaim;
 use gravity_proto::gravity::{MsgBatchSendToEthClaim, MsgSubmitBadSignatureEvidence};
 use gravity_proto::gravity::{MsgCancelSendToEth, MsgConfirmBatch};
 use gravity_utils::types::*;
+use lazy_static::lazy_static;
+use prost::Message;
+use std::sync::Mutex as StdMutex;
 use std::{collections::HashMap, time::Duration};
+use tonic::transport::Channel;
 
+use crate::ledger::{send_messages_with_ledger, AminoMsg, LedgerSigner};
 use crate::utils::BadSignatureEvidence;
 
 pub const MEMO: &str = "Sent using Althea Orchestrator";
 pub const TIMEOUT: Duration = Duration::from_secs(60);
+/// The gas limit used while simulating a transaction, simulation is not
+/// charged so this only needs to be large enough to never run out
+const SIMULATION_GAS_LIMIT: u64 = 500_000_000;
+
+/// The key that signs validator operations, a Ledger keeps the validator key off this machine
+pub enum ValidatorSigner {
+    PrivateKey(PrivateKey),
+    Ledger(LedgerSigner),
+}
+
+impl ValidatorSigner {
+    /// The validator's account address, for a Ledger this is read from the device
+    pub fn to_address(&mut self, prefix: &str) -> Result<Address, CosmosGrpcError> {
+        match self {
+            ValidatorSigner::PrivateKey(key) => Ok(key.to_address(prefix)?),
+            ValidatorSigner::Ledger(ledger) => Ok(ledger.public_key(prefix, false)?.1),
+        }
+    }
+}
+
+impl From<PrivateKey> for ValidatorSigner {
+    fn from(key: PrivateKey) -> Self {
+        ValidatorSigner::PrivateKey(key)
+    }
+}
 
 /// Send a transaction updating the eth address for the sending
 /// Cosmos address. The sending Cosmos address should be a validator
-/// this can only be called once! Key rotation code is possible but
-/// not currently implemented
+/// this can only be called once! The Gravity module rejects delegate
+/// keys for a validator that has already set them
 pub async fn set_gravity_delegate_addresses(
     contact: &Contact,
     delegate_eth_address: EthAddress,
     delegate_cosmos_address: Address,
-    private_key: PrivateKey,
-    fee: Coin,
+    signer: &mut ValidatorSigner,
+    fee: &FeeSettings,
 ) -> Result<TxResponse, CosmosGrpcError> {
     trace!("Updating Gravity Delegate addresses");
-    let our_valoper_address = private_key
-        .to_address(&contact.get_prefix())
-        .unwrap()
+    let our_valoper_address = signer
+        .to_address(&contact.get_prefix())?
         // This works so long as the format set by the cosmos hub is maintained
         // having a main prefix followed by a series of titles for specific keys
         // this will not work if that convention is broken. This will be resolved when
         // GRPC exposes prefix endpoints (coming to upstream cosmos sdk soon)
         .to_bech32(format!("{}valoper", contact.get_prefix()))
         .unwrap();
-    let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
 
     let msg_set_orch_address = MsgSetOrchestratorAddress {
         validator: our_valoper_address.to_string(),
@@ -61,28 +92,41 @@ pub async fn set_gravity_delegate_addresses(
         eth_address: delegate_eth_address.to_string(),
     };
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000u64,
-        granter: None,
-        payer: None,
-    };
+    match signer {
+        ValidatorSigner::PrivateKey(private_key) => {
+            let msg = Msg::new(
+                "/gravity.v1.MsgSetOrchestratorAddress",
+                msg_set_orch_address,
+            );
+            send_messages(contact, *private_key, fee, vec![msg], Some(TIMEOUT)).await
+        }
+        ValidatorSigner::Ledger(ledger) => {
+            let msg = AminoMsg::set_orchestrator_address(msg_set_orch_address);
+            send_messages_with_ledger(contact, ledger, fee, vec![msg], Some(TIMEOUT)).await
+        }
+    }
+}
 
-    let msg = Msg::new(
+/// Builds the MsgSetOrchestratorAddress sent by `set_gravity_delegate_addresses` for
+/// the validator with the given account address, for use with offline signing
+pub fn build_set_orchestrator_address_msg(
+    validator_address: Address,
+    prefix: &str,
+    delegate_eth_address: EthAddress,
+    delegate_cosmos_address: Address,
+) -> Result<Msg, CosmosGrpcError> {
+    let msg_set_orch_address = MsgSetOrchestratorAddress {
+        validator: validator_address
+            .to_bech32(format!("{}valoper", prefix))
+            .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid prefix {:?}", e)))?
+            .to_string(),
+        orchestrator: delegate_cosmos_address.to_string(),
+        eth_address: delegate_eth_address.to_string(),
+    };
+    Ok(Msg::new(
         "/gravity.v1.MsgSetOrchestratorAddress",
         msg_set_orch_address,
-    );
-
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&[msg], args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    ))
 }
 
 /// Send in a confirmation for an array of validator sets, it's far more efficient to send these
@@ -91,21 +135,27 @@ pub async fn set_gravity_delegate_addresses(
 pub async fn send_valset_confirms(
     contact: &Contact,
     eth_private_key: EthPrivateKey,
-    fee: Coin,
+    fee: &FeeSettings,
     valsets: Vec<Valset>,
     private_key: PrivateKey,
     gravity_id: String,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
-    let our_eth_address = eth_private_key.to_public_key().unwrap();
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
+    let messages = build_valset_confirm_msgs(our_address, eth_private_key, valsets, gravity_id);
+
+    send_messages(contact, private_key, fee, messages, Some(TIMEOUT)).await
+}
 
+/// Signs the provided validator sets with our Ethereum key and packages the signatures
+/// into MsgValsetConfirm messages ready to be broadcast
+pub fn build_valset_confirm_msgs(
+    our_address: Address,
+    eth_private_key: EthPrivateKey,
+    valsets: Vec<Valset>,
+    gravity_id: String,
+) -> Vec<Msg> {
+    let our_eth_address = eth_private_key.to_public_key().unwrap();
     let mut messages = Vec::new();
 
     for valset in valsets {
@@ -126,37 +176,39 @@ pub async fn send_valset_confirms(
         let msg = Msg::new("/gravity.v1.MsgValsetConfirm", confirm);
         messages.push(msg);
     }
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&messages, args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    messages
 }
 
 /// Send in a confirmation for a specific transaction batch
 pub async fn send_batch_confirm(
     contact: &Contact,
     eth_private_key: EthPrivateKey,
-    fee: Coin,
+    fee: &FeeSettings,
     transaction_batches: Vec<TransactionBatch>,
     private_key: PrivateKey,
     gravity_id: String,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
-    let our_eth_address = eth_private_key.to_public_key().unwrap();
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
+    let messages = build_batch_confirm_msgs(
+        our_address,
+        eth_private_key,
+        transaction_batches,
+        gravity_id,
+    );
+
+    send_messages(contact, private_key, fee, messages, Some(TIMEOUT)).await
+}
 
+/// Signs the provided transaction batches with our Ethereum key and packages the signatures
+/// into MsgConfirmBatch messages ready to be broadcast
+pub fn build_batch_confirm_msgs(
+    our_address: Address,
+    eth_private_key: EthPrivateKey,
+    transaction_batches: Vec<TransactionBatch>,
+    gravity_id: String,
+) -> Vec<Msg> {
+    let our_eth_address = eth_private_key.to_public_key().unwrap();
     let mut messages = Vec::new();
 
     for batch in transaction_batches {
@@ -178,37 +230,35 @@ pub async fn send_batch_confirm(
         let msg = Msg::new("/gravity.v1.MsgConfirmBatch", confirm);
         messages.push(msg);
     }
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&messages, args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    messages
 }
 
 /// Send in a confirmation for a specific logic call
 pub async fn send_logic_call_confirm(
     contact: &Contact,
     eth_private_key: EthPrivateKey,
-    fee: Coin,
+    fee: &FeeSettings,
     logic_calls: Vec<LogicCall>,
     private_key: PrivateKey,
     gravity_id: String,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
-    let our_eth_address = eth_private_key.to_public_key().unwrap();
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
+    let messages =
+        build_logic_call_confirm_msgs(our_address, eth_private_key, logic_calls, gravity_id);
+
+    send_messages(contact, private_key, fee, messages, Some(TIMEOUT)).await
+}
 
+/// Signs the provided logic calls with our Ethereum key and packages the signatures
+/// into MsgConfirmLogicCall messages ready to be broadcast
+pub fn build_logic_call_confirm_msgs(
+    our_address: Address,
+    eth_private_key: EthPrivateKey,
+    logic_calls: Vec<LogicCall>,
+    gravity_id: String,
+) -> Vec<Msg> {
+    let our_eth_address = eth_private_key.to_public_key().unwrap();
     let mut messages = Vec::new();
 
     for call in logic_calls {
@@ -230,16 +280,7 @@ pub async fn send_logic_call_confirm(
         let msg = Msg::new("/gravity.v1.MsgConfirmLogicCall", confirm);
         messages.push(msg);
     }
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&messages, args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    messages
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -251,10 +292,34 @@ pub async fn send_ethereum_claims(
     erc20_deploys: Vec<Erc20DeployedEvent>,
     logic_calls: Vec<LogicCallExecutedEvent>,
     valsets: Vec<ValsetUpdatedEvent>,
-    fee: Coin,
+    fee: &FeeSettings,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
 
+    let msgs = build_ethereum_claim_msgs(
+        our_address,
+        contact.get_prefix(),
+        deposits,
+        withdraws,
+        erc20_deploys,
+        logic_calls,
+        valsets,
+    );
+
+    send_messages(contact, private_key, fee, msgs, Some(TIMEOUT)).await
+}
+
+/// Packages the provided Ethereum events into claim messages ordered by event nonce
+/// ready to be broadcast
+pub fn build_ethereum_claim_msgs(
+    our_address: Address,
+    prefix: String,
+    deposits: Vec<SendToCosmosEvent>,
+    withdraws: Vec<TransactionBatchExecutedEvent>,
+    erc20_deploys: Vec<Erc20DeployedEvent>,
+    logic_calls: Vec<LogicCallExecutedEvent>,
+    valsets: Vec<ValsetUpdatedEvent>,
+) -> Vec<Msg> {
     // This sorts oracle messages by event nonce before submitting them. It's not a pretty implementation because
     // we're missing an intermediary layer of abstraction. We could implement 'EventTrait' and then implement sort
     // for it, but then when we go to transform 'EventTrait' objects into GravityMsg enum values we'll have all sorts
@@ -270,7 +335,7 @@ pub async fn send_ethereum_claims(
             block_height: downcast_uint256(deposit.block_height).unwrap(),
             token_contract: deposit.erc20.to_string(),
             amount: deposit.amount.to_string(),
-            cosmos_receiver: deposit.destination.to_bech32(contact.get_prefix()).unwrap(),
+            cosmos_receiver: deposit.destination.to_bech32(prefix.clone()).unwrap(),
             ethereum_sender: deposit.sender.to_string(),
             orchestrator: our_address.to_string(),
         };
@@ -339,36 +404,19 @@ pub async fn send_ethereum_claims(
     for i in keys {
         msgs.push(unordered_msgs.remove_entry(&i).unwrap().1);
     }
-
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
-
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&msgs, args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    msgs
 }
 
 /// Sends tokens from Cosmos to Ethereum. These tokens will not be sent immediately instead
 /// they will require some time to be included in a batch. Note that there are two fees
 /// one is the fee to be sent to Ethereum, which must be the same denom as the amount
-/// the other is the Cosmos chain fee, which can be any allowed coin
+/// the other is the Cosmos chain fee, which is computed from the provided fee settings
 pub async fn send_to_eth(
     private_key: PrivateKey,
     destination: EthAddress,
     amount: Coin,
     bridge_fee: Coin,
-    fee: Coin,
+    fee: &FeeSettings,
     contact: &Contact,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
@@ -378,98 +426,145 @@ pub async fn send_to_eth(
             amount.denom, bridge_fee.denom,
         )));
     }
-    let balances = contact.get_balances(our_address).await.unwrap();
-    let mut found = false;
-    for balance in balances {
-        if balance.denom == amount.denom {
-            let total_amount = amount.amount.clone() + (fee.amount.clone() * 2u8.into());
-            if balance.amount < total_amount {
-                return Err(CosmosGrpcError::BadInput(format!(
-                    "Insufficient balance of {} to send {}",
-                    amount.denom, total_amount,
-                )));
-            }
-            found = true;
-        }
+    let msgs = vec![build_send_to_eth_msg(
+        our_address,
+        destination,
+        amount.clone(),
+        bridge_fee.clone(),
+    )];
+    let mut args = contact
+        .get_message_args(our_address, simulation_fee())
+        .await?;
+    args.fee = estimate_fee(contact, private_key, &msgs, args.clone(), fee).await?;
+
+    let mut total_amount = amount.amount.clone() + bridge_fee.amount.clone();
+    // the chain fee comes out of the same balance when we pay it in the token we are sending
+    for coin in args.fee.amount.iter().filter(|c| c.denom == amount.denom) {
+        total_amount = total_amount + coin.amount.clone();
     }
-    if !found {
-        return Err(CosmosGrpcError::BadInput(format!(
-            "No balance of {} to send",
-            amount.denom,
-        )));
+    let balances = contact.get_balances(our_address).await?;
+    match balances.iter().find(|b| b.denom == amount.denom) {
+        Some(balance) if balance.amount < total_amount => {
+            return Err(CosmosGrpcError::BadInput(format!(
+                "Insufficient balance of {} to send {} including fees",
+                amount.denom, total_amount,
+            )))
+        }
+        Some(_) => {}
+        None => {
+            return Err(CosmosGrpcError::BadInput(format!(
+                "No balance of {} to send",
+                amount.denom,
+            )))
+        }
     }
 
+    let msg_bytes = private_key.sign_std_msg(&msgs, args, MEMO)?;
+    let response = contact
+        .send_transaction(msg_bytes, BroadcastMode::Sync)
+        .await?;
+    contact.wait_for_tx(response, TIMEOUT).await
+}
+
+/// Builds the MsgSendToEth sent by `send_to_eth` without checking balances
+pub fn build_send_to_eth_msg(
+    sender: Address,
+    destination: EthAddress,
+    amount: Coin,
+    bridge_fee: Coin,
+) -> Msg {
     let msg_send_to_eth = MsgSendToEth {
-        sender: our_address.to_string(),
+        sender: sender.to_string(),
         eth_dest: destination.to_string(),
         amount: Some(amount.into()),
-        bridge_fee: Some(bridge_fee.clone().into()),
-    };
-
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000u64,
-        granter: None,
-        payer: None,
+        bridge_fee: Some(bridge_fee.into()),
     };
 
-    let msg = Msg::new("/gravity.v1.MsgSendToEth", msg_send_to_eth);
-
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
+    Msg::new("/gravity.v1.MsgSendToEth", msg_send_to_eth)
+}
 
-    let msg_bytes = private_key.sign_std_msg(&[msg], args, MEMO)?;
+/// Sends many transfers to Ethereum in a single transaction, each transfer is a
+/// (destination, amount, bridge fee) tuple. Unlike `send_to_eth` balances are not
+/// checked, the caller is expected to check the total of all transfers
+pub async fn send_to_eth_bulk(
+    private_key: PrivateKey,
+    transfers: Vec<(EthAddress, Coin, Coin)>,
+    fee: &FeeSettings,
+    contact: &Contact,
+    timeout: Option<Duration>,
+) -> Result<TxResponse, CosmosGrpcError> {
+    let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
+    let msgs = build_send_to_eth_bulk_msgs(our_address, transfers)?;
+    send_messages(contact, private_key, fee, msgs, timeout).await
+}
 
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
+/// Simulates the transaction `send_to_eth_bulk` would send and returns it's gas limit
+pub async fn estimate_send_to_eth_bulk_gas(
+    private_key: PrivateKey,
+    transfers: Vec<(EthAddress, Coin, Coin)>,
+    fee: &FeeSettings,
+    contact: &Contact,
+) -> Result<u64, CosmosGrpcError> {
+    let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
+    let msgs = build_send_to_eth_bulk_msgs(our_address, transfers)?;
+    let args = contact
+        .get_message_args(our_address, simulation_fee())
         .await?;
+    Ok(estimate_fee(contact, private_key, &msgs, args, fee)
+        .await?
+        .gas_limit)
+}
 
-    contact.wait_for_tx(response, TIMEOUT).await
+fn build_send_to_eth_bulk_msgs(
+    sender: Address,
+    transfers: Vec<(EthAddress, Coin, Coin)>,
+) -> Result<Vec<Msg>, CosmosGrpcError> {
+    let mut msgs = Vec::new();
+    for (destination, amount, bridge_fee) in transfers {
+        if amount.denom != bridge_fee.denom {
+            return Err(CosmosGrpcError::BadInput(format!(
+                "{} {} is an invalid denom set for SendToEth you must pay fees in the same token your sending",
+                amount.denom, bridge_fee.denom,
+            )));
+        }
+        msgs.push(build_send_to_eth_msg(
+            sender,
+            destination,
+            amount,
+            bridge_fee,
+        ));
+    }
+    Ok(msgs)
 }
 
 pub async fn send_request_batch(
     private_key: PrivateKey,
     denom: String,
-    fee: Coin,
+    fee: &FeeSettings,
     contact: &Contact,
     timeout: Option<Duration>,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
 
+    let msg = build_request_batch_msg(our_address, denom);
+
+    send_messages(contact, private_key, fee, vec![msg], timeout).await
+}
+
+pub fn build_request_batch_msg(sender: Address, denom: String) -> Msg {
     let msg_request_batch = MsgRequestBatch {
-        sender: our_address.to_string(),
+        sender: sender.to_string(),
         denom,
     };
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
-
-    let msg = Msg::new("/gravity.v1.MsgRequestBatch", msg_request_batch);
-
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&[msg], args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    match timeout {
-        Some(duration) => contact.wait_for_tx(response, duration).await,
-        None => Ok(response),
-    }
+    Msg::new("/gravity.v1.MsgRequestBatch", msg_request_batch)
 }
 
 /// Sends evidence of a bad signature to the chain to slash the malicious validator
 /// who signed an invalid message with their Ethereum key
 pub async fn submit_bad_signature_evidence(
     private_key: PrivateKey,
-    fee: Coin,
+    fee: &FeeSettings,
     contact: &Contact,
     signed_object: BadSignatureEvidence,
     signature: Signature,
@@ -484,62 +579,192 @@ pub async fn submit_bad_signature_evidence(
         sender: our_address.to_string(),
     };
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 500_000_000u64,
-        granter: None,
-        payer: None,
-    };
-
     let msg = Msg::new(
         "/gravity.v1.MsgSubmitBadSignatureEvidence",
         msg_submit_bad_signature_evidence,
     );
 
-    let args = contact.get_message_args(our_address, fee).await?;
-    trace!("got optional tx info");
-
-    let msg_bytes = private_key.sign_std_msg(&[msg], args, MEMO)?;
-
-    let response = contact
-        .send_transaction(msg_bytes, BroadcastMode::Sync)
-        .await?;
-
-    contact.wait_for_tx(response, TIMEOUT).await
+    send_messages(contact, private_key, fee, vec![msg], Some(TIMEOUT)).await
 }
 
 /// Cancels a user provided SendToEth transaction, provided it's not already in a batch
 /// you should check with `QueryPendingSendToEth`
 pub async fn cancel_send_to_eth(
     private_key: PrivateKey,
-    fee: Coin,
+    fee: &FeeSettings,
     contact: &Contact,
     transaction_id: u64,
 ) -> Result<TxResponse, CosmosGrpcError> {
     let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
 
+    let msg = build_cancel_send_to_eth_msg(our_address, transaction_id);
+
+    send_messages(contact, private_key, fee, vec![msg], Some(TIMEOUT)).await
+}
+
+/// Cancels several SendToEth transactions in a single Cosmos transaction, if any one of them
+/// is already in a batch none of them are cancelled
+pub async fn cancel_sends_to_eth(
+    private_key: PrivateKey,
+    fee: &FeeSettings,
+    contact: &Contact,
+    transaction_ids: &[u64],
+) -> Result<TxResponse, CosmosGrpcError> {
+    let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
+
+    let msgs = transaction_ids
+        .iter()
+        .map(|id| build_cancel_send_to_eth_msg(our_address, *id))
+        .collect();
+
+    send_messages(contact, private_key, fee, msgs, Some(TIMEOUT)).await
+}
+
+pub fn build_cancel_send_to_eth_msg(sender: Address, transaction_id: u64) -> Msg {
     let msg_cancel_send_to_eth = MsgCancelSendToEth {
         transaction_id,
-        sender: our_address.to_string(),
+        sender: sender.to_string(),
     };
 
-    let fee = Fee {
-        amount: vec![fee],
-        gas_limit: 600_000u64,
-        granter: None,
-        payer: None,
-    };
+    Msg::new("/gravity.v1.MsgCancelSendToEth", msg_cancel_send_to_eth)
+}
 
-    let msg = Msg::new("/gravity.v1.MsgCancelSendToEth", msg_cancel_send_to_eth);
+/// Simulates, signs, and broadcasts the provided messages as a single transaction, the gas
+/// limit and fee are computed from the simulated gas usage and the provided fee settings.
+/// Waits for the transaction to enter a block if a timeout is provided
+async fn send_messages(
+    contact: &Contact,
+    private_key: PrivateKey,
+    fee: &FeeSettings,
+    msgs: Vec<Msg>,
+    timeout: Option<Duration>,
+) -> Result<TxResponse, CosmosGrpcError> {
+    let our_address = private_key.to_address(&contact.get_prefix()).unwrap();
 
-    let args = contact.get_message_args(our_address, fee).await?;
+    let args = contact
+        .get_message_args(our_address, simulation_fee())
+        .await?;
     trace!("got optional tx info");
 
-    let msg_bytes = private_key.sign_std_msg(&[msg], args, MEMO)?;
+    let msg_bytes = sign_with_simulated_fee(contact, private_key, &msgs, args, fee).await?;
 
     let response = contact
         .send_transaction(msg_bytes, BroadcastMode::Sync)
         .await?;
 
-    contact.wait_for_tx(response, TIMEOUT).await
+    match timeout {
+        Some(duration) => contact.wait_for_tx(response, duration).await,
+        None => Ok(response),
+    }
+}
+
+/// The fee used while simulating a transaction, no amount is provided as the node
+/// would otherwise check our balance against the simulation gas limit
+pub(crate) fn simulation_fee() -> Fee {
+    Fee {
+        amount: Vec::new(),
+        gas_limit: SIMULATION_GAS_LIMIT,
+        granter: None,
+        payer: None,
+    }
+}
+
+/// Simulates the provided messages with the provided message args and returns the signed transaction
+/// bytes with the fee produced by `estimate_fee`
+pub async fn sign_with_simulated_fee(
+    contact: &Contact,
+    private_key: PrivateKey,
+    msgs: &[Msg],
+    mut args: MessageArgs,
+    fee: &FeeSettings,
+) -> Result<Vec<u8>, CosmosGrpcError> {
+    args.fee = estimate_fee(contact, private_key, msgs, args.clone(), fee).await?;
+    Ok(private_key.sign_std_msg(msgs, args, MEMO)?)
+}
+
+/// Simulates the provided messages with the provided message args and returns a fee with the gas
+/// limit set to the simulated gas usage times the gas adjustment and the amount set to the gas
+/// limit times the gas price
+pub async fn estimate_fee(
+    contact: &Contact,
+    private_key: PrivateKey,
+    msgs: &[Msg],
+    mut args: MessageArgs,
+    fee: &FeeSettings,
+) -> Result<Fee, CosmosGrpcError> {
+    args.fee = simulation_fee();
+    let simulation_bytes = private_key.sign_std_msg(msgs, args, MEMO)?;
+    let gas_used = simulate_gas(contact, simulation_bytes).await?;
+
+    let gas_limit = fee.gas_limit(gas_used);
+    trace!(
+        "Simulated {} messages used {} gas, setting gas limit {}",
+        msgs.len(),
+        gas_used,
+        gas_limit
+    );
+    // Cosmos SDK v0.42 has no fee grants and requires the fee payer to sign every transaction,
+    // as all of ours have a single signer it always pays the fee
+    Ok(Fee {
+        amount: vec![fee.fee_for_gas(gas_limit)],
+        gas_limit,
+        granter: None,
+        payer: None,
+    })
+}
+
+lazy_static! {
+    /// Tx service clients by url, every transaction is simulated first so the connection is
+    /// kept open rather than made again for each one
+    static ref TX_CLIENTS: StdMutex<HashMap<String, TxServiceClient<Channel>>> =
+        StdMutex::new(HashMap::new());
+}
+
+/// Gets a Tx service client for the node the contact talks to, connecting only the first time
+async fn get_tx_client(contact: &Contact) -> Result<TxServiceClient<Channel>, CosmosGrpcError> {
+    let url = contact.get_url();
+    if let Some(client) = TX_CLIENTS.lock().unwrap().get(&url) {
+        return Ok(client.clone());
+    }
+    let client = TxServiceClient::connect(url.clone()).await.map_err(|e| {
+        CosmosGrpcError::BadInput(format!("Failed to connect to simulate tx {:?}", e))
+    })?;
+    TX_CLIENTS.lock().unwrap().insert(url, client.clone());
+    Ok(client)
+}
+
+/// Runs the signed transaction against the current state of the chain without
+/// committing it and returns the amount of gas used
+pub(crate) async fn simulate_gas(
+    contact: &Contact,
+    tx_bytes: Vec<u8>,
+) -> Result<u64, CosmosGrpcError> {
+    // the Cosmos SDK 0.42 simulate endpoint takes a decoded transaction rather than bytes
+    let tx_raw = TxRaw::decode(tx_bytes.as_slice())
+        .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid tx bytes {:?}", e)))?;
+    let body = TxBody::decode(tx_raw.body_bytes.as_slice())
+        .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid tx body {:?}", e)))?;
+    let auth_info = AuthInfo::decode(tx_raw.auth_info_bytes.as_slice())
+        .map_err(|e| CosmosGrpcError::BadInput(format!("Invalid tx auth info {:?}", e)))?;
+    let tx = Tx {
+        body: Some(body),
+        auth_info: Some(auth_info),
+        signatures: tx_raw.signatures,
+    };
+
+    let mut txrpc = get_tx_client(contact).await?;
+    let response = txrpc
+        .simulate(SimulateRequest { tx: Some(tx) })
+        .await
+        .map_err(|e| {
+            CosmosGrpcError::BadInput(format!("Transaction simulation failed {}", e.message()))
+        })?
+        .into_inner();
+
+    match response.gas_info {
+        Some(gas_info) => Ok(gas_info.gas_used),
+        None => Err(CosmosGrpcError::BadInput(
+            "Simulation did not return gas info".to_string(),
+        )),
+    }
 }
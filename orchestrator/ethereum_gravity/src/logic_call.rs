@@ -273,6 +273,7 @@ mod tests {
             timeout: 4766922941000,
             invalidation_id: invalidation_id.clone(),
             invalidation_nonce,
+            block: 0,
         };
 
         // a validator set
@@ -226,6 +226,7 @@ mod test {
             }],
             total_fee: token,
             token_contract: erc20_addr,
+            block: 0,
         };
 
         let checkpoint = encode_tx_batch_confirm("foo".to_string(), batch.clone());
@@ -273,6 +274,7 @@ mod test {
             }],
             total_fee: token,
             token_contract: erc20_addr,
+            block: 0,
         };
 
         let mut rng = rand::thread_rng();
@@ -318,6 +320,7 @@ mod test {
             )
             .unwrap(),
             invalidation_nonce: 1u8.into(),
+            block: 0,
         };
         let checkpoint = encode_logic_call_confirm("foo".to_string(), logic_call);
         println!("{}", checkpoint.len() / 32);
@@ -87,7 +87,8 @@ pub async fn send_eth_transaction_batch(
     Ok(())
 }
 
-/// Returns the cost in Eth of sending this batch
+/// Returns the cost in Eth of sending this batch from `our_eth_address`. An address without
+/// any Eth is not limited by its balance, so anyone can price a batch
 pub async fn estimate_tx_batch_cost(
     current_valset: Valset,
     batch: TransactionBatch,
@@ -95,21 +96,28 @@ pub async fn estimate_tx_batch_cost(
     web3: &Web3,
     gravity_contract_address: EthAddress,
     gravity_id: String,
-    our_eth_key: EthPrivateKey,
+    our_eth_address: EthAddress,
 ) -> Result<GasCost, GravityError> {
-    let our_eth_address = our_eth_key.to_public_key().unwrap();
     let our_balance = web3.eth_get_balance(our_eth_address).await?;
     let our_nonce = web3.eth_get_transaction_count(our_eth_address).await?;
-    let gas_limit = min((u64::MAX - 1).into(), our_balance.clone());
     let gas_price = web3.eth_gas_price().await?;
     let zero: Uint256 = 0u8.into();
+    // the node caps the estimate at what the sender can pay for, unless no price is given
+    let (gas_limit, request_gas_price) = if our_balance == zero {
+        (None, None)
+    } else {
+        (
+            Some(min((u64::MAX - 1).into(), our_balance.clone()).into()),
+            Some(gas_price.clone().into()),
+        )
+    };
     let val = web3
         .eth_estimate_gas(TransactionRequest {
             from: Some(our_eth_address),
             to: gravity_contract_address,
             nonce: Some(our_nonce.clone().into()),
-            gas_price: Some(gas_price.clone().into()),
-            gas: Some(gas_limit.into()),
+            gas_price: request_gas_price,
+            gas: gas_limit,
             value: Some(zero.into()),
             data: Some(encode_batch_payload(current_valset, &batch, confirms, gravity_id)?.into()),
         })
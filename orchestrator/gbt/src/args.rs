@@ -3,12 +3,15 @@
 
 use std::path::PathBuf;
 
+use crate::output::OutputFormat;
+use crate::utils::TokenAmount;
 use clap::AppSettings;
 use clap::Clap;
 use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
-use deep_space::PrivateKey as CosmosPrivateKey;
 use deep_space::{address::Address as CosmosAddress, Coin};
+use gravity_utils::types::GasPrice;
+use relayer::fee_estimator::FeeTarget;
 
 /// Gravity Bridge tools (gbt) provides tools for interacting with the Althea Gravity bridge for Cosmos based blockchains.
 #[derive(Clap)]
@@ -25,10 +28,14 @@ pub struct Opts {
     /// $HOME/.althea_gbt/
     #[clap(short, long, parse(from_str))]
     pub home: Option<PathBuf>,
-    /// Set the address prefix for the Cosmos chain
-    /// default is 'cosmos'
-    #[clap(short, long, default_value = "cosmos")]
-    pub address_prefix: String,
+    /// Set the address prefix for the Cosmos chain, overrides the address_prefix
+    /// value in the config, default is 'cosmos'
+    #[clap(short, long)]
+    pub address_prefix: Option<String>,
+    /// The format of the result printed to stdout, text or json. With json every command
+    /// prints a single result object when it finishes, logs are always written to stderr
+    #[clap(long, default_value = "text", parse(try_from_str))]
+    pub output: OutputFormat,
     #[clap(subcommand)]
     pub subcmd: SubCommand,
 }
@@ -39,7 +46,12 @@ pub enum SubCommand {
     Relayer(RelayerOpts),
     Client(ClientOpts),
     Keys(KeyOpts),
+    Journal(JournalOpts),
+    Query(QueryOpts),
+    Config(ConfigOpts),
+    Doctor(DoctorOpts),
     Init(InitOpts),
+    Tx(TxOpts),
 }
 
 /// The Gravity Bridge orchestrator is required for all validators of the Cosmos chain running
@@ -48,24 +60,62 @@ pub enum SubCommand {
 #[clap(setting = AppSettings::ColoredHelp)]
 pub struct OrchestratorOpts {
     /// Cosmos mnemonic phrase containing the tokens you would like to send
-    #[clap(short, long, parse(try_from_str))]
-    pub cosmos_phrase: Option<CosmosPrivateKey>,
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
     /// An Ethereum private key containing ETH to pay for fees, this will also hold the relayers earnings
     /// in the near future it will be possible to disable the Orchestrators integrated relayer
     #[clap(short, long, parse(try_from_str))]
     pub ethereum_key: Option<EthPrivateKey>,
-    /// (Optional) The Cosmos gRPC server that will be used
-    #[clap(long, default_value = "http://localhost:9090")]
-    pub cosmos_grpc: String,
-    /// (Optional) The Ethereum RPC server that will be used
-    #[clap(long, default_value = "http://localhost:8545")]
-    pub ethereum_rpc: String,
-    /// The Cosmos Denom and amount to pay Cosmos chain fees
-    #[clap(short, long, parse(try_from_str))]
-    pub fees: Coin,
+    /// (Optional) The Cosmos gRPC server that will be used, a comma separated list of
+    /// servers may be provided, the first is used until it falls behind or goes offline
+    /// at which point the Orchestrator will fail over to the next. Overrides the
+    /// cosmos_grpc value in the config
+    #[clap(long)]
+    pub cosmos_grpc: Option<String>,
+    /// (Optional) The Ethereum RPC server that will be used, overrides the
+    /// ethereum_rpc value in the config
+    #[clap(long)]
+    pub ethereum_rpc: Option<String>,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025ugraviton
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// Deprecated, use --gas-price. A fixed fee paid by every transaction eg: 1ugraviton
+    #[clap(short, long, parse(try_from_str), conflicts_with = "gas_price")]
+    pub fees: Option<Coin>,
     /// The address fo the Gravity contract on Ethereum
     #[clap(short, long, parse(try_from_str))]
     pub gravity_contract_address: Option<EthAddress>,
+    #[clap(subcommand)]
+    pub subcmd: Option<OrchestratorSubcommand>,
+}
+
+#[derive(Clap)]
+pub enum OrchestratorSubcommand {
+    Approve(ApproveOpts),
+    Status(OrchestratorStatusOpts),
+}
+
+/// Approve a batch or logic call held by the signing policy, the Orchestrator will sign it
+/// on it's next loop. Check what you are approving carefully!
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct ApproveOpts {
+    /// The checkpoint hash logged by the Orchestrator when it held the batch or logic call
+    pub checkpoint: String,
+}
+
+/// List the valsets, batches and logic calls the Orchestrator has not signed along with
+/// the block at which the validator becomes slashable for each
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct OrchestratorStatusOpts {
+    /// (Optional) The Orchestrator address to check, defaults to the stored Orchestrator key
+    #[clap(long, parse(try_from_str))]
+    pub orchestrator_address: Option<CosmosAddress>,
+    /// (Optional) The Cosmos gRPC server that will be used
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
 }
 
 /// The Gravity Bridge Relayer is an unpermissioned role that takes data from the Cosmos blockchain
@@ -80,12 +130,14 @@ pub struct RelayerOpts {
     /// The address fo the Gravity contract on Ethereum
     #[clap(short, long, parse(try_from_str))]
     pub gravity_contract_address: Option<EthAddress>,
-    /// (Optional) The Ethereum RPC server that will be used
-    #[clap(long, default_value = "http://localhost:8545")]
-    pub ethereum_rpc: String,
-    /// (Optional) The Cosmos gRPC server that will be used to
-    #[clap(short, long, default_value = "http://localhost:9090")]
-    pub cosmos_grpc: String,
+    /// (Optional) The Ethereum RPC server that will be used, overrides the
+    /// ethereum_rpc value in the config
+    #[clap(long)]
+    pub ethereum_rpc: Option<String>,
+    /// (Optional) The Cosmos gRPC server that will be used, a comma separated list of
+    /// servers may be provided for failover. Overrides the cosmos_grpc value in the config
+    #[clap(short, long)]
+    pub cosmos_grpc: Option<String>,
 }
 
 /// The Gravity Bridge client contains helpful command line tools for interacting with the Gravity bridge
@@ -101,31 +153,63 @@ pub enum ClientSubcommand {
     CosmosToEth(CosmosToEthOpts),
     EthToCosmos(EthToCosmosOpts),
     DeployErc20Representation(DeployErc20RepresentationOpts),
+    CancelSendToEth(CancelSendToEthOpts),
+    RequestBatch(RequestBatchOpts),
+    Track(TrackOpts),
+    BulkSend(BulkSendOpts),
 }
 
 /// Send Cosmos tokens to Ethereum
 #[derive(Clap)]
 #[clap(setting = AppSettings::ColoredHelp)]
 pub struct CosmosToEthOpts {
-    /// Cosmos mnemonic phrase containing the tokens you would like to send
-    #[clap(short, long, parse(try_from_str))]
-    pub cosmos_phrase: CosmosPrivateKey,
+    /// Cosmos mnemonic phrase containing the tokens you would like to send, not needed
+    /// with --generate-only
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
     /// (Optional) The Cosmos gRPC server that will be used to submit the transaction
     #[clap(long, default_value = "http://localhost:9090")]
     pub cosmos_grpc: String,
-    /// The Denom and amount you wish to send eg: 100uatom
+    /// The Denom and amount you wish to send eg: 1.5uatom, in the token's display units
+    /// unless --base-units is passed
     #[clap(short, long, parse(try_from_str))]
-    pub amount: Coin,
-    /// The Cosmos Denom and amount to pay Cosmos chain fees eg: 1uatom
-    #[clap(short, long, parse(try_from_str))]
-    pub fees: Coin,
+    pub amount: TokenAmount,
+    /// Read --amount and --bridge-fee as whole numbers of the token's base units, needed
+    /// when the token's display units are not known
+    #[clap(long)]
+    pub base_units: bool,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025uatom
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// Deprecated, use --gas-price. A fixed fee paid by every transaction eg: 1uatom
+    #[clap(short, long, parse(try_from_str), conflicts_with = "gas_price")]
+    pub fees: Option<Coin>,
     /// The destination address on the Ethereum chain
     #[clap(short, long, parse(try_from_str))]
     pub eth_destination: EthAddress,
+    /// (Optional) The fee paid to the relayer in the token being sent, in the same units as
+    /// --amount, defaults to 1 base unit
+    #[clap(long, conflicts_with = "fee_target")]
+    pub bridge_fee: Option<String>,
+    /// (Optional) Estimate a competitive bridge fee instead of setting one, next-batch pays
+    /// enough for the next batch to be profitable to relay and cheapest pays this transfer's
+    /// share of it. Uses --ethereum-rpc to price the batch and the token on Uniswap like
+    /// relayers do
+    #[clap(long, parse(try_from_str))]
+    pub fee_target: Option<FeeTarget>,
     /// If this command should request a batch to push
     /// your tx along immediately
     #[clap(short, long)]
     pub no_batch: bool,
+    /// Follow the transfer until it has been executed on Ethereum
+    #[clap(short, long)]
+    pub wait: bool,
+    /// (Optional) The Ethereum RPC server used to follow the transfer with --wait
+    #[clap(long, default_value = "http://localhost:8545")]
+    pub ethereum_rpc: String,
+    #[clap(flatten)]
+    pub generate: GenerateOnlyOpts,
 }
 
 /// Send an Ethereum ERC20 token to Cosmos
@@ -144,12 +228,22 @@ pub struct EthToCosmosOpts {
     /// The ERC20 contract address of the ERC20 you are sending
     #[clap(short, long, parse(try_from_str))]
     pub token_contract_address: EthAddress,
-    /// The amount of tokens you are sending eg. 1.2 ATOM
-    #[clap(short, long, parse(try_from_str))]
-    pub amount: f64,
+    /// The amount of tokens you are sending eg. 1.2, in whole tokens using the ERC20's decimals
+    /// unless --base-units is passed
+    #[clap(short, long)]
+    pub amount: String,
+    /// Read --amount as a whole number of the ERC20's base units
+    #[clap(long)]
+    pub base_units: bool,
     /// The destination address on the Cosmos blockchain
     #[clap(short, long, parse(try_from_str))]
     pub destination: CosmosAddress,
+    /// Follow the deposit until it has been credited on Cosmos
+    #[clap(short, long)]
+    pub wait: bool,
+    /// (Optional) The Cosmos gRPC server used to follow the deposit with --wait
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
 }
 
 /// Deploy an ERC20 representation of a Cosmos asset on the Ethereum chain
@@ -183,6 +277,151 @@ pub struct DeployErc20RepresentationOpts {
     pub erc20_decimals: u8,
 }
 
+/// List transfers to Ethereum that have not yet been batched and cancel them, refunding
+/// the amount and bridge fee. Without --id or --all the pending transfers are only listed
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct CancelSendToEthOpts {
+    /// Cosmos mnemonic phrase of the address that sent the transfers, not needed with
+    /// --generate-only
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
+    /// (Optional) The Cosmos gRPC server that will be used to submit the transaction
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025uatom
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// The id of a transfer to cancel, may be given more than once
+    #[clap(long)]
+    pub id: Vec<u64>,
+    /// Cancel every unbatched transfer from this address
+    #[clap(long, conflicts_with = "id")]
+    pub all: bool,
+    /// Do not ask for confirmation before cancelling
+    #[clap(short, long)]
+    pub yes: bool,
+    #[clap(flatten)]
+    pub generate: GenerateOnlyOpts,
+}
+
+/// Request a batch for a token type without sending anything, this pushes the
+/// transfers waiting in the pool for that token towards Ethereum
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct RequestBatchOpts {
+    /// Cosmos mnemonic phrase of the address paying fees for the request, not needed with
+    /// --generate-only
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
+    /// (Optional) The Cosmos gRPC server that will be used to submit the transaction
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025uatom
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// The Cosmos denom to request a batch for
+    #[clap(short, long)]
+    pub denom: String,
+    #[clap(flatten)]
+    pub generate: GenerateOnlyOpts,
+}
+
+/// Send many transfers listed in a CSV or JSON file, rows with an Ethereum destination
+/// are sent from Cosmos and rows with a Cosmos destination are sent from Ethereum
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct BulkSendOpts {
+    /// The file listing the transfers, a .json file holds an array of objects and any other
+    /// file is read as CSV. Both use the fields destination, amount, token and the optional
+    /// bridge_fee, amounts are in the token's display units unless --base-units is passed.
+    /// The token is a Cosmos denom for transfers to Ethereum or an ERC20 address for
+    /// transfers to Cosmos
+    #[clap(short, long, parse(from_str))]
+    pub file: PathBuf,
+    /// Read every amount and bridge fee in the file as whole numbers of base units
+    #[clap(long)]
+    pub base_units: bool,
+    /// (Optional) Where to write the per row results, defaults to the input file name with
+    /// .report added. The report is JSON if this ends in .json and CSV otherwise
+    #[clap(short, long, parse(from_str))]
+    pub report: Option<PathBuf>,
+    /// Continue an earlier run, rows the report lists as sent or unknown are not sent again.
+    /// Unknown rows were broadcast but not seen executing, check their txid before
+    /// sending them again with a new file
+    #[clap(long)]
+    pub resume: bool,
+    /// Cosmos mnemonic phrase containing the tokens sent to Ethereum
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
+    /// (Optional) The Cosmos gRPC server that will be used to submit the transactions
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025uatom
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// (Optional) The most transfers to Ethereum packed into one Cosmos transaction
+    #[clap(long, default_value = "50")]
+    pub max_msgs_per_tx: usize,
+    /// (Optional) The most gas one Cosmos transaction may use, transactions are simulated
+    /// and split into more transactions to stay under it
+    #[clap(long, default_value = "3000000")]
+    pub max_gas_per_tx: u64,
+    /// Do not request batches once the transfers to Ethereum are sent
+    #[clap(short, long)]
+    pub no_batch: bool,
+    /// The Ethereum private key holding the tokens sent to Cosmos
+    #[clap(long, parse(try_from_str))]
+    pub ethereum_key: Option<EthPrivateKey>,
+    /// (Optional) The Ethereum RPC server that will be used to submit the transactions
+    #[clap(long, default_value = "http://localhost:8545")]
+    pub ethereum_rpc: String,
+    /// (Optional) The address of the Gravity contract on Ethereum, overrides the
+    /// gravity_contract_address value in the config
+    #[clap(short, long, parse(try_from_str))]
+    pub gravity_contract_address: Option<EthAddress>,
+    /// Send without asking for confirmation
+    #[clap(short, long)]
+    pub yes: bool,
+}
+
+/// Options for commands that can print an unsigned transaction instead of signing and
+/// broadcasting it, see `gbt tx sign` and `gbt tx broadcast`
+#[derive(Clap)]
+pub struct GenerateOnlyOpts {
+    /// Print the unsigned transaction as JSON instead of signing and broadcasting it
+    #[clap(long)]
+    pub generate_only: bool,
+    /// The address that will sign the transaction, used in place of a key with --generate-only
+    #[clap(long, parse(try_from_str))]
+    pub from: Option<CosmosAddress>,
+    /// The gas limit of the generated transaction, it can not be simulated without the key
+    #[clap(long, default_value = "200000")]
+    pub gas_limit: u64,
+}
+
+/// Follow a transfer to Ethereum through the pool, batching, validator confirms and
+/// execution on Ethereum
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct TrackOpts {
+    /// The txhash of the transfer or it's id in the transaction pool
+    pub target: String,
+    /// The address that sent the transfer, needed to find a transfer by id while it is in
+    /// the pool. Found automatically when tracking by txhash
+    #[clap(long, parse(try_from_str))]
+    pub sender: Option<CosmosAddress>,
+    /// (Optional) The Cosmos gRPC server that will be used to query
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+    /// (Optional) The Ethereum RPC server that will be used to query
+    #[clap(long, default_value = "http://localhost:8545")]
+    pub ethereum_rpc: String,
+}
+
 /// Manage keys
 #[derive(Clap)]
 #[clap(setting = AppSettings::ColoredHelp)]
@@ -196,18 +435,29 @@ pub enum KeysSubcommand {
     RegisterOrchestratorAddress(RegisterOrchestratorAddressOpts),
     SetEthereumKey(SetEthereumKeyOpts),
     SetOrchestratorKey(SetOrchestratorKeyOpts),
+    Recover(RecoverKeysOpts),
     Show,
 }
 
 /// Register delegate keys for the Gravity Orchestrator.
-/// this is a mandatory part of setting up a Gravity Orchestrator
-/// If you would like sign using a ledger see `cosmos tx gravity set-orchestrator-address` instead
+/// this is a mandatory part of setting up a Gravity Orchestrator.
+/// The registration is signed with either the validator phrase or a Ledger device
 #[derive(Clap)]
 #[clap(setting = AppSettings::ColoredHelp)]
 pub struct RegisterOrchestratorAddressOpts {
-    /// The Cosmos private key of the validator
-    #[clap(short, long, parse(try_from_str))]
-    pub validator_phrase: CosmosPrivateKey,
+    /// The Cosmos private key of the validator, not needed when signing with a Ledger
+    #[clap(short, long)]
+    pub validator_phrase: Option<String>,
+    /// Sign with the validator key on a Ledger device running the Cosmos app
+    #[clap(long)]
+    pub ledger: bool,
+    /// The HD path of the validator key on the Ledger
+    #[clap(long, default_value = "m/44'/118'/0'/0/0")]
+    pub ledger_hd_path: String,
+    /// (Optional) Talk to a Speculos Ledger emulator at this address instead of a USB device,
+    /// eg: 127.0.0.1:9999
+    #[clap(long)]
+    pub speculos: Option<String>,
     /// (Optional) The Ethereum private key to register, will be generated if not provided
     #[clap(short, long, parse(try_from_str))]
     pub ethereum_key: Option<EthPrivateKey>,
@@ -217,12 +467,40 @@ pub struct RegisterOrchestratorAddressOpts {
     /// (Optional) The Cosmos gRPC server that will be used to submit the transaction
     #[clap(long, default_value = "http://localhost:9090")]
     pub cosmos_grpc: String,
-    /// The Cosmos Denom and amount to pay Cosmos chain fees
-    #[clap(short, long, parse(try_from_str))]
-    pub fees: Coin,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025ugraviton
+    /// overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// Deprecated, use --gas-price. A fixed fee paid by every transaction eg: 1ugraviton
+    #[clap(short, long, parse(try_from_str), conflicts_with = "gas_price")]
+    pub fees: Option<Coin>,
+    /// The index n of the Ethereum key derived from the Cosmos phrase at m/44'/60'/0'/0/n,
+    /// used when no Ethereum key is provided or stored
+    #[clap(long, default_value = "0")]
+    pub ethereum_index: u32,
     /// Do not save keys to disk for later use with `orchestrator start`
     #[clap(long)]
     pub no_save: bool,
+    /// With --generate-only use --from for the validator's account address
+    #[clap(flatten)]
+    pub generate: GenerateOnlyOpts,
+}
+
+/// Rebuild the Orchestrator's Cosmos and Ethereum keys from a mnemonic phrase and store
+/// them. The Cosmos key uses the HD path from the [keys] section of the config and the
+/// Ethereum key the standard m/44'/60'/0'/0/n path
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct RecoverKeysOpts {
+    /// (Optional) The mnemonic phrase to recover from, asked for if not provided
+    #[clap(short, long)]
+    pub phrase: Option<String>,
+    /// The index n of the Ethereum key at m/44'/60'/0'/0/n
+    #[clap(long, default_value = "0")]
+    pub ethereum_index: u32,
+    /// Only print the recovered addresses, do not save the keys
+    #[clap(long)]
+    pub no_save: bool,
 }
 
 /// Add an Ethereum private key for use with either the Relayer or the Orchestrator
@@ -242,7 +520,295 @@ pub struct SetOrchestratorKeyOpts {
     pub phrase: String,
 }
 
-/// Initialize configuration
+/// Inspect the journal of every Ethereum signature produced by the Orchestrator
 #[derive(Clap)]
 #[clap(setting = AppSettings::ColoredHelp)]
-pub struct InitOpts {}
+pub struct JournalOpts {
+    #[clap(subcommand)]
+    pub subcmd: JournalSubcommand,
+}
+
+#[derive(Clap)]
+pub enum JournalSubcommand {
+    Show(JournalShowOpts),
+    Export(JournalExportOpts),
+}
+
+/// Filters applied to journal entries, all filters are optional
+#[derive(Clap)]
+pub struct JournalFilterOpts {
+    /// Only show entries of this type, one of valset, batch or logic-call
+    #[clap(long = "type")]
+    pub object_type: Option<String>,
+    /// Only show entries with this nonce
+    #[clap(long)]
+    pub nonce: Option<u64>,
+    /// Only show entries with this checkpoint hash
+    #[clap(long)]
+    pub checkpoint: Option<String>,
+}
+
+/// Print signing journal entries
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct JournalShowOpts {
+    #[clap(flatten)]
+    pub filter: JournalFilterOpts,
+}
+
+/// Export signing journal entries to a file
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct JournalExportOpts {
+    #[clap(flatten)]
+    pub filter: JournalFilterOpts,
+    /// The file to write the entries to
+    #[clap(short, long, parse(from_str))]
+    pub output: PathBuf,
+    /// The export format, json or csv
+    #[clap(long, default_value = "json")]
+    pub format: String,
+}
+
+/// Check that the Orchestrator is configured correctly and can pay it's fees. The same
+/// checks run every time the Orchestrator starts
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct DoctorOpts {
+    /// (Optional) The Cosmos gRPC server that will be used, overrides the config
+    #[clap(long)]
+    pub cosmos_grpc: Option<String>,
+    /// (Optional) The Ethereum RPC server that will be used, overrides the config
+    #[clap(long)]
+    pub ethereum_rpc: Option<String>,
+    /// (Optional) The Gravity contract to check, defaults to the config or the Gravity params
+    #[clap(short, long, parse(try_from_str))]
+    pub gravity_contract_address: Option<EthAddress>,
+    /// (Optional) The Orchestrator address to check, defaults to the configured key
+    #[clap(long, parse(try_from_str))]
+    pub orchestrator_address: Option<CosmosAddress>,
+    /// (Optional) The Ethereum address to check, defaults to the configured key
+    #[clap(long, parse(try_from_str))]
+    pub ethereum_address: Option<EthAddress>,
+    /// (Optional) The gas price used to pay Cosmos chain fees, overrides the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// The number of days the fee and ETH balances should last at the current rate
+    #[clap(long, default_value = "7")]
+    pub days: u64,
+}
+
+/// Query the state of the Gravity module
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct QueryOpts {
+    /// Print the result as json instead of human readable text
+    #[clap(long)]
+    pub json: bool,
+    /// (Optional) The Cosmos gRPC server that will be used to query
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+    #[clap(subcommand)]
+    pub subcmd: QuerySubcommand,
+}
+
+#[derive(Clap)]
+pub enum QuerySubcommand {
+    /// The Gravity module params
+    Params,
+    /// The validator set currently in effect on the Cosmos chain
+    CurrentValset,
+    /// The most recent validator set updates waiting to be relayed
+    PendingValsets,
+    /// Outgoing transaction batches
+    Batches(BatchesQueryOpts),
+    /// Outgoing logic calls
+    LogicCalls(LogicCallsQueryOpts),
+    /// Recent attestations for Ethereum events and the validators that have not voted on them
+    Attestations(AttestationsQueryOpts),
+    /// Delegate keys registered for a validator
+    DelegateKeys(DelegateKeysQueryOpts),
+    /// The Cosmos denom that represents an ERC20
+    Erc20ToDenom(Erc20ToDenomQueryOpts),
+    /// The ERC20 that represents a Cosmos denom
+    DenomToErc20(DenomToErc20QueryOpts),
+    /// Transfers to Ethereum from an address that have not yet been executed
+    PendingSends(PendingSendsQueryOpts),
+}
+
+#[derive(Clap)]
+pub struct BatchesQueryOpts {
+    /// Also show the confirms submitted for every batch
+    #[clap(long)]
+    pub confirms: bool,
+}
+
+#[derive(Clap)]
+pub struct LogicCallsQueryOpts {
+    /// Also show the confirms submitted for every logic call
+    #[clap(long)]
+    pub confirms: bool,
+}
+
+#[derive(Clap)]
+pub struct AttestationsQueryOpts {
+    /// The maximum number of attestations to show
+    #[clap(long)]
+    pub limit: Option<u64>,
+    /// Only show the attestation for this event nonce
+    #[clap(long)]
+    pub event_nonce: Option<u64>,
+}
+
+/// Exactly one of the addresses must be provided
+#[derive(Clap)]
+pub struct DelegateKeysQueryOpts {
+    /// The validator operator address
+    #[clap(long)]
+    pub validator: Option<String>,
+    /// The delegate Ethereum address
+    #[clap(long, parse(try_from_str))]
+    pub eth_address: Option<EthAddress>,
+    /// The delegate Cosmos address
+    #[clap(long, parse(try_from_str))]
+    pub orchestrator: Option<CosmosAddress>,
+}
+
+#[derive(Clap)]
+pub struct Erc20ToDenomQueryOpts {
+    #[clap(parse(try_from_str))]
+    pub erc20: EthAddress,
+}
+
+#[derive(Clap)]
+pub struct DenomToErc20QueryOpts {
+    pub denom: String,
+}
+
+#[derive(Clap)]
+pub struct PendingSendsQueryOpts {
+    #[clap(parse(try_from_str))]
+    pub address: CosmosAddress,
+}
+
+/// Inspect the configuration
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct ConfigOpts {
+    #[clap(subcommand)]
+    pub subcmd: ConfigSubcommand,
+}
+
+#[derive(Clap)]
+pub enum ConfigSubcommand {
+    Show(ConfigShowOpts),
+}
+
+/// Print the effective config after environment variable and command line
+/// overrides, keys are redacted. Pass the same overrides as the Orchestrator
+/// or Relayer to see the config they will run with
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct ConfigShowOpts {
+    /// (Optional) Overrides the cosmos_grpc value in the config
+    #[clap(long)]
+    pub cosmos_grpc: Option<String>,
+    /// (Optional) Overrides the ethereum_rpc value in the config
+    #[clap(long)]
+    pub ethereum_rpc: Option<String>,
+    /// (Optional) Overrides the gas_price value in the config
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// (Optional) Overrides the gravity_contract_address value in the config
+    #[clap(short, long, parse(try_from_str))]
+    pub gravity_contract_address: Option<EthAddress>,
+}
+
+/// Set up the Gravity bridge tools config and keys, asks for and tests the servers, detects
+/// the address prefix and Gravity contract from the chain, imports or generates the delegate
+/// keys and optionally registers them
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct InitOpts {
+    /// Do not ask any questions, use the values provided on the command line, the existing
+    /// keys and the defaults
+    #[clap(short, long)]
+    pub yes: bool,
+    /// Replace an existing config without asking
+    #[clap(long)]
+    pub overwrite: bool,
+    /// (Optional) The Cosmos gRPC server to use
+    #[clap(long)]
+    pub cosmos_grpc: Option<String>,
+    /// (Optional) The Ethereum RPC server to use
+    #[clap(long)]
+    pub ethereum_rpc: Option<String>,
+    /// (Optional) The Gravity contract address, detected from the chain params if not provided
+    #[clap(short, long, parse(try_from_str))]
+    pub gravity_contract_address: Option<EthAddress>,
+    /// (Optional) The Orchestrator Cosmos phrase to import, generated if not provided
+    #[clap(short, long)]
+    pub cosmos_phrase: Option<String>,
+    /// (Optional) The Orchestrator Ethereum private key to import, generated if not provided
+    #[clap(short, long, parse(try_from_str))]
+    pub ethereum_key: Option<EthPrivateKey>,
+    /// (Optional) The gas price used to pay Cosmos chain fees eg: 0.025ugraviton
+    #[clap(long, parse(try_from_str))]
+    pub gas_price: Option<GasPrice>,
+    /// (Optional) The Cosmos private key of the validator, if provided the delegate keys
+    /// are registered
+    #[clap(short, long)]
+    pub validator_phrase: Option<String>,
+}
+
+/// Sign transactions generated with --generate-only on an offline machine and broadcast
+/// them later
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct TxOpts {
+    #[clap(subcommand)]
+    pub subcmd: TxSubcommand,
+}
+
+#[derive(Clap)]
+pub enum TxSubcommand {
+    Sign(TxSignOpts),
+    Broadcast(TxBroadcastOpts),
+}
+
+/// Sign an unsigned transaction file without connecting to the chain, writes the signed
+/// transaction as hex
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct TxSignOpts {
+    /// The unsigned transaction JSON produced by --generate-only
+    #[clap(parse(from_str))]
+    pub file: PathBuf,
+    /// Cosmos mnemonic phrase of the signer
+    #[clap(short, long)]
+    pub cosmos_phrase: String,
+    /// The account number of the signer
+    #[clap(long)]
+    pub account_number: u64,
+    /// The sequence of the signer, the number of transactions it has sent so far
+    #[clap(long)]
+    pub sequence: u64,
+    /// (Optional) Write the signed transaction to this file instead of printing it
+    #[clap(short, long, parse(from_str))]
+    pub output: Option<PathBuf>,
+    /// Do not ask for confirmation before signing
+    #[clap(short, long)]
+    pub yes: bool,
+}
+
+/// Broadcast a transaction signed with `gbt tx sign`
+#[derive(Clap)]
+#[clap(setting = AppSettings::ColoredHelp)]
+pub struct TxBroadcastOpts {
+    /// The file containing the signed transaction hex
+    #[clap(parse(from_str))]
+    pub file: PathBuf,
+    /// (Optional) The Cosmos gRPC server that will be used to submit the transaction
+    #[clap(long, default_value = "http://localhost:9090")]
+    pub cosmos_grpc: String,
+}
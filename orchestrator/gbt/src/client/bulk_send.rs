@@ -0,0 +1,1060 @@
+//! Sends many transfers listed in a file. Transfers to Ethereum are packed into as few Cosmos
+//! transactions as possible and transfers to Cosmos share one ERC20 approval per token, the
+//! outcome of every row is written to a report file
+
+use crate::args::BulkSendOpts;
+use crate::client::track::get_send_to_eth_ids;
+use crate::config::{parse_config_gravity_address, parse_cosmos_phrase};
+use crate::output::{fail, print_result, ErrorCode};
+use crate::utils::{
+    confirm_prompt, get_fee_settings, parse_token_amount, require_connection, TokenDisplays,
+    TIMEOUT,
+};
+use clarity::Address as EthAddress;
+use clarity::PrivateKey as EthPrivateKey;
+use clarity::Uint256;
+use cosmos_gravity::send::{estimate_send_to_eth_bulk_gas, send_request_batch, send_to_eth_bulk};
+use deep_space::{Address as CosmosAddress, Coin, Contact, PrivateKey as CosmosPrivateKey};
+use ethereum_gravity::send_to_cosmos::send_to_cosmos;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_proto::gravity::QueryDenomToErc20Request;
+use gravity_utils::connection_prep::{
+    check_for_eth, check_for_gas_price, create_rpc_connections, Connections,
+};
+use gravity_utils::types::{FeeSettings, GravityBridgeToolsConfig};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// Where a transfer is going, which also decides which chain it is sent from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destination {
+    Ethereum(EthAddress),
+    Cosmos(CosmosAddress),
+}
+
+/// One row of a bulk send file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    /// The 1 based position of the transfer in the file
+    pub row: usize,
+    pub destination: Destination,
+    /// The Cosmos denom for transfers to Ethereum, the ERC20 address for transfers to Cosmos
+    pub token: String,
+    pub amount: Uint256,
+    /// Paid in the transferred token, always zero for transfers to Cosmos
+    pub bridge_fee: Uint256,
+}
+
+/// A row as it is written in the file, before it is validated
+#[derive(Deserialize, Debug, Clone)]
+pub struct RawTransfer {
+    destination: String,
+    amount: String,
+    token: String,
+    #[serde(default)]
+    bridge_fee: Option<String>,
+}
+
+impl RawTransfer {
+    /// Transfers to an Ethereum address are sent from Cosmos
+    fn to_ethereum(&self) -> bool {
+        self.destination.starts_with("0x")
+    }
+
+    fn parse(self, row: usize, decimals: Option<u8>, base_units: bool) -> Result<Transfer, String> {
+        let destination = if self.to_ethereum() {
+            match self.destination.parse() {
+                Ok(address) => Destination::Ethereum(address),
+                Err(e) => {
+                    return Err(format!(
+                        "row {}: invalid Ethereum address {} {:?}",
+                        row, self.destination, e
+                    ))
+                }
+            }
+        } else {
+            match self.destination.parse() {
+                Ok(address) => Destination::Cosmos(address),
+                Err(e) => {
+                    return Err(format!(
+                        "row {}: invalid Cosmos address {} {:?}",
+                        row, self.destination, e
+                    ))
+                }
+            }
+        };
+        if self.token.is_empty() {
+            return Err(format!("row {}: missing token", row));
+        }
+        if let Destination::Cosmos(_) = destination {
+            if self.token.parse::<EthAddress>().is_err() {
+                return Err(format!(
+                    "row {}: {} is not an ERC20 address, transfers to Cosmos send ERC20 tokens",
+                    row, self.token
+                ));
+            }
+        }
+        let amount = parse_token_amount(&self.amount, decimals, base_units)
+            .map_err(|e| format!("row {}: {}", row, e))?;
+        if amount == 0u8.into() {
+            return Err(format!("row {}: the amount must be greater than zero", row));
+        }
+        let bridge_fee = match (destination, self.bridge_fee) {
+            // the same minimal fee `gbt client cosmos-to-eth` pays
+            (Destination::Ethereum(_), None) => 1u8.into(),
+            (Destination::Ethereum(_), Some(fee)) => parse_token_amount(&fee, decimals, base_units)
+                .map_err(|e| format!("row {}: {}", row, e))?,
+            (Destination::Cosmos(_), None) => 0u8.into(),
+            (Destination::Cosmos(_), Some(_)) => {
+                return Err(format!(
+                    "row {}: transfers to Cosmos do not pay a bridge fee",
+                    row
+                ))
+            }
+        };
+        Ok(Transfer {
+            row,
+            destination,
+            token: self.token,
+            amount,
+            bridge_fee,
+        })
+    }
+}
+
+/// Reads the rows of a bulk send file, either a JSON array of objects or CSV with a header
+/// row. Blank CSV lines and lines starting with # are skipped
+pub fn read_transfers(contents: &str, json: bool) -> Result<Vec<RawTransfer>, String> {
+    if json {
+        serde_json::from_str(contents).map_err(|e| format!("Invalid JSON {}", e))
+    } else {
+        parse_csv(contents)
+    }
+}
+
+/// Validates the rows of a bulk send file, every amount in the file is in base units or
+/// every amount is in display units, decimals holds the decimals of the tokens for the latter
+pub fn parse_transfers(
+    raw: Vec<RawTransfer>,
+    decimals: &HashMap<String, u8>,
+    base_units: bool,
+) -> Result<Vec<Transfer>, String> {
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, transfer)| {
+            let token_decimals = decimals.get(&transfer.token).copied();
+            transfer.parse(i + 1, token_decimals, base_units)
+        })
+        .collect()
+}
+
+/// Looks up the decimals of every token in the file when it's amounts are in display units,
+/// Cosmos denoms use their display units and ERC20s their decimals. Tokens that can't be
+/// looked up are left out so their amounts are rejected
+async fn get_decimals(
+    connections: &Connections,
+    raw: &[RawTransfer],
+    base_units: bool,
+) -> HashMap<String, u8> {
+    let mut out = HashMap::new();
+    if base_units {
+        return out;
+    }
+    let mut displays = match connections.contact.as_ref() {
+        Some(contact) => Some(TokenDisplays::new(contact).await),
+        None => None,
+    };
+    for transfer in raw.iter() {
+        if out.contains_key(&transfer.token) {
+            continue;
+        }
+        let decimals = if transfer.to_ethereum() {
+            match (displays.as_mut(), connections.grpc.clone()) {
+                (Some(displays), Some(mut grpc)) => displays
+                    .get(&mut grpc, connections.web3.as_ref(), &transfer.token)
+                    .await
+                    .map(|display| display.decimals),
+                _ => None,
+            }
+        } else {
+            match (connections.web3.as_ref(), transfer.token.parse()) {
+                (Some(web3), Ok(erc20)) => match web3.get_erc20_decimals(erc20, erc20).await {
+                    Ok(decimals) => decimals.to_string().parse().ok(),
+                    Err(_) => None,
+                },
+                _ => None,
+            }
+        };
+        if let Some(decimals) = decimals {
+            out.insert(transfer.token.clone(), decimals);
+        }
+    }
+    out
+}
+
+fn parse_csv(contents: &str) -> Result<Vec<RawTransfer>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    // column names are matched case insensitively
+    let header: csv::StringRecord = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header {}", e))?
+        .iter()
+        .map(|column| column.to_lowercase())
+        .collect();
+    reader
+        .records()
+        .map(|record| {
+            record
+                .and_then(|record| record.deserialize(Some(&header)))
+                .map_err(|e| format!("Invalid CSV {}", e))
+        })
+        .collect()
+}
+
+/// The outcome of one row, written to the report
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct TransferResult {
+    row: usize,
+    destination: String,
+    token: String,
+    amount: String,
+    bridge_fee: String,
+    /// sent, failed, unknown or not_sent
+    status: String,
+    /// The Cosmos txhash or Ethereum txid the transfer was sent in
+    txid: Option<String>,
+    /// The id of a transfer to Ethereum, used by `gbt client track`
+    transfer_id: Option<u64>,
+    error: Option<String>,
+}
+
+impl TransferResult {
+    fn new(transfer: &Transfer) -> Self {
+        let destination = match transfer.destination {
+            Destination::Ethereum(address) => address.to_string(),
+            Destination::Cosmos(address) => address.to_string(),
+        };
+        TransferResult {
+            row: transfer.row,
+            destination,
+            token: transfer.token.clone(),
+            amount: transfer.amount.to_string(),
+            bridge_fee: transfer.bridge_fee.to_string(),
+            status: "not_sent".to_string(),
+            txid: None,
+            transfer_id: None,
+            error: None,
+        }
+    }
+
+    fn sent(&mut self, txid: String, transfer_id: Option<u64>) {
+        self.status = "sent".to_string();
+        self.txid = Some(txid);
+        self.transfer_id = transfer_id;
+    }
+
+    fn failed(&mut self, error: String) {
+        self.status = "failed".to_string();
+        self.error = Some(error);
+    }
+
+    /// The transaction was broadcast but not seen executing, it may still execute
+    fn unknown(&mut self, txid: String, error: String) {
+        self.status = "unknown".to_string();
+        self.txid = Some(txid);
+        self.error = Some(error);
+    }
+
+    /// Rows that are sent or may still execute must not be sent again
+    fn is_done(&self) -> bool {
+        self.status == "sent" || self.status == "unknown"
+    }
+}
+
+/// Copies the rows of an earlier report that are done into results so they are skipped,
+/// returning how many were. The report must be for the same transfer file, read in the same
+/// units since the report holds base units
+fn resume(results: &mut [TransferResult], previous: Vec<TransferResult>) -> Result<usize, String> {
+    let mut skipped = 0;
+    for row in previous.into_iter().filter(TransferResult::is_done) {
+        let current = match row.row.checked_sub(1).and_then(|i| results.get_mut(i)) {
+            Some(v) => v,
+            None => {
+                return Err(format!(
+                    "The report has row {} which is not in the file",
+                    row.row
+                ))
+            }
+        };
+        if current.destination != row.destination
+            || current.token != row.token
+            || current.amount != row.amount
+            || current.bridge_fee != row.bridge_fee
+        {
+            return Err(format!(
+                "Row {} of the report does not match the transfer file",
+                row.row
+            ));
+        }
+        *current = row;
+        skipped += 1;
+    }
+    Ok(skipped)
+}
+
+pub async fn bulk_send(
+    args: BulkSendOpts,
+    address_prefix: String,
+    config: &GravityBridgeToolsConfig,
+) {
+    let contents = match fs::read_to_string(&args.file) {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::Io,
+                format!("Failed to read {} {:?}", args.file.display(), e),
+            );
+        }
+    };
+    let raw = match read_transfers(&contents, is_json_file(&args.file)) {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Invalid transfer file {}", e),
+            );
+        }
+    };
+    if raw.is_empty() {
+        fail(
+            ErrorCode::InvalidInput,
+            format!("{} has no transfers", args.file.display()),
+        );
+    }
+    let report_path = args
+        .report
+        .clone()
+        .unwrap_or_else(|| default_report_path(&args.file));
+
+    let to_eth_count = raw.iter().filter(|t| t.to_ethereum()).count();
+    let to_cosmos_count = raw.len() - to_eth_count;
+    let cosmos_key = match args
+        .cosmos_phrase
+        .map(|phrase| parse_cosmos_phrase(&phrase, &config.keys))
+    {
+        None if to_eth_count > 0 => fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "{} transfers to Ethereum need a --cosmos-phrase to send from",
+                to_eth_count
+            ),
+        ),
+        // a key for a direction with no transfers is not used
+        key => key.filter(|_| to_eth_count > 0),
+    };
+    let ethereum_key = match args.ethereum_key {
+        None if to_cosmos_count > 0 => fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "{} transfers to Cosmos need an --ethereum-key to send from",
+                to_cosmos_count
+            ),
+        ),
+        key => key.filter(|_| to_cosmos_count > 0),
+    };
+    let gravity_contract = args
+        .gravity_contract_address
+        .or_else(|| parse_config_gravity_address(&config.connection));
+    if gravity_contract.is_none() && to_cosmos_count > 0 {
+        fail(
+            ErrorCode::InvalidInput,
+            "Transfers to Cosmos need --gravity-contract-address or gravity_contract_address in the config",
+        );
+    }
+    let fee = cosmos_key.map(|_| get_fee_settings(args.gas_price.clone(), &config.fees));
+
+    let cosmos_grpc = if to_eth_count == 0 {
+        None
+    } else {
+        Some(args.cosmos_grpc.clone())
+    };
+    let ethereum_rpc = if to_cosmos_count == 0 {
+        None
+    } else {
+        Some(args.ethereum_rpc.clone())
+    };
+    let connections =
+        create_rpc_connections(address_prefix, cosmos_grpc, ethereum_rpc, TIMEOUT).await;
+
+    let decimals = get_decimals(&connections, &raw, args.base_units).await;
+    let transfers = match parse_transfers(raw, &decimals, args.base_units) {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Invalid transfer file {}", e),
+            );
+        }
+    };
+    let mut results: Vec<TransferResult> = transfers.iter().map(TransferResult::new).collect();
+    if args.resume {
+        let previous = match read_report(&report_path) {
+            Ok(v) => v,
+            Err(e) => {
+                fail(
+                    ErrorCode::Io,
+                    format!(
+                        "Failed to read the report {} to resume from {}",
+                        report_path.display(),
+                        e
+                    ),
+                );
+            }
+        };
+        match resume(&mut results, previous) {
+            Ok(skipped) => info!("Skipping {} rows already sent or unknown", skipped),
+            Err(e) => fail(ErrorCode::InvalidInput, e),
+        }
+    }
+    let to_eth: Vec<usize> = (0..transfers.len())
+        .filter(|i| matches!(transfers[*i].destination, Destination::Ethereum(_)))
+        .filter(|i| !results[*i].is_done())
+        .collect();
+    let to_cosmos: Vec<usize> = (0..transfers.len())
+        .filter(|i| matches!(transfers[*i].destination, Destination::Cosmos(_)))
+        .filter(|i| !results[*i].is_done())
+        .collect();
+
+    // everything is checked up front so a bad file does not leave a partial payout behind
+    if let (Some(key), Some(fee)) = (cosmos_key, fee.as_ref()) {
+        let contact = require_connection(connections.contact.as_ref(), "Cosmos gRPC");
+        let mut grpc = require_connection(connections.grpc.clone(), "Cosmos gRPC");
+        check_cosmos_sender(contact, &mut grpc, key, fee, &transfers, &to_eth).await;
+    }
+    if let Some(key) = ethereum_key {
+        let web3 = require_connection(connections.web3.as_ref(), "Ethereum RPC");
+        check_ethereum_sender(web3, key, &transfers, &to_cosmos).await;
+    }
+
+    let pending: Vec<usize> = to_eth.iter().chain(to_cosmos.iter()).copied().collect();
+    let totals: Vec<String> = get_totals(&transfers, &pending)
+        .iter()
+        .map(|(token, total)| format!("{} {}", total, token))
+        .collect();
+    let question = format!(
+        "Send {} transfers to Ethereum and {} transfers to Cosmos, {} in total including bridge fees?",
+        to_eth.len(),
+        to_cosmos.len(),
+        totals.join(", ")
+    );
+    if !args.yes && !confirm_prompt(&question) {
+        info!("Nothing was sent");
+        print_result(&json!({ "sent": 0, "failed": 0, "report": null }));
+        return;
+    }
+
+    let mut batch_requests = Vec::new();
+    if let (Some(key), Some(fee)) = (cosmos_key, fee.as_ref()) {
+        let contact = require_connection(connections.contact.as_ref(), "Cosmos gRPC");
+        send_from_cosmos(
+            contact,
+            key,
+            fee,
+            &transfers,
+            &to_eth,
+            args.max_msgs_per_tx.max(1),
+            args.max_gas_per_tx,
+            &mut results,
+        )
+        .await;
+        if !args.no_batch {
+            batch_requests =
+                request_batches(contact, key, fee, &transfers, &to_eth, &results).await;
+        }
+    }
+    if let (Some(key), Some(gravity_contract)) = (ethereum_key, gravity_contract) {
+        let web3 = require_connection(connections.web3.as_ref(), "Ethereum RPC");
+        send_from_ethereum(
+            web3,
+            key,
+            gravity_contract,
+            &transfers,
+            &to_cosmos,
+            &mut results,
+        )
+        .await;
+    }
+
+    if let Err(e) = write_report(&report_path, &results) {
+        fail(
+            ErrorCode::Io,
+            format!("Failed to write the report {} {}", report_path.display(), e),
+        );
+    }
+    let sent = results.iter().filter(|r| r.status == "sent").count();
+    let unknown = results.iter().filter(|r| r.status == "unknown").count();
+    info!(
+        "Sent {} of {} transfers, results written to {}",
+        sent,
+        results.len(),
+        report_path.display()
+    );
+    if sent < results.len() {
+        fail(
+            ErrorCode::Transaction,
+            format!(
+                "{} of {} transfers were not sent, {} of them may still execute and are skipped by --resume, see {} for details",
+                results.len() - sent,
+                results.len(),
+                unknown,
+                report_path.display()
+            ),
+        );
+    }
+    print_result(&json!({
+        "sent": sent,
+        "failed": 0,
+        "report": report_path.display().to_string(),
+        "batch_requests": batch_requests,
+        "results": results,
+    }));
+}
+
+/// Checks every denom can be bridged and the sender holds the amount plus bridge fee of every
+/// transfer to Ethereum
+async fn check_cosmos_sender(
+    contact: &Contact,
+    grpc: &mut GravityQueryClient<Channel>,
+    key: CosmosPrivateKey,
+    fee: &FeeSettings,
+    transfers: &[Transfer],
+    indices: &[usize],
+) {
+    let address = match key.to_address(&contact.get_prefix()) {
+        Ok(address) => address,
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!("Invalid --cosmos-phrase {:?}", e),
+        ),
+    };
+    let balances = match contact.get_balances(address).await {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get the balances of {} {:?}", address, e),
+            );
+        }
+    };
+    for (denom, total) in get_totals(transfers, indices) {
+        let res = grpc
+            .denom_to_erc20(QueryDenomToErc20Request {
+                denom: denom.clone(),
+            })
+            .await;
+        if res.is_err() {
+            fail(
+                ErrorCode::InvalidInput,
+                format!(
+                    "Asset {} has no ERC20 representation, you may need to deploy an ERC20 for it!",
+                    denom
+                ),
+            );
+        }
+        let balance = balances
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .map(|coin| coin.amount.clone())
+            .unwrap_or_else(|| 0u8.into());
+        if total > balance {
+            fail(
+                ErrorCode::InsufficientFunds,
+                format!(
+                    "Transfers to Ethereum need {}{} but {} only holds {}{}",
+                    total, denom, address, balance, denom
+                ),
+            );
+        }
+    }
+    if let Err(e) = check_for_gas_price(&fee.gas_price, address, contact).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
+}
+
+/// Checks the sender holds some ETH for gas and enough of every ERC20 sent to Cosmos
+async fn check_ethereum_sender(
+    web3: &Web3,
+    key: EthPrivateKey,
+    transfers: &[Transfer],
+    indices: &[usize],
+) {
+    let address = match key.to_public_key() {
+        Ok(address) => address,
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!("Invalid --ethereum-key {:?}", e),
+        ),
+    };
+    if let Err(e) = check_for_eth(address, web3).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
+    for (token, total) in get_totals(transfers, indices) {
+        let erc20: EthAddress = token.parse().unwrap();
+        let balance = match web3.get_erc20_balance(erc20, address).await {
+            Ok(v) => v,
+            Err(e) => {
+                fail(
+                    ErrorCode::Query,
+                    format!("Failed to get the {} balance of {} {:?}", erc20, address, e),
+                );
+            }
+        };
+        if total > balance {
+            fail(
+                ErrorCode::InsufficientFunds,
+                format!(
+                    "Transfers to Cosmos need {} of {} but {} only holds {}",
+                    total, erc20, address, balance
+                ),
+            );
+        }
+    }
+}
+
+/// How a transaction of transfers to Ethereum ended
+enum ChunkOutcome {
+    /// Holds the txhash and the transfer id of every transfer
+    Sent(String, Vec<Option<u64>>),
+    Failed(String),
+    /// Broadcast but not seen executing, holds the txhash and the error
+    Unknown(String, String),
+}
+
+/// Sends the transfers to Ethereum in as few transactions as possible, each holds at most
+/// `max_msgs` transfers and is kept under `max_gas` by simulating it first. A failed
+/// transaction marks its rows as failed and the remaining transfers are still sent
+#[allow(clippy::too_many_arguments)]
+async fn send_from_cosmos(
+    contact: &Contact,
+    key: CosmosPrivateKey,
+    fee: &FeeSettings,
+    transfers: &[Transfer],
+    indices: &[usize],
+    max_msgs: usize,
+    max_gas: u64,
+    results: &mut [TransferResult],
+) {
+    let mut remaining = indices;
+    let mut size = max_msgs;
+    while !remaining.is_empty() {
+        let chunk = &remaining[..size.min(remaining.len())];
+        let msgs = send_to_eth_msgs(transfers, chunk);
+        let outcome = match estimate_send_to_eth_bulk_gas(key, msgs.clone(), fee, contact).await {
+            Ok(gas) if gas > max_gas && chunk.len() > 1 => {
+                size = smaller_chunk(chunk.len(), gas, max_gas);
+                info!(
+                    "{} transfers need {} gas, more than {}, trying {} per transaction",
+                    chunk.len(),
+                    gas,
+                    max_gas,
+                    size
+                );
+                continue;
+            }
+            Ok(_) => send_chunk(contact, key, fee, msgs).await,
+            Err(e) => ChunkOutcome::Failed(format!("Failed to simulate the transaction {:?}", e)),
+        };
+        match outcome {
+            ChunkOutcome::Sent(txhash, ids) => {
+                info!(
+                    "Sent {} transfers to Ethereum with txid {}",
+                    chunk.len(),
+                    txhash
+                );
+                for (i, id) in chunk.iter().zip(ids) {
+                    results[*i].sent(txhash.clone(), id);
+                }
+            }
+            ChunkOutcome::Failed(error) => {
+                warn!(
+                    "Failed to send {} transfers to Ethereum {}",
+                    chunk.len(),
+                    error
+                );
+                for i in chunk {
+                    results[*i].failed(error.clone());
+                }
+            }
+            ChunkOutcome::Unknown(txhash, error) => {
+                warn!(
+                    "Transaction {} with {} transfers to Ethereum was broadcast but not seen executing {}, check it before sending these rows again",
+                    txhash,
+                    chunk.len(),
+                    error
+                );
+                for i in chunk {
+                    results[*i].unknown(txhash.clone(), error.clone());
+                }
+            }
+        }
+        remaining = &remaining[chunk.len()..];
+    }
+}
+
+/// Broadcasts one transaction of transfers to Ethereum and waits for it to execute
+async fn send_chunk(
+    contact: &Contact,
+    key: CosmosPrivateKey,
+    fee: &FeeSettings,
+    msgs: Vec<(EthAddress, Coin, Coin)>,
+) -> ChunkOutcome {
+    let count = msgs.len();
+    let res = match send_to_eth_bulk(key, msgs, fee, contact, None).await {
+        Ok(res) => res,
+        Err(e) => return ChunkOutcome::Failed(format!("{:?}", e)),
+    };
+    // a sync broadcast only returns an error code if the transaction never entered the mempool
+    if res.code != 0 {
+        return ChunkOutcome::Failed(format!("Transaction {} failed {}", res.txhash, res.raw_log));
+    }
+    let txhash = res.txhash.clone();
+    match contact.wait_for_tx(res, TIMEOUT).await {
+        Ok(res) if res.code == 0 => {
+            let ids = get_send_to_eth_ids(&res, count);
+            ChunkOutcome::Sent(res.txhash, ids)
+        }
+        Ok(res) => {
+            ChunkOutcome::Failed(format!("Transaction {} failed {}", res.txhash, res.raw_log))
+        }
+        // the transaction may still execute, sending the rows again could pay them twice
+        Err(e) => ChunkOutcome::Unknown(txhash, format!("{:?}", e)),
+    }
+}
+
+/// The (destination, amount, bridge fee) of the given transfers to Ethereum
+fn send_to_eth_msgs(transfers: &[Transfer], indices: &[usize]) -> Vec<(EthAddress, Coin, Coin)> {
+    indices
+        .iter()
+        .map(|i| {
+            let transfer = &transfers[*i];
+            let destination = match transfer.destination {
+                Destination::Ethereum(address) => address,
+                Destination::Cosmos(_) => unreachable!(),
+            };
+            let amount = Coin {
+                denom: transfer.token.clone(),
+                amount: transfer.amount.clone(),
+            };
+            let bridge_fee = Coin {
+                denom: transfer.token.clone(),
+                amount: transfer.bridge_fee.clone(),
+            };
+            (destination, amount, bridge_fee)
+        })
+        .collect()
+}
+
+/// The number of transfers to try when `len` transfers need more than `max_gas`, scaled
+/// down by the estimate and always smaller so that splitting ends
+fn smaller_chunk(len: usize, gas: u64, max_gas: u64) -> usize {
+    let scaled = (len as u128 * max_gas as u128 / gas as u128) as usize;
+    scaled.min(len - 1).max(1)
+}
+
+/// Requests one batch for every denom that had a transfer sent, returning the txhashes
+async fn request_batches(
+    contact: &Contact,
+    key: CosmosPrivateKey,
+    fee: &FeeSettings,
+    transfers: &[Transfer],
+    indices: &[usize],
+    results: &[TransferResult],
+) -> Vec<String> {
+    let sent: Vec<usize> = indices
+        .iter()
+        .copied()
+        .filter(|i| results[*i].status == "sent")
+        .collect();
+    let mut txhashes = Vec::new();
+    for denom in get_totals(transfers, &sent).keys() {
+        info!("Requesting a {} batch to push the transfers along", denom);
+        match send_request_batch(key, denom.clone(), fee, contact, Some(TIMEOUT)).await {
+            Ok(res) => txhashes.push(res.txhash),
+            // the transfers are already in the pool, anyone can request the batch later
+            Err(e) => warn!("Failed to request a {} batch {:?}", denom, e),
+        }
+    }
+    txhashes
+}
+
+/// Sends the transfers to Cosmos one after another, approving each ERC20 once up front
+async fn send_from_ethereum(
+    web3: &Web3,
+    key: EthPrivateKey,
+    gravity_contract: EthAddress,
+    transfers: &[Transfer],
+    indices: &[usize],
+    results: &mut [TransferResult],
+) {
+    let address = key.to_public_key().unwrap();
+    for token in get_totals(transfers, indices).keys() {
+        let erc20: EthAddress = token.parse().unwrap();
+        let rows = indices
+            .iter()
+            .copied()
+            .filter(|i| transfers[*i].token == *token);
+        if let Err(e) = approve(web3, key, erc20, gravity_contract).await {
+            warn!("Failed to approve {} {}", erc20, e);
+            for i in rows {
+                results[i].failed(format!("ERC20 approval failed {}", e));
+            }
+            continue;
+        }
+        for i in rows {
+            let transfer = &transfers[i];
+            let destination = match transfer.destination {
+                Destination::Cosmos(address) => address,
+                Destination::Ethereum(_) => unreachable!(),
+            };
+            let res = send_to_cosmos(
+                erc20,
+                gravity_contract,
+                transfer.amount.clone(),
+                destination,
+                key,
+                Some(TIMEOUT),
+                web3,
+                vec![],
+            )
+            .await;
+            match res {
+                Ok(txid) => {
+                    info!(
+                        "Sent row {} from {} to {} with txid {:#066x}",
+                        transfer.row, address, destination, txid
+                    );
+                    results[i].sent(format!("{:#066x}", txid), None);
+                }
+                Err(e) => {
+                    warn!("Failed to send row {} to Cosmos {:?}", transfer.row, e);
+                    results[i].failed(format!("{:?}", e));
+                }
+            }
+        }
+    }
+}
+
+/// Approves the Gravity contract to spend the sender's ERC20 if it is not already approved
+async fn approve(
+    web3: &Web3,
+    key: EthPrivateKey,
+    erc20: EthAddress,
+    gravity_contract: EthAddress,
+) -> Result<(), String> {
+    let address = key.to_public_key().unwrap();
+    let approved = web3
+        .check_erc20_approved(erc20, address, gravity_contract)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    if approved {
+        return Ok(());
+    }
+    let txid = web3
+        .approve_erc20_transfers(erc20, key, gravity_contract, None, vec![])
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    web3.wait_for_transaction(txid.clone(), TIMEOUT, None)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    info!(
+        "Approved {} for the Gravity contract with txid {:#066x}",
+        erc20, txid
+    );
+    Ok(())
+}
+
+/// Sums the amount and bridge fee of the given transfers by token
+fn get_totals(transfers: &[Transfer], indices: &[usize]) -> BTreeMap<String, Uint256> {
+    let mut totals: BTreeMap<String, Uint256> = BTreeMap::new();
+    for i in indices {
+        let transfer = &transfers[*i];
+        let total = totals
+            .entry(transfer.token.clone())
+            .or_insert_with(|| 0u8.into());
+        *total = total.clone() + transfer.amount.clone() + transfer.bridge_fee.clone();
+    }
+    totals
+}
+
+fn is_json_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("json")
+}
+
+/// payouts.csv becomes payouts.report.csv next to the input file
+fn default_report_path(file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("transfers");
+    let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("csv");
+    file.with_file_name(format!("{}.report.{}", stem, extension))
+}
+
+fn write_report(path: &Path, results: &[TransferResult]) -> Result<(), String> {
+    let contents = if is_json_file(path) {
+        serde_json::to_vec_pretty(results).unwrap()
+    } else {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for result in results {
+            writer.serialize(result).map_err(|e| e.to_string())?;
+        }
+        writer.into_inner().map_err(|e| e.to_string())?
+    };
+    fs::write(path, contents).map_err(|e| format!("{:?}", e))
+}
+
+fn read_report(path: &Path) -> Result<Vec<TransferResult>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{:?}", e))?;
+    if is_json_file(path) {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    } else {
+        csv::Reader::from_reader(contents.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[test]
+fn test_parse_transfers() {
+    let csv = "destination,amount,token,bridge_fee
+# payouts for March
+0xD50c0953a99325d01cca655E57070F1be4983b6b,1000,ugraviton,5
+
+cosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4,20,0xD50c0953a99325d01cca655E57070F1be4983b6b,
+";
+    let parse = |contents: &str, json: bool| {
+        parse_transfers(read_transfers(contents, json)?, &HashMap::new(), true)
+    };
+    let transfers = parse(csv, false).unwrap();
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(
+        transfers[0].destination,
+        Destination::Ethereum(
+            "0xD50c0953a99325d01cca655E57070F1be4983b6b"
+                .parse()
+                .unwrap()
+        )
+    );
+    assert_eq!(transfers[0].amount, 1000u64.into());
+    assert_eq!(transfers[0].bridge_fee, 5u64.into());
+    assert_eq!(transfers[1].row, 2);
+    assert_eq!(transfers[1].bridge_fee, 0u64.into());
+
+    let json = r#"[{"destination": "0xD50c0953a99325d01cca655E57070F1be4983b6b", "amount": "7", "token": "ugraviton"}]"#;
+    let transfers = parse(json, true).unwrap();
+    assert_eq!(transfers[0].bridge_fee, 1u64.into());
+
+    // transfers to Cosmos send an ERC20 and can not pay a bridge fee
+    assert!(parse(
+        "destination,amount,token\ncosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4,1,ugraviton",
+        false
+    )
+    .is_err());
+    assert!(parse("destination,amount,token,bridge_fee\ncosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4,1,0xD50c0953a99325d01cca655E57070F1be4983b6b,1", false).is_err());
+    assert!(parse(
+        "destination,token\n0xD50c0953a99325d01cca655E57070F1be4983b6b,ugraviton",
+        false
+    )
+    .is_err());
+    // display units need the decimals of the token and base units are whole numbers
+    let decimal = "destination,amount,token,bridge_fee\n0xD50c0953a99325d01cca655E57070F1be4983b6b,1.5,ugraviton,0.01";
+    assert!(parse(decimal, false).is_err());
+    let raw = read_transfers(decimal, false).unwrap();
+    assert!(parse_transfers(raw.clone(), &HashMap::new(), false).is_err());
+    let mut decimals = HashMap::new();
+    decimals.insert("ugraviton".to_string(), 6);
+    let transfers = parse_transfers(raw, &decimals, false).unwrap();
+    assert_eq!(transfers[0].amount, 1500000u64.into());
+    assert_eq!(transfers[0].bridge_fee, 10000u64.into());
+    // every row of a file is read in the same units, with or without a decimal point
+    let mixed = "destination,amount,token\n0xD50c0953a99325d01cca655E57070F1be4983b6b,1,ugraviton\n0xD50c0953a99325d01cca655E57070F1be4983b6b,1.0,ugraviton";
+    let transfers =
+        parse_transfers(read_transfers(mixed, false).unwrap(), &decimals, false).unwrap();
+    assert_eq!(transfers[0].amount, transfers[1].amount);
+    assert!(parse(mixed, false).is_err());
+    // quoted fields and header names in any case
+    let quoted = "Destination,\"Amount\",token\n\"0xD50c0953a99325d01cca655E57070F1be4983b6b\",\"1000\",ugraviton";
+    assert_eq!(parse(quoted, false).unwrap()[0].amount, 1000u64.into());
+}
+
+#[test]
+fn test_default_report_path() {
+    assert_eq!(
+        default_report_path(Path::new("/tmp/payouts.csv")),
+        PathBuf::from("/tmp/payouts.report.csv")
+    );
+    assert_eq!(
+        default_report_path(Path::new("payouts.json")),
+        PathBuf::from("payouts.report.json")
+    );
+}
+
+#[test]
+fn test_resume() {
+    let csv = "destination,amount,token
+0xD50c0953a99325d01cca655E57070F1be4983b6b,1000,ugraviton
+0xD50c0953a99325d01cca655E57070F1be4983b6b,2000,ugraviton
+0xD50c0953a99325d01cca655E57070F1be4983b6b,3000,ugraviton";
+    let transfers =
+        parse_transfers(read_transfers(csv, false).unwrap(), &HashMap::new(), true).unwrap();
+    let mut results: Vec<TransferResult> = transfers.iter().map(TransferResult::new).collect();
+    results[0].sent("AB12".to_string(), Some(7));
+    results[1].unknown("CD34".to_string(), "Timed out, with a comma".to_string());
+    results[2].failed("out of gas".to_string());
+
+    // the report is read back the same as it was written
+    let path = std::env::temp_dir().join(format!("bulk_send_{}.report.csv", std::process::id()));
+    write_report(&path, &results).unwrap();
+    let previous = read_report(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(previous, results);
+
+    // sent and unknown rows are skipped, the failed row is sent again
+    let mut resumed: Vec<TransferResult> = transfers.iter().map(TransferResult::new).collect();
+    assert_eq!(resume(&mut resumed, previous.clone()), Ok(2));
+    assert_eq!(resumed[0], results[0]);
+    assert_eq!(resumed[1], results[1]);
+    assert!(!resumed[2].is_done());
+
+    // a report for a different file is refused
+    let mut other: Vec<TransferResult> = transfers.iter().map(TransferResult::new).collect();
+    other[1].amount = "5".to_string();
+    assert!(resume(&mut other, previous.clone()).is_err());
+    let mut other: Vec<TransferResult> = transfers.iter().map(TransferResult::new).collect();
+    other[0].bridge_fee = "5".to_string();
+    assert!(resume(&mut other, previous.clone()).is_err());
+
+    // the same file read in display units has different amounts and is refused
+    let mut decimals = HashMap::new();
+    decimals.insert("ugraviton".to_string(), 6);
+    let display = parse_transfers(read_transfers(csv, false).unwrap(), &decimals, false).unwrap();
+    let mut other: Vec<TransferResult> = display.iter().map(TransferResult::new).collect();
+    assert!(resume(&mut other, previous).is_err());
+}
+
+#[test]
+fn test_smaller_chunk() {
+    assert_eq!(smaller_chunk(50, 4_000_000, 3_000_000), 37);
+    // always shrinks even when the estimate is barely over
+    assert_eq!(smaller_chunk(10, 3_000_001, 3_000_000), 9);
+    assert_eq!(smaller_chunk(2, 100_000_000, 3_000_000), 1);
+}
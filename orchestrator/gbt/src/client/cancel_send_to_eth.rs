@@ -0,0 +1,204 @@
+use crate::args::CancelSendToEthOpts;
+use crate::output::{fail, print_result, ErrorCode};
+use crate::tx::{generate_only, get_sender};
+use crate::utils::{
+    confirm_prompt, get_fee_settings, print_coin, TokenDisplay, TokenDisplays, TIMEOUT,
+};
+use clarity::Address as EthAddress;
+use clarity::Uint256;
+use cosmos_gravity::query::{get_erc20_to_denom, get_pending_send_to_eth};
+use cosmos_gravity::send::{build_cancel_send_to_eth_msg, cancel_sends_to_eth};
+use deep_space::Contact;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::types::{BatchTransaction, GravityBridgeToolsConfig};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use tonic::transport::Channel;
+
+pub async fn cancel_send_to_eth(
+    args: CancelSendToEthOpts,
+    address_prefix: String,
+    config: &GravityBridgeToolsConfig,
+) {
+    let (cosmos_key, cosmos_address) = get_sender(
+        args.cosmos_phrase,
+        &args.generate,
+        &address_prefix,
+        &config.keys,
+    );
+
+    let connections =
+        create_rpc_connections(address_prefix, Some(args.cosmos_grpc), None, TIMEOUT).await;
+    let contact = connections.contact.unwrap();
+    let mut grpc = connections.grpc.unwrap();
+
+    let pending = match get_pending_send_to_eth(&mut grpc, cosmos_address).await {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get pending transfers {:?}", e),
+            );
+        }
+    };
+    let mut unbatched = Vec::new();
+    for tx in pending.unbatched_transfers {
+        match BatchTransaction::try_from(tx) {
+            Ok(tx) => unbatched.push(tx),
+            Err(e) => warn!("Skipping unreadable transfer {:?}", e),
+        }
+    }
+    let denoms = get_denoms(&contact, &mut grpc, &unbatched).await;
+    let unbatched_json: Vec<Value> = unbatched
+        .iter()
+        .map(|tx| {
+            json!({
+                "id": tx.id,
+                "denom": denoms[&tx.erc20_token.token_contract_address].0,
+                "amount": tx.erc20_token.amount.to_string(),
+                "bridge_fee": tx.erc20_fee.amount.to_string(),
+                "destination": tx.destination.to_string(),
+            })
+        })
+        .collect();
+    let finish = |cancelled: Vec<Value>| {
+        print_result(&json!({
+            "sender": cosmos_address.to_string(),
+            "unbatched": unbatched_json,
+            "cancelled": cancelled,
+        }))
+    };
+
+    if unbatched.is_empty() {
+        info!("{} has no unbatched transfers to Ethereum", cosmos_address);
+    } else {
+        info!("Unbatched transfers from {}", cosmos_address);
+        for tx in unbatched.iter() {
+            let (denom, display) = &denoms[&tx.erc20_token.token_contract_address];
+            info!(
+                "id {}: {} to {} with a bridge fee of {}",
+                tx.id,
+                print_coin(&tx.erc20_token.amount, denom, display),
+                tx.destination,
+                print_coin(&tx.erc20_fee.amount, denom, display)
+            );
+        }
+    }
+    if !pending.transfers_in_batches.is_empty() {
+        info!(
+            "{} more transfers are already in batches and can no longer be cancelled",
+            pending.transfers_in_batches.len()
+        );
+    }
+
+    let to_cancel: Vec<&BatchTransaction> = if args.all {
+        unbatched.iter().collect()
+    } else if !args.id.is_empty() {
+        let mut out = Vec::new();
+        for id in args.id.iter() {
+            match unbatched.iter().find(|tx| tx.id == *id) {
+                Some(tx) => out.push(tx),
+                None => {
+                    fail(ErrorCode::InvalidInput, format!("Transfer {} is not an unbatched transfer from {}, it may already be in a batch",
+                        id, cosmos_address));
+                }
+            }
+        }
+        out
+    } else {
+        finish(Vec::new());
+        return;
+    };
+    if to_cancel.is_empty() {
+        finish(Vec::new());
+        return;
+    }
+
+    // the amount and the bridge fee are both refunded
+    let mut refund: HashMap<EthAddress, Uint256> = HashMap::new();
+    for tx in to_cancel.iter() {
+        let total = refund
+            .entry(tx.erc20_token.token_contract_address)
+            .or_insert_with(|| 0u8.into());
+        *total = total.clone() + tx.erc20_token.amount.clone() + tx.erc20_fee.amount.clone();
+    }
+    let refund: Vec<String> = refund
+        .iter()
+        .map(|(erc20, amount)| {
+            let (denom, display) = &denoms[erc20];
+            print_coin(amount, denom, display)
+        })
+        .collect();
+    let question = format!(
+        "Cancel {} transfers and refund {} to {}?",
+        to_cancel.len(),
+        refund.join(", "),
+        cosmos_address
+    );
+    let fee = get_fee_settings(args.gas_price, &config.fees);
+    let cosmos_key = match cosmos_key {
+        Some(key) => key,
+        None => {
+            // every cancel goes into the one generated transaction
+            let msgs = to_cancel
+                .iter()
+                .map(|tx| build_cancel_send_to_eth_msg(cosmos_address, tx.id))
+                .collect();
+            generate_only(&contact, cosmos_address, msgs, &args.generate, &fee).await;
+            return;
+        }
+    };
+    if !args.yes && !confirm_prompt(&question) {
+        info!("Nothing was cancelled");
+        finish(Vec::new());
+        return;
+    }
+
+    // every cancel goes into one transaction so they either all succeed or none do
+    let ids: Vec<u64> = to_cancel.iter().map(|tx| tx.id).collect();
+    match cancel_sends_to_eth(cosmos_key, &fee, &contact, &ids).await {
+        Ok(res) => {
+            info!("Cancelled transfers {:?} with txid {}", ids, res.txhash);
+            let cancelled = ids
+                .iter()
+                .map(|id| json!({ "id": id, "txhash": res.txhash }))
+                .collect();
+            finish(cancelled);
+        }
+        Err(e) => {
+            fail(
+                ErrorCode::Transaction,
+                format!(
+                    "Failed to cancel transfers {:?}, they are cancelled together so none or all may have gone through {:?}",
+                    ids, e
+                ),
+            );
+        }
+    }
+}
+
+/// Looks up the Cosmos denom and how to display it for every token in the given transfers,
+/// falling back to the ERC20 address if the lookup fails
+async fn get_denoms(
+    contact: &Contact,
+    grpc: &mut GravityQueryClient<Channel>,
+    transfers: &[BatchTransaction],
+) -> HashMap<EthAddress, (String, Option<TokenDisplay>)> {
+    let mut out = HashMap::new();
+    let mut displays = TokenDisplays::new(contact).await;
+    for tx in transfers {
+        let erc20 = tx.erc20_token.token_contract_address;
+        if out.contains_key(&erc20) {
+            continue;
+        }
+        let denom = match get_erc20_to_denom(grpc, erc20).await {
+            Ok(res) => res.denom,
+            Err(_) => erc20.to_string(),
+        };
+        let display = displays.get(grpc, None, &denom).await;
+        out.insert(erc20, (denom, display));
+    }
+    out
+}
@@ -1,57 +1,151 @@
-use crate::utils::print_eth;
+use crate::args::CosmosToEthOpts;
+use crate::client::track::{get_send_to_eth_id, track_transfer};
+use crate::output::{fail, print_result, ErrorCode};
+use crate::tx::{generate_only, get_sender};
 use crate::utils::TIMEOUT;
-use crate::{args::CosmosToEthOpts, utils::print_atom};
-use cosmos_gravity::send::{send_request_batch, send_to_eth};
+use crate::utils::{
+    format_amount, get_fee_settings_or_fees, parse_token_amount, print_coin, TokenDisplays,
+};
+use clarity::Address as EthAddress;
+use cosmos_gravity::send::{
+    build_request_batch_msg, build_send_to_eth_msg, send_request_batch, send_to_eth,
+};
 use deep_space::Coin;
 use gravity_proto::gravity::QueryDenomToErc20Request;
-use gravity_utils::connection_prep::{check_for_fee, create_rpc_connections};
-use std::process::exit;
+use gravity_utils::connection_prep::{check_for_fee, check_for_gas_price, create_rpc_connections};
+use gravity_utils::types::GravityBridgeToolsConfig;
+use relayer::fee_estimator::estimate_bridge_fee;
+use serde_json::json;
 
-pub async fn cosmos_to_eth(args: CosmosToEthOpts, address_prefix: String) {
-    let cosmos_key = args.cosmos_phrase;
-    let gravity_coin = args.amount;
-    let fee = args.fees;
+pub async fn cosmos_to_eth(
+    args: CosmosToEthOpts,
+    address_prefix: String,
+    config: &GravityBridgeToolsConfig,
+) {
+    let (cosmos_key, cosmos_address) = get_sender(
+        args.cosmos_phrase,
+        &args.generate,
+        &address_prefix,
+        &config.keys,
+    );
+    let denom = args.amount.denom.clone();
+    let fee = get_fee_settings_or_fees(args.gas_price, args.fees, &config.fees);
     let cosmos_grpc = args.cosmos_grpc;
     let eth_dest = args.eth_destination;
     let no_batch = args.no_batch;
-
-    let cosmos_address = cosmos_key.to_address(&address_prefix).unwrap();
-
-    let is_cosmos_originated = gravity_coin.denom.starts_with("gravity");
+    let wait = args.wait;
+    let fee_target = args.fee_target;
+    let base_units = args.base_units;
+    if wait && cosmos_key.is_none() {
+        fail(
+            ErrorCode::InvalidInput,
+            "--wait can not be used with --generate-only",
+        );
+    }
 
     info!("Sending from Cosmos address {}", cosmos_address);
+    let ethereum_rpc = if wait || fee_target.is_some() {
+        Some(args.ethereum_rpc)
+    } else {
+        None
+    };
     let connections =
-        create_rpc_connections(address_prefix, Some(cosmos_grpc), None, TIMEOUT).await;
+        create_rpc_connections(address_prefix, Some(cosmos_grpc), ethereum_rpc, TIMEOUT).await;
     let contact = connections.contact.unwrap();
     let mut grpc = connections.grpc.unwrap();
 
     let res = grpc
         .denom_to_erc20(QueryDenomToErc20Request {
-            denom: gravity_coin.denom.clone(),
+            denom: denom.clone(),
         })
         .await;
-    match res {
-        Ok(val) => info!(
-            "Asset {} has ERC20 representation {}",
-            gravity_coin.denom,
-            val.into_inner().erc20
-        ),
-        Err(_e) => {
-            info!(
-                "Asset {} has no ERC20 representation, you may need to deploy an ERC20 for it!",
-                gravity_coin.denom
-            );
-            exit(1);
+    let erc20: EthAddress = match res {
+        Ok(val) => {
+            let erc20 = val.into_inner().erc20;
+            info!("Asset {} has ERC20 representation {}", denom, erc20);
+            match erc20.parse() {
+                Ok(erc20) => erc20,
+                Err(e) => fail(
+                    ErrorCode::Query,
+                    format!("Invalid ERC20 address {} {:?}", erc20, e),
+                ),
+            }
         }
-    }
+        Err(_e) => fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "Asset {} has no ERC20 representation, you may need to deploy an ERC20 for it!",
+                denom
+            ),
+        ),
+    };
 
+    let display = TokenDisplays::new(&contact)
+        .await
+        .get(&mut grpc, connections.web3.as_ref(), &denom)
+        .await;
+    let decimals = display.as_ref().map(|d| d.decimals);
+
+    let gravity_coin = match parse_token_amount(&args.amount.amount, decimals, base_units) {
+        Ok(amount) => Coin {
+            denom: denom.clone(),
+            amount,
+        },
+        Err(e) => fail(ErrorCode::InvalidInput, format!("Invalid --amount {}", e)),
+    };
     let amount = gravity_coin.clone();
+    let bridge_fee_amount = match (args.bridge_fee, fee_target) {
+        (Some(fee), _) => match parse_token_amount(&fee, decimals, base_units) {
+            Ok(fee) => fee,
+            Err(e) => fail(
+                ErrorCode::InvalidInput,
+                format!("Invalid --bridge-fee {}", e),
+            ),
+        },
+        (None, Some(target)) => {
+            let web3 = connections.web3.as_ref().unwrap();
+            match estimate_bridge_fee(web3, &mut grpc, erc20, eth_dest, target).await {
+                Ok(estimate) => {
+                    let next_batch_cost = format_amount(&estimate.next_batch_cost.get_total(), 18);
+                    match estimate.fee {
+                        Some(fee) => {
+                            info!(
+                                "Estimated a bridge fee of {}, the next batch of {} transfers costs about {} ETH to relay and the pool already pays {}",
+                                print_coin(&fee, &gravity_coin.denom, &display),
+                                estimate.next_batch_transfers,
+                                next_batch_cost,
+                                print_coin(&estimate.pending_fees, &gravity_coin.denom, &display)
+                            );
+                            fee
+                        }
+                        None => {
+                            warn!(
+                                "{} can not be priced against WETH on Uniswap so relayers will not value its fees, the next batch costs about {} ETH to relay. Using a bridge fee of 1, pass --bridge-fee to choose one",
+                                denom, next_batch_cost
+                            );
+                            1u64.into()
+                        }
+                    }
+                }
+                Err(e) => fail(
+                    ErrorCode::Query,
+                    format!("Failed to estimate the bridge fee {:?}", e),
+                ),
+            }
+        }
+        // relayers are unlikely to take a batch for this fee alone
+        (None, None) => 1u64.into(),
+    };
     let bridge_fee = Coin {
         denom: gravity_coin.denom.clone(),
-        amount: 1u64.into(),
+        amount: bridge_fee_amount,
     };
-    check_for_fee(&gravity_coin, cosmos_address, &contact).await;
-    check_for_fee(&fee, cosmos_address, &contact).await;
+    if let Err(e) = check_for_fee(&gravity_coin, cosmos_address, &contact).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
+    if let Err(e) = check_for_gas_price(&fee.gas_price, cosmos_address, &contact).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
 
     let balances = contact
         .get_balances(cosmos_address)
@@ -67,47 +161,99 @@ pub async fn cosmos_to_eth(args: CosmosToEthOpts, address_prefix: String) {
     info!("Cosmos balances {:?}", balances);
 
     if found.is_none() {
-        error!("You don't have any {} tokens!", gravity_coin.denom);
-        exit(1);
-    } else if amount.amount.clone() >= found.unwrap().amount {
-        if is_cosmos_originated {
-            error!("Your transfer of {} {} tokens is greater than your balance of {} tokens. Remember you need some to pay for fees!", print_atom(amount.amount), gravity_coin.denom, print_atom(found.unwrap().amount.clone()));
-        } else {
-            error!("Your transfer of {} {} tokens is greater than your balance of {} tokens. Remember you need some to pay for fees!", print_eth(amount.amount), gravity_coin.denom, print_eth(found.unwrap().amount.clone()));
-        }
-        exit(1);
+        fail(
+            ErrorCode::InsufficientFunds,
+            format!("You don't have any {} tokens!", gravity_coin.denom),
+        );
+    } else if amount.amount.clone() + bridge_fee.amount.clone() >= found.unwrap().amount {
+        fail(ErrorCode::InsufficientFunds, format!("Your transfer of {} tokens plus a bridge fee of {} is greater than your balance of {} tokens. Remember you need some to pay for fees!", print_coin(&amount.amount, &amount.denom, &display), print_coin(&bridge_fee.amount, &amount.denom, &display), print_coin(&found.unwrap().amount, &amount.denom, &display)));
     }
 
+    let cosmos_key = match cosmos_key {
+        Some(key) => key,
+        None => {
+            let mut msgs = vec![build_send_to_eth_msg(
+                cosmos_address,
+                eth_dest,
+                amount,
+                bridge_fee,
+            )];
+            if !no_batch {
+                msgs.push(build_request_batch_msg(cosmos_address, gravity_coin.denom));
+            }
+            generate_only(&contact, cosmos_address, msgs, &args.generate, &fee).await;
+            return;
+        }
+    };
+
     info!(
-        "Locking {} / {} into the batch pool",
-        amount.denom, gravity_coin.denom
+        "Locking {} into the batch pool",
+        print_coin(&amount.amount, &amount.denom, &display)
     );
     let res = send_to_eth(
         cosmos_key,
         eth_dest,
         amount.clone(),
         bridge_fee.clone(),
-        fee,
+        &fee,
         &contact,
     )
     .await;
-    match res {
-        Ok(tx_id) => info!("Send to Eth txid {}", tx_id.txhash),
-        Err(e) => info!("Failed to send tokens! {:?}", e),
-    }
+    let (txhash, transfer_id) = match res {
+        Ok(tx_id) => {
+            info!("Send to Eth txid {}", tx_id.txhash);
+            (tx_id.txhash.clone(), get_send_to_eth_id(&tx_id))
+        }
+        Err(e) => fail(
+            ErrorCode::Transaction,
+            format!("Failed to send tokens! {:?}", e),
+        ),
+    };
 
-    if !no_batch {
+    let batch_txhash = if !no_batch {
         info!("Requesting a batch to push transaction along immediately");
-        send_request_batch(
+        match send_request_batch(
             cosmos_key,
-            gravity_coin.denom,
-            bridge_fee,
+            gravity_coin.denom.clone(),
+            &fee,
             &contact,
             Some(TIMEOUT),
         )
         .await
-        .expect("Failed to request batch");
+        {
+            Ok(res) => Some(res.txhash),
+            Err(e) => fail(
+                ErrorCode::Transaction,
+                format!("Failed to request batch {:?}", e),
+            ),
+        }
     } else {
-        info!("--no-batch specified, your transfer will wait until someone requests a batch for this token type")
+        info!("--no-batch specified, your transfer will wait until someone requests a batch for this token type");
+        None
+    };
+
+    if wait {
+        match transfer_id {
+            Some(id) => {
+                let web3 = connections.web3.unwrap();
+                track_transfer(&contact, &mut grpc, &web3, Some(cosmos_address), id).await
+            }
+            None => {
+                fail(
+                    ErrorCode::Query,
+                    "Could not determine the transfer id, can not wait for it",
+                );
+            }
+        }
     }
+    print_result(&json!({
+        "txhash": txhash,
+        "transfer_id": transfer_id,
+        "batch_request_txhash": batch_txhash,
+        "sender": cosmos_address.to_string(),
+        "destination": eth_dest.to_string(),
+        "amount": amount.amount.to_string(),
+        "denom": amount.denom,
+        "bridge_fee": bridge_fee.amount.to_string(),
+    }));
 }
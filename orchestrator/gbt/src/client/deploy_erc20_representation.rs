@@ -1,8 +1,10 @@
+use crate::output::{fail, print_result, ErrorCode};
 use crate::{args::DeployErc20RepresentationOpts, utils::TIMEOUT};
 use cosmos_gravity::query::get_gravity_params;
 use ethereum_gravity::deploy_erc20::deploy_erc20;
 use gravity_proto::gravity::QueryDenomToErc20Request;
 use gravity_utils::connection_prep::{check_for_eth, create_rpc_connections};
+use serde_json::json;
 use std::{
     process::exit,
     time::{Duration, Instant},
@@ -26,7 +28,9 @@ pub async fn deploy_erc20_representation(
     let mut grpc = connections.grpc.unwrap();
 
     let ethereum_public_key = ethereum_key.to_public_key().unwrap();
-    check_for_eth(ethereum_public_key, &web3).await;
+    if let Err(e) = check_for_eth(ethereum_public_key, &web3).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
 
     let contract_address = if let Some(c) = args.gravity_contract_address {
         c
@@ -34,8 +38,7 @@ pub async fn deploy_erc20_representation(
         let params = get_gravity_params(&mut grpc).await.unwrap();
         let c = params.bridge_ethereum_address.parse();
         if c.is_err() {
-            error!("The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
-            exit(1);
+            fail(ErrorCode::Query, "The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
         }
         c.unwrap()
     };
@@ -46,16 +49,18 @@ pub async fn deploy_erc20_representation(
         })
         .await;
     if let Ok(val) = res {
-        info!(
-            "Asset {} already has ERC20 representation {}",
-            denom,
-            val.into_inner().erc20
+        fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "Asset {} already has ERC20 representation {}",
+                denom,
+                val.into_inner().erc20
+            ),
         );
-        exit(1);
     }
 
     info!("Starting deploy of ERC20");
-    let res = deploy_erc20(
+    let txid = deploy_erc20(
         denom.clone(),
         args.erc20_name,
         args.erc20_symbol,
@@ -69,7 +74,7 @@ pub async fn deploy_erc20_representation(
     .await
     .unwrap();
 
-    info!("We have deployed ERC20 contract {:#066x}, waiting to see if the Cosmos chain choses to adopt it", res);
+    info!("We have deployed ERC20 contract {:#066x}, waiting to see if the Cosmos chain choses to adopt it", txid);
 
     let start = Instant::now();
     loop {
@@ -80,17 +85,24 @@ pub async fn deploy_erc20_representation(
             .await;
 
         if let Ok(val) = res {
+            let erc20 = val.into_inner().erc20;
             info!(
                 "Asset {} has accepted new ERC20 representation {}",
-                denom,
-                val.into_inner().erc20
+                denom, erc20
             );
+            print_result(&json!({
+                "denom": denom,
+                "erc20": erc20,
+                "eth_txid": format!("{:#066x}", txid),
+            }));
             exit(0);
         }
 
         if Instant::now() - start > Duration::from_secs(100) {
-            info!("Your ERC20 contract was not adopted, double check the metadata and try again");
-            exit(1);
+            fail(
+                ErrorCode::Transaction,
+                "Your ERC20 contract was not adopted, double check the metadata and try again",
+            );
         }
         delay_for(Duration::from_secs(1)).await;
     }
@@ -1,57 +1,142 @@
-use std::process::exit;
-
 use crate::args::EthToCosmosOpts;
-use crate::utils::fraction_to_exponent;
-use crate::utils::TIMEOUT;
+use crate::client::track_deposit::{get_balance, track_deposit};
+use crate::output::{fail, print_result, ErrorCode};
+use crate::utils::{format_amount, parse_token_amount};
+use crate::utils::{require_connection, TIMEOUT};
+use cosmos_gravity::query::get_erc20_to_denom;
+use deep_space::Coin;
 use ethereum_gravity::send_to_cosmos::send_to_cosmos;
 use ethereum_gravity::utils::get_valset_nonce;
 use gravity_utils::connection_prep::{check_for_eth, create_rpc_connections};
+use serde_json::json;
 
 pub async fn eth_to_cosmos(args: EthToCosmosOpts, prefix: String) {
     let gravity_address = args.gravity_contract_address;
     let erc20_address = args.token_contract_address;
     let cosmos_dest = args.destination;
     let ethereum_key = args.ethereum_key;
-    let ethereum_public_key = ethereum_key.to_public_key().unwrap();
+    let ethereum_public_key = match ethereum_key.to_public_key() {
+        Ok(key) => key,
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!("Invalid --ethereum-key {:?}", e),
+        ),
+    };
     let ethereum_rpc = args.ethereum_rpc;
     let amount = args.amount;
+    let base_units = args.base_units;
+    let wait = args.wait;
+    let cosmos_grpc = if wait { Some(args.cosmos_grpc) } else { None };
 
-    let connections = create_rpc_connections(prefix, None, Some(ethereum_rpc), TIMEOUT).await;
+    let connections =
+        create_rpc_connections(prefix, cosmos_grpc, Some(ethereum_rpc), TIMEOUT).await;
 
-    let web3 = connections.web3.unwrap();
+    let web3 = require_connection(connections.web3, "Ethereum RPC");
 
-    get_valset_nonce(gravity_address, ethereum_public_key, &web3)
-        .await
-        .expect("Incorrect Gravity Address or otherwise unable to contact Gravity");
+    if let Err(e) = get_valset_nonce(gravity_address, ethereum_public_key, &web3).await {
+        fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "Incorrect Gravity address {} or otherwise unable to contact Gravity {:?}",
+                gravity_address, e
+            ),
+        );
+    }
 
-    check_for_eth(ethereum_public_key, &web3).await;
+    if let Err(e) = check_for_eth(ethereum_public_key, &web3).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
 
-    let res = web3
+    let decimals: u8 = match web3
         .get_erc20_decimals(erc20_address, ethereum_public_key)
         .await
-        .expect("Failed to query ERC20 contract");
-    let decimals: u8 = res.to_string().parse().unwrap();
-    let amount = fraction_to_exponent(amount, decimals);
+        .map(|decimals| decimals.to_string().parse())
+    {
+        Ok(Ok(decimals)) => decimals,
+        Ok(Err(e)) => fail(
+            ErrorCode::InvalidInput,
+            format!("ERC20 {} has invalid decimals {:?}", erc20_address, e),
+        ),
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!("Failed to query ERC20 contract {} {:?}", erc20_address, e),
+        ),
+    };
+    let amount = match parse_token_amount(&amount, Some(decimals), base_units) {
+        Ok(amount) => amount,
+        Err(e) => {
+            fail(ErrorCode::InvalidInput, format!("Invalid amount {}", e));
+        }
+    };
 
-    let erc20_balance = web3
+    let erc20_balance = match web3
         .get_erc20_balance(erc20_address, ethereum_public_key)
         .await
-        .expect("Failed to get balance, check ERC20 contract address");
+    {
+        Ok(balance) => balance,
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!(
+                "Failed to get balance, check ERC20 contract address {} {:?}",
+                erc20_address, e
+            ),
+        ),
+    };
 
     if erc20_balance == 0u8.into() {
-        error!(
-            "You have zero {} tokens, please double check your sender and erc20 addresses!",
-            erc20_address
+        fail(
+            ErrorCode::InsufficientFunds,
+            format!(
+                "You have zero {} tokens, please double check your sender and erc20 addresses!",
+                erc20_address
+            ),
         );
-        exit(1);
     } else if amount.clone() > erc20_balance {
-        error!("Insufficient balance {} > {}", amount, erc20_balance);
-        exit(1);
+        fail(
+            ErrorCode::InsufficientFunds,
+            format!(
+                "Insufficient balance {} > {}",
+                format_amount(&amount, decimals),
+                format_amount(&erc20_balance, decimals)
+            ),
+        );
     }
 
+    // the starting balance is needed to tell when the deposit has been credited
+    let starting_balance = if wait {
+        let contact = require_connection(connections.contact.as_ref(), "Cosmos gRPC");
+        let mut grpc = require_connection(connections.grpc.clone(), "Cosmos gRPC");
+        let denom = match get_erc20_to_denom(&mut grpc, erc20_address).await {
+            Ok(res) => res.denom,
+            Err(e) => {
+                fail(
+                    ErrorCode::Query,
+                    format!(
+                        "Failed to get the Cosmos denom for {} {:?}",
+                        erc20_address, e
+                    ),
+                );
+            }
+        };
+        match get_balance(contact, cosmos_dest, &denom).await {
+            Some(amount) => Some(Coin { denom, amount }),
+            None => {
+                fail(
+                    ErrorCode::Query,
+                    format!("Failed to get the balance of {}", cosmos_dest),
+                );
+            }
+        }
+    } else {
+        None
+    };
+
     info!(
         "Sending {} / {} to Cosmos from {} to {}",
-        amount, erc20_address, ethereum_public_key, cosmos_dest
+        format_amount(&amount, decimals),
+        erc20_address,
+        ethereum_public_key,
+        cosmos_dest
     );
     // we send some erc20 tokens to the gravity contract to register a deposit
     let res = send_to_cosmos(
@@ -65,8 +150,46 @@ pub async fn eth_to_cosmos(args: EthToCosmosOpts, prefix: String) {
         vec![],
     )
     .await;
-    match res {
-        Ok(tx_id) => info!("Send to Cosmos txid: {:#066x}", tx_id),
-        Err(e) => info!("Failed to send tokens! {:?}", e),
+    let tx_id = match res {
+        Ok(tx_id) => {
+            info!("Send to Cosmos txid: {:#066x}", tx_id);
+            tx_id
+        }
+        Err(e) => fail(
+            ErrorCode::Transaction,
+            format!("Failed to send tokens! {:?}", e),
+        ),
+    };
+
+    if let Some(starting_balance) = starting_balance {
+        let contact = require_connection(connections.contact, "Cosmos gRPC");
+        let mut grpc = require_connection(connections.grpc, "Cosmos gRPC");
+        if let Err(e) = track_deposit(
+            &contact,
+            &mut grpc,
+            &web3,
+            gravity_address,
+            tx_id.clone(),
+            cosmos_dest,
+            starting_balance,
+            decimals,
+        )
+        .await
+        {
+            fail(
+                ErrorCode::Query,
+                format!(
+                    "Deposit {:#066x} was sent but tracking stopped, {}",
+                    tx_id, e
+                ),
+            );
+        }
     }
+    print_result(&json!({
+        "eth_txid": format!("{:#066x}", tx_id),
+        "sender": ethereum_public_key.to_string(),
+        "destination": cosmos_dest.to_string(),
+        "erc20": erc20_address.to_string(),
+        "amount": amount.to_string(),
+    }));
 }
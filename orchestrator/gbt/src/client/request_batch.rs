@@ -0,0 +1,63 @@
+use crate::args::RequestBatchOpts;
+use crate::output::{fail, print_result, ErrorCode};
+use crate::tx::{generate_only, get_sender};
+use crate::utils::{get_fee_settings, TIMEOUT};
+use cosmos_gravity::query::get_denom_to_erc20;
+use cosmos_gravity::send::{build_request_batch_msg, send_request_batch};
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::types::GravityBridgeToolsConfig;
+use serde_json::json;
+
+pub async fn request_batch(
+    args: RequestBatchOpts,
+    address_prefix: String,
+    config: &GravityBridgeToolsConfig,
+) {
+    let (cosmos_key, cosmos_address) = get_sender(
+        args.cosmos_phrase,
+        &args.generate,
+        &address_prefix,
+        &config.keys,
+    );
+    let fee = get_fee_settings(args.gas_price, &config.fees);
+
+    let connections =
+        create_rpc_connections(address_prefix, Some(args.cosmos_grpc), None, TIMEOUT).await;
+    let contact = connections.contact.unwrap();
+    let mut grpc = connections.grpc.unwrap();
+
+    if get_denom_to_erc20(&mut grpc, args.denom.clone())
+        .await
+        .is_err()
+    {
+        fail(
+            ErrorCode::Query,
+            format!(
+                "Asset {} has no ERC20 representation, there is nothing to batch",
+                args.denom
+            ),
+        );
+    }
+
+    let cosmos_key = match cosmos_key {
+        Some(key) => key,
+        None => {
+            let msg = build_request_batch_msg(cosmos_address, args.denom);
+            generate_only(&contact, cosmos_address, vec![msg], &args.generate, &fee).await;
+            return;
+        }
+    };
+    info!("Requesting a batch for {}", args.denom);
+    match send_request_batch(cosmos_key, args.denom, &fee, &contact, Some(TIMEOUT)).await {
+        Ok(res) => {
+            info!("Requested batch with txid {}", res.txhash);
+            print_result(&json!({ "txhash": res.txhash }));
+        }
+        Err(e) => {
+            fail(
+                ErrorCode::Transaction,
+                format!("Failed to request batch {:?}", e),
+            );
+        }
+    }
+}
@@ -0,0 +1,549 @@
+//! Follows a transfer from Cosmos to Ethereum through every stage of the bridge, from the
+//! transaction pool, into an outgoing batch, through validator confirms and finally to the
+//! TransactionBatchExecutedEvent on Ethereum.
+
+use crate::args::TrackOpts;
+use crate::output::{fail, print_result, ErrorCode};
+use crate::utils::{require_connection, TIMEOUT};
+use clarity::utils::bytes_to_hex_str;
+use clarity::Address as EthAddress;
+use cosmos_gravity::query::{
+    get_current_valset, get_gravity_params, get_latest_transaction_batches,
+    get_pending_send_to_eth, get_transaction_batch_signatures,
+};
+use deep_space::client::ChainStatus;
+use deep_space::{Address as CosmosAddress, Contact};
+use ethereum_gravity::utils::get_tx_batch_nonce;
+use gravity_proto::cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::service_client::ServiceClient as TxServiceClient;
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::GetTxRequest;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_proto::gravity::Params;
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::types::event_signatures::TRANSACTION_BATCH_EXECUTED_EVENT_SIG;
+use gravity_utils::types::{TransactionBatch, TransactionBatchExecutedEvent};
+use serde_json::json;
+use std::time::Duration;
+use tokio::time::sleep as delay_for;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// How often the bridge is checked for progress
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The power threshold the Gravity contract is deployed with, signatures from at least
+/// this much of the normalized validator power are required to submit a batch
+const POWER_THRESHOLD: u64 = 2834678415;
+
+/// The stages a transfer goes through on it's way to Ethereum
+#[derive(Debug, Clone)]
+enum TransferStage {
+    /// Waiting in the pool for someone to request a batch
+    InPool,
+    /// In a batch that validators are signing, with the power that has signed so far
+    InBatch {
+        batch: TransactionBatch,
+        signed_power: u64,
+    },
+    /// The batch has been executed on Ethereum in the given transaction, but not yet observed on Cosmos
+    Executed {
+        batch: TransactionBatch,
+        txid: String,
+    },
+    /// The batch has been executed and observed, the transfer is complete
+    Complete,
+}
+
+impl TransferStage {
+    /// Identifies the stage for change detection, a batch gaining signatures counts as a change
+    fn key(&self) -> (u8, u64, u64) {
+        match self {
+            TransferStage::InPool => (0, 0, 0),
+            TransferStage::InBatch {
+                batch,
+                signed_power,
+            } => (1, batch.nonce, *signed_power),
+            TransferStage::Executed { batch, .. } => (2, batch.nonce, 0),
+            TransferStage::Complete => (3, 0, 0),
+        }
+    }
+}
+
+pub async fn track(args: TrackOpts, address_prefix: String) {
+    let connections = create_rpc_connections(
+        address_prefix,
+        Some(args.cosmos_grpc),
+        Some(args.ethereum_rpc),
+        TIMEOUT,
+    )
+    .await;
+    let contact = require_connection(connections.contact, "Cosmos gRPC");
+    let mut grpc = require_connection(connections.grpc, "Cosmos gRPC");
+    let web3 = require_connection(connections.web3, "Ethereum RPC");
+
+    // a transfer id is a number, anything else is taken to be a txhash
+    let (id, sender) = match args.target.parse::<u64>() {
+        Ok(id) => (id, args.sender),
+        Err(_) => match get_tx_by_hash(&contact, &args.target).await {
+            Ok(response) => match get_send_to_eth_id(&response) {
+                Some(id) => (id, args.sender.or_else(|| get_sender(&response))),
+                None => {
+                    fail(
+                        ErrorCode::InvalidInput,
+                        format!("{} is not a transfer to Ethereum", args.target),
+                    );
+                }
+            },
+            Err(e) => {
+                fail(
+                    ErrorCode::Query,
+                    format!("Could not find transaction {} {}", args.target, e),
+                );
+            }
+        },
+    };
+
+    track_transfer(&contact, &mut grpc, &web3, sender, id).await;
+    print_result(&json!({
+        "transfer_id": id,
+        "status": "complete",
+    }));
+}
+
+/// Follows the transfer with the given id until it has been executed on Ethereum and observed
+/// on Cosmos, the sender is needed to find the transfer while it is waiting in the pool for
+/// it's first batch. Without it a transfer whose batch timed out is assumed to be back in the pool
+pub async fn track_transfer(
+    contact: &Contact,
+    grpc: &mut GravityQueryClient<Channel>,
+    web3: &Web3,
+    sender: Option<CosmosAddress>,
+    id: u64,
+) {
+    let params = match get_gravity_params(grpc).await {
+        Ok(params) => params,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get Gravity params {:?}", e),
+            );
+        }
+    };
+    let gravity_contract: EthAddress = match params.bridge_ethereum_address.parse() {
+        Ok(address) => address,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Gravity params contain an invalid bridge address {:?}", e),
+            );
+        }
+    };
+
+    info!("Tracking transfer {}", id);
+    let mut last_stage = None;
+    let mut last_batch = None;
+    loop {
+        let stage = match get_stage(
+            grpc,
+            web3,
+            gravity_contract,
+            &params,
+            sender,
+            id,
+            &last_batch,
+        )
+        .await
+        {
+            Ok(Some(stage)) => stage,
+            Ok(None) => {
+                if sender.is_none() {
+                    fail(
+                        ErrorCode::Query,
+                        format!("Transfer {} is not in any batch, pass --sender to also look in the transaction pool", id),
+                    );
+                } else {
+                    fail(
+                        ErrorCode::Query,
+                        format!("Transfer {} is not pending, it has either been cancelled or was completed before tracking started", id),
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("Failed to check transfer progress {}, will try again", e);
+                delay_for(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        if last_stage != Some(stage.key()) {
+            report_stage(contact, web3, &params, &stage).await;
+        }
+        match &stage {
+            TransferStage::InBatch { batch, .. } | TransferStage::Executed { batch, .. } => {
+                last_batch = Some(batch.clone())
+            }
+            TransferStage::Complete => return,
+            // keep the last batch, without a sender it is the only way to tell that a
+            // transfer we can't see has returned to the pool rather than disappeared
+            TransferStage::InPool => {}
+        }
+        last_stage = Some(stage.key());
+        delay_for(POLL_INTERVAL).await;
+    }
+}
+
+/// What was found on chain about a transfer during one poll
+#[derive(Debug, Clone, Default)]
+struct Observation {
+    /// The transfer is waiting in the transaction pool
+    in_pool: bool,
+    /// The transaction pool was searched, this requires the sender
+    pool_checked: bool,
+    /// The batch on Cosmos that currently contains the transfer
+    batch: Option<TransactionBatch>,
+    /// The txid of the TransactionBatchExecutedEvent for `batch`, or for the last batch
+    /// we saw the transfer in if it is no longer on Cosmos
+    execution: Option<String>,
+    /// The power that has signed `batch`
+    signed_power: u64,
+}
+
+/// Determines where in the bridge the transfer currently is, the batch we last saw it in
+/// is used to detect completion once it is no longer pending. Returns None if the transfer
+/// can not be found at all
+async fn get_stage(
+    grpc: &mut GravityQueryClient<Channel>,
+    web3: &Web3,
+    gravity_contract: EthAddress,
+    params: &Params,
+    sender: Option<CosmosAddress>,
+    id: u64,
+    last_batch: &Option<TransactionBatch>,
+) -> Result<Option<TransferStage>, String> {
+    let mut observation = Observation::default();
+    if let Some(sender) = sender {
+        let pending = get_pending_send_to_eth(grpc, sender)
+            .await
+            .map_err(|e| format!("{:?}", e))?;
+        observation.pool_checked = true;
+        if pending.unbatched_transfers.iter().any(|tx| tx.id == id) {
+            observation.in_pool = true;
+            return Ok(stage_from(observation, last_batch));
+        }
+    }
+
+    let batches = get_latest_transaction_batches(grpc)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    observation.batch = batches
+        .into_iter()
+        .find(|b| b.transactions.iter().any(|tx| tx.id == id));
+
+    if let Some(batch) = observation.batch.as_ref().or_else(|| last_batch.as_ref()) {
+        observation.execution = find_execution(web3, gravity_contract, params, batch).await?;
+    }
+    if let (Some(batch), None) = (&observation.batch, &observation.execution) {
+        observation.signed_power = get_signed_power(grpc, batch).await?;
+    }
+    Ok(stage_from(observation, last_batch))
+}
+
+/// Works out the stage from an observation, a batch only counts as executed once the
+/// TransactionBatchExecutedEvent for it's own nonce has been found
+fn stage_from(
+    observation: Observation,
+    last_batch: &Option<TransactionBatch>,
+) -> Option<TransferStage> {
+    if observation.in_pool {
+        return Some(TransferStage::InPool);
+    }
+    match (observation.batch, last_batch, observation.execution) {
+        (Some(batch), _, Some(txid)) => Some(TransferStage::Executed { batch, txid }),
+        (Some(batch), _, None) => Some(TransferStage::InBatch {
+            batch,
+            signed_power: observation.signed_power,
+        }),
+        // the batch has been removed from Cosmos, either because it was executed
+        // or because it timed out in which case the transfer goes back to the pool
+        (None, Some(_), Some(_)) => Some(TransferStage::Complete),
+        // without the sender the pool can't be searched, but a transfer whose batch was
+        // removed without executing can only have returned to it
+        (None, Some(_), None) if !observation.pool_checked => Some(TransferStage::InPool),
+        (None, _, _) => None,
+    }
+}
+
+/// Looks for the execution of the given batch on Ethereum. Ethereum only accepts batches with
+/// a nonce higher than the last one executed for the token so the event is only searched for
+/// once the last executed nonce has reached this batch. A later batch executing first also
+/// passes this batch, which is why the event for this exact nonce is required
+async fn find_execution(
+    web3: &Web3,
+    gravity_contract: EthAddress,
+    params: &Params,
+    batch: &TransactionBatch,
+) -> Result<Option<String>, String> {
+    let last_nonce = get_tx_batch_nonce(
+        gravity_contract,
+        batch.token_contract,
+        gravity_contract,
+        web3,
+    )
+    .await
+    .map_err(|e| format!("{:?}", e))?;
+    if last_nonce < batch.nonce {
+        return Ok(None);
+    }
+    find_execution_txid(web3, gravity_contract, params, batch).await
+}
+
+/// Sums the normalized power of the current validator set members that have
+/// confirmed the given batch
+async fn get_signed_power(
+    grpc: &mut GravityQueryClient<Channel>,
+    batch: &TransactionBatch,
+) -> Result<u64, String> {
+    let confirms = get_transaction_batch_signatures(grpc, batch.nonce, batch.token_contract)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let valset = get_current_valset(grpc)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    Ok(valset
+        .members
+        .iter()
+        .filter(|m| {
+            confirms
+                .iter()
+                .any(|c| Some(c.ethereum_signer) == m.eth_address)
+        })
+        .map(|m| m.power)
+        .sum())
+}
+
+async fn report_stage(contact: &Contact, web3: &Web3, params: &Params, stage: &TransferStage) {
+    match stage {
+        TransferStage::InPool => info!(
+            "Waiting in the transaction pool, a batch must be requested for this token before it can move on"
+        ),
+        TransferStage::InBatch {
+            batch,
+            signed_power,
+        } => {
+            info!(
+                "In batch {}:{}, validators with {:.2}% of the voting power have signed, {:.2}% is required",
+                batch.token_contract,
+                batch.nonce,
+                *signed_power as f64 / u32::MAX as f64 * 100f64,
+                POWER_THRESHOLD as f64 / u32::MAX as f64 * 100f64,
+            );
+            if *signed_power < POWER_THRESHOLD {
+                if let Ok(ChainStatus::Moving { block_height }) = contact.get_chain_status().await
+                {
+                    let deadline = batch.block + params.signed_batches_window;
+                    info!(
+                        "Validators must sign by block {}, {}",
+                        deadline,
+                        eta(deadline.saturating_sub(block_height), params.average_block_time)
+                    );
+                }
+            } else {
+                info!("Batch is fully signed and waiting for a relayer to submit it to Ethereum");
+            }
+            if let Ok(eth_height) = web3.eth_block_number().await {
+                let eth_height: u64 = eth_height.to_string().parse().unwrap_or(0);
+                if eth_height >= batch.batch_timeout {
+                    warn!(
+                        "Batch timed out at Ethereum block {}, the transfer will return to the pool",
+                        batch.batch_timeout
+                    );
+                } else {
+                    info!(
+                        "Batch times out at Ethereum block {}, {}",
+                        batch.batch_timeout,
+                        eta(
+                            batch.batch_timeout - eth_height,
+                            params.average_ethereum_block_time
+                        )
+                    );
+                }
+            }
+        }
+        TransferStage::Executed { batch, txid } => {
+            info!(
+                "Batch {}:{} executed on Ethereum in transaction 0x{}",
+                batch.token_contract, batch.nonce, txid
+            );
+            info!("Waiting for validators to observe the execution on Cosmos");
+        }
+        TransferStage::Complete => info!("Transfer complete!"),
+    }
+}
+
+/// Searches Ethereum for the TransactionBatchExecutedEvent of the given batch and returns the
+/// hash of the transaction that emitted it. The batch can not have been executed before it
+/// was created, which is estimated from it's timeout, so the search starts there
+async fn find_execution_txid(
+    web3: &Web3,
+    gravity_contract: EthAddress,
+    params: &Params,
+    batch: &TransactionBatch,
+) -> Result<Option<String>, String> {
+    let timeout_blocks = if params.average_ethereum_block_time > 0 {
+        params.target_batch_timeout / params.average_ethereum_block_time
+    } else {
+        0
+    };
+    let start = batch.batch_timeout.saturating_sub(timeout_blocks);
+    let logs = web3
+        .check_for_events(
+            start.into(),
+            None,
+            vec![gravity_contract],
+            vec![TRANSACTION_BATCH_EXECUTED_EVENT_SIG],
+        )
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    for log in logs {
+        if let Ok(event) = TransactionBatchExecutedEvent::from_log(&log) {
+            if event.batch_nonce == batch.nonce && event.erc20 == batch.token_contract {
+                return Ok(Some(
+                    log.transaction_hash
+                        .map(|hash| bytes_to_hex_str(&hash.0))
+                        .unwrap_or_default(),
+                ));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Formats the time it will take to produce the given number of blocks
+fn eta(blocks: u64, block_time_ms: u64) -> String {
+    let minutes = blocks * block_time_ms / 1000 / 60;
+    if minutes < 1 {
+        "in less than a minute".to_string()
+    } else if minutes < 120 {
+        format!("in about {} minutes", minutes)
+    } else {
+        format!("in about {} hours", minutes / 60)
+    }
+}
+
+async fn get_tx_by_hash(contact: &Contact, txhash: &str) -> Result<TxResponse, String> {
+    let mut txrpc = TxServiceClient::connect(contact.get_url())
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let response = txrpc
+        .get_tx(GetTxRequest {
+            hash: txhash.trim_start_matches("0x").to_uppercase(),
+        })
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+    response
+        .tx_response
+        .ok_or_else(|| "No transaction response".to_string())
+}
+
+/// Gets the id the Gravity module assigned to the transfer created by a MsgSendToEth
+pub fn get_send_to_eth_id(response: &TxResponse) -> Option<u64> {
+    get_attribute(response, "outgoing_tx_id").and_then(|id| id.parse().ok())
+}
+
+/// Gets the transfer ids for a transaction with `count` MsgSendToEth messages, in message order
+pub fn get_send_to_eth_ids(response: &TxResponse, count: usize) -> Vec<Option<u64>> {
+    let mut ids = vec![None; count];
+    for log in response.logs.iter() {
+        let index = log.msg_index as usize;
+        if index >= count {
+            continue;
+        }
+        ids[index] = log
+            .events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == "outgoing_tx_id")
+            .and_then(|attribute| attribute.value.parse().ok());
+    }
+    ids
+}
+
+fn get_sender(response: &TxResponse) -> Option<CosmosAddress> {
+    get_attribute(response, "sender").and_then(|sender| sender.parse().ok())
+}
+
+fn get_attribute(response: &TxResponse, key: &str) -> Option<String> {
+    for log in response.logs.iter() {
+        for event in log.events.iter() {
+            for attribute in event.attributes.iter() {
+                if attribute.key == key {
+                    return Some(attribute.value.clone());
+                }
+            }
+        }
+    }
+    None
+}
+
+#[test]
+fn test_eta() {
+    assert_eq!(eta(5, 5000), "in less than a minute");
+    assert_eq!(eta(120, 5000), "in about 10 minutes");
+    assert_eq!(eta(1440, 5000), "in about 2 hours");
+}
+
+#[cfg(test)]
+fn test_batch(nonce: u64) -> TransactionBatch {
+    TransactionBatch {
+        nonce,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_stage_from() {
+    let stage = |observation: Observation, last_batch: Option<TransactionBatch>| {
+        stage_from(observation, &last_batch).map(|s| s.key())
+    };
+    let in_pool = Observation {
+        in_pool: true,
+        ..Default::default()
+    };
+    assert_eq!(stage(in_pool, Some(test_batch(1))), Some((0, 0, 0)));
+
+    let in_batch = Observation {
+        batch: Some(test_batch(2)),
+        signed_power: 100,
+        ..Default::default()
+    };
+    assert_eq!(stage(in_batch, None), Some((1, 2, 100)));
+
+    let executed = Observation {
+        batch: Some(test_batch(2)),
+        execution: Some("ab".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(stage(executed, None), Some((2, 2, 0)));
+
+    // gone from Cosmos with the execution event for our batch found
+    let complete = Observation {
+        execution: Some("ab".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(stage(complete, Some(test_batch(2))), Some((3, 0, 0)));
+
+    // gone from Cosmos without an execution event, a later batch was executed instead
+    // and this one timed out, so the transfer is back in the pool unless we looked there
+    let not_in_pool = Observation {
+        pool_checked: true,
+        ..Default::default()
+    };
+    assert_eq!(stage(not_in_pool.clone(), Some(test_batch(2))), None);
+    assert_eq!(
+        stage(Observation::default(), Some(test_batch(2))),
+        Some((0, 0, 0))
+    );
+    assert_eq!(stage(not_in_pool, None), None);
+    assert_eq!(stage(Observation::default(), None), None);
+}
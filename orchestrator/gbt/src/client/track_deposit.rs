@@ -0,0 +1,241 @@
+//! Follows a deposit from Ethereum to Cosmos until it has been credited. A deposit must first
+//! reach the confirmation depth the Oracle waits for, then be attested by the validators and
+//! finally shows up in the destination balance.
+
+use crate::utils::format_amount;
+use clarity::Address as EthAddress;
+use clarity::Uint256;
+use cosmos_gravity::query::{get_current_valset, get_send_to_cosmos_attestation};
+use deep_space::{Address as CosmosAddress, Coin, Contact};
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::types::event_signatures::SENT_TO_COSMOS_EVENT_SIG;
+use gravity_utils::types::SendToCosmosEvent;
+use orchestrator::ethereum_event_watcher::get_block_delay;
+use std::time::{Duration, Instant};
+use tokio::time::sleep as delay_for;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// How often the bridge is checked for progress
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The number of blocks before the current one searched for our deposit event
+const SEARCH_BLOCKS: u64 = 100;
+
+/// How long to look for the deposit event on Ethereum, it has already been mined so
+/// this only has to cover a lagging node
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long to wait for the validators to attest to the deposit once it is confirmed
+const ATTESTATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How long to wait for the deposit to show up in the balance once it is attested
+const CREDIT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Gets the balance of the given denom, zero if the address holds none
+pub async fn get_balance(
+    contact: &Contact,
+    address: CosmosAddress,
+    denom: &str,
+) -> Option<Uint256> {
+    let balances = contact.get_balances(address).await.ok()?;
+    Some(
+        balances
+            .into_iter()
+            .find(|c| c.denom == denom)
+            .map(|c| c.amount)
+            .unwrap_or_else(|| 0u8.into()),
+    )
+}
+
+/// Follows the deposit made in Ethereum transaction tx_id until it has been credited on Cosmos,
+/// the starting balance is the destination balance of the deposited token before the deposit
+/// and decimals are the ERC20's decimals, used to display the balance. Returns an error if
+/// any stage takes too long
+#[allow(clippy::too_many_arguments)]
+pub async fn track_deposit(
+    contact: &Contact,
+    grpc: &mut GravityQueryClient<Channel>,
+    web3: &Web3,
+    gravity_contract: EthAddress,
+    tx_id: Uint256,
+    destination: CosmosAddress,
+    starting_balance: Coin,
+    decimals: u8,
+) -> Result<(), String> {
+    let event = find_deposit(web3, gravity_contract, &tx_id).await?;
+    info!(
+        "Deposit has event nonce {} in Ethereum block {}",
+        event.event_nonce, event.block_height
+    );
+
+    let block_delay = get_block_delay(web3).await;
+    let mut last_depth = None;
+    loop {
+        let latest = match web3.eth_block_number().await {
+            Ok(latest) => latest,
+            Err(e) => {
+                warn!("Failed to get the latest Ethereum block {:?}", e);
+                delay_for(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+        let depth = confirmations(&latest, &event.block_height, &block_delay);
+        if last_depth.as_ref() != Some(&depth) {
+            info!("Deposit has {} of {} confirmations", depth, block_delay);
+        }
+        if depth >= block_delay {
+            break;
+        }
+        last_depth = Some(depth);
+        delay_for(POLL_INTERVAL).await;
+    }
+
+    info!("Waiting for validators to attest to the deposit");
+    let mut last_votes = None;
+    let start = Instant::now();
+    loop {
+        if Instant::now() - start > ATTESTATION_TIMEOUT {
+            return Err(format!(
+                "validators did not attest to deposit {} within {} minutes",
+                event.event_nonce,
+                ATTESTATION_TIMEOUT.as_secs() / 60
+            ));
+        }
+        let attestation = match get_send_to_cosmos_attestation(grpc, event.event_nonce).await {
+            Ok(attestation) => attestation,
+            Err(e) => {
+                warn!("Failed to get attestations {:?}", e);
+                delay_for(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+        let (votes, observed) = match attestation {
+            Some(attestation) => (attestation.votes.len(), attestation.observed),
+            None => (0, false),
+        };
+        if last_votes != Some(votes) {
+            match get_current_valset(grpc).await {
+                Ok(valset) => info!(
+                    "{} of {} validators have attested to the deposit",
+                    votes,
+                    valset.members.len()
+                ),
+                Err(_) => info!("{} validators have attested to the deposit", votes),
+            }
+            last_votes = Some(votes);
+        }
+        if observed {
+            info!("Deposit has been observed on Cosmos");
+            break;
+        }
+        delay_for(POLL_INTERVAL).await;
+    }
+
+    let expected = starting_balance.amount + event.amount;
+    let start = Instant::now();
+    loop {
+        if Instant::now() - start > CREDIT_TIMEOUT {
+            return Err(format!(
+                "deposit {} was observed but {} was not credited within {} minutes",
+                event.event_nonce,
+                destination,
+                CREDIT_TIMEOUT.as_secs() / 60
+            ));
+        }
+        if let Some(balance) = get_balance(contact, destination, &starting_balance.denom).await {
+            if balance >= expected {
+                info!(
+                    "Deposit credited, {} now holds {} {}",
+                    destination,
+                    format_amount(&balance, decimals),
+                    starting_balance.denom
+                );
+                return Ok(());
+            }
+        }
+        delay_for(POLL_INTERVAL).await;
+    }
+}
+
+/// Finds the deposit event emitted by the given Ethereum transaction, it has just been
+/// mined so it is within the last few blocks
+async fn find_deposit(
+    web3: &Web3,
+    gravity_contract: EthAddress,
+    tx_id: &Uint256,
+) -> Result<SendToCosmosEvent, String> {
+    let start = Instant::now();
+    while Instant::now() - start < SEARCH_TIMEOUT {
+        let latest: u64 = match web3.eth_block_number().await {
+            Ok(latest) => latest.to_string().parse().unwrap(),
+            Err(e) => {
+                warn!("Failed to get the latest Ethereum block {:?}", e);
+                delay_for(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+        let logs = web3
+            .check_for_events(
+                latest.saturating_sub(SEARCH_BLOCKS).into(),
+                None,
+                vec![gravity_contract],
+                vec![SENT_TO_COSMOS_EVENT_SIG],
+            )
+            .await;
+        if let Ok(logs) = logs {
+            let deposit = logs
+                .iter()
+                .filter(|log| match &log.transaction_hash {
+                    Some(hash) => is_transaction(&hash.0, tx_id),
+                    None => false,
+                })
+                .find_map(|log| SendToCosmosEvent::from_log(log).ok());
+            if let Some(deposit) = deposit {
+                return Ok(deposit);
+            }
+        }
+        delay_for(POLL_INTERVAL).await;
+    }
+    Err(format!(
+        "no deposit event from transaction {:#066x} was found",
+        tx_id
+    ))
+}
+
+/// Compares a transaction hash from an Ethereum log with the txid returned when sending it
+fn is_transaction(hash: &[u8], tx_id: &Uint256) -> bool {
+    Uint256::from_bytes_be(hash) == *tx_id
+}
+
+/// The number of confirmations the deposit has, capped at the block delay the Oracle waits for
+fn confirmations(latest: &Uint256, event_height: &Uint256, block_delay: &Uint256) -> Uint256 {
+    if latest <= event_height {
+        0u8.into()
+    } else if latest.clone() - event_height.clone() > *block_delay {
+        block_delay.clone()
+    } else {
+        latest.clone() - event_height.clone()
+    }
+}
+
+#[test]
+fn test_is_transaction() {
+    let mut hash = [0u8; 32];
+    hash[31] = 0x2a;
+    assert!(is_transaction(&hash, &42u8.into()));
+    assert!(!is_transaction(&hash, &43u8.into()));
+    hash[0] = 1;
+    assert!(!is_transaction(&hash, &42u8.into()));
+}
+
+#[test]
+fn test_confirmations() {
+    let c = |latest: u8, height: u8| -> Uint256 {
+        confirmations(&latest.into(), &height.into(), &6u8.into())
+    };
+    assert_eq!(c(9, 10), 0u8.into());
+    assert_eq!(c(10, 10), 0u8.into());
+    assert_eq!(c(13, 10), 3u8.into());
+    assert_eq!(c(30, 10), 6u8.into());
+}
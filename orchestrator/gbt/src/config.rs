@@ -1,12 +1,18 @@
 //! Handles configuration structs + saving and loading for Gravity bridge tools
 
-use crate::args::InitOpts;
+use crate::args::ConfigShowOpts;
+use crate::output::{fail, is_json, print_result, ErrorCode};
+use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
-use gravity_utils::types::GravityBridgeToolsConfig;
+use deep_space::PrivateKey as CosmosPrivateKey;
+use gravity_utils::hd_wallet::cosmos_key_from_phrase;
+use gravity_utils::types::{ConnectionConfig, GravityBridgeToolsConfig, KeysConfig};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::str::FromStr;
 use std::{
-    fs::{self, create_dir},
+    env, fs,
     path::{Path, PathBuf},
-    process::exit,
 };
 
 /// The name of the config file, this file is copied
@@ -16,17 +22,29 @@ pub const CONFIG_NAME: &str = "config.toml";
 /// The name of the keys file, this file is not expected
 /// to be hand edited.
 pub const KEYS_NAME: &str = "keys.json";
+/// The name of the file holding checkpoint hashes approved
+/// for signing despite failing the signing policy
+pub const APPROVALS_NAME: &str = "approvals.toml";
+/// The name of the append-only journal of every Ethereum signature
+/// produced by the Orchestrator
+pub const JOURNAL_NAME: &str = "signing-journal.jsonl";
 /// The folder name for the config
 pub const CONFIG_FOLDER: &str = ".gbt";
 
 /// The keys storage struct, including encrypted and un-encrypted local keys
 /// un-encrypted keys provide for orchestrator start and relayer start functions
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct KeyStorage {
     pub orchestrator_phrase: Option<String>,
     pub ethereum_key: Option<EthPrivateKey>,
 }
 
+/// Checkpoint hashes the operator has approved with `gbt orchestrator approve`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ApprovalStorage {
+    pub checkpoints: Vec<String>,
+}
+
 /// Checks if the user has setup their config environment
 pub fn config_exists(home_dir: &Path) -> bool {
     let config_file = home_dir.join(CONFIG_FOLDER).with_file_name(CONFIG_NAME);
@@ -34,26 +52,39 @@ pub fn config_exists(home_dir: &Path) -> bool {
     home_dir.exists() && config_file.exists() && keys_file.exists()
 }
 
-/// Creates the config directory and default config file if it does
-/// not already exist
-pub fn init_config(_init_ops: InitOpts, home_dir: PathBuf) {
-    if home_dir.exists() {
-        warn!(
-            "The Gravity bridge tools config folder {} already exists!",
-            home_dir.to_str().unwrap()
-        );
-        warn!("You can delete this folder and run init again, you will lose any keys or other config data!");
-    } else {
-        create_dir(home_dir.clone()).expect("Failed to create config directory!");
-
-        fs::write(home_dir.join(CONFIG_NAME), get_default_config())
-            .expect("Unable to write config file");
-        fs::write(
-            home_dir.join(KEYS_NAME),
-            toml::to_string(&KeyStorage::default()).unwrap(),
-        )
-        .expect("Unable to write config file");
+/// Creates the contents of a config file from the annotated default config with the
+/// given (section, key, value) string entries set. Commented out examples are replaced
+/// in place so that each value stays next to it's documentation
+pub fn render_config(values: &[(&str, &str, String)]) -> Result<String, String> {
+    let mut remaining: Vec<&(&str, &str, String)> = values.iter().collect();
+    let mut section = String::new();
+    let mut out = String::new();
+    for line in get_default_config().lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+        }
+        let uncommented = trimmed.trim_start_matches('#').trim_start();
+        let position = remaining
+            .iter()
+            .position(|(s, k, _)| *s == section && uncommented.starts_with(&format!("{} =", k)));
+        match position {
+            Some(i) => {
+                let (_, key, value) = remaining.remove(i);
+                out += &format!("{} = {}", key, toml::Value::String(value.clone()));
+            }
+            None => out += line,
+        }
+        out.push('\n');
+    }
+    if !remaining.is_empty() {
+        let unknown: Vec<String> = remaining
+            .iter()
+            .map(|(section, key, _)| format!("{}.{}", section, key))
+            .collect();
+        return Err(format!("Unknown config entries {}", unknown.join(", ")));
     }
+    Ok(out)
 }
 
 /// Loads the default config from the default-config.toml file
@@ -69,52 +100,309 @@ pub fn get_home_dir(home_arg: Option<PathBuf>) -> PathBuf {
         (_, Some(user_home)) => PathBuf::from(&user_home),
         (Some(default_home_dir), None) => default_home_dir.join(CONFIG_FOLDER),
         (None, None) => {
-            error!("Failed to automatically determine your home directory, please provide a path to the --home argument!");
-            exit(1);
+            fail(ErrorCode::Config, "Failed to automatically determine your home directory, please provide a path to the --home argument!");
         }
     }
 }
 
-/// Load the config file, this operates at runtime
+/// Load the config file, this operates at runtime. Environment variables override
+/// the values in the file
 pub fn load_config(home_dir: &Path) -> GravityBridgeToolsConfig {
     let config_file = home_dir.join(CONFIG_FOLDER).with_file_name(CONFIG_NAME);
-    if !config_file.exists() {
-        return GravityBridgeToolsConfig::default();
+    let mut config = if config_file.exists() {
+        let config =
+            fs::read_to_string(config_file).expect("Could not find config file! Run `gbt init`");
+        match toml::from_str(&config) {
+            Ok(v) => v,
+            Err(e) => {
+                fail(ErrorCode::Config, format!("Invalid config! {:?}", e));
+            }
+        }
+    } else {
+        GravityBridgeToolsConfig::default()
+    };
+
+    if let Err(e) = apply_overrides(&mut config, |name| env::var(name).ok()) {
+        fail(
+            ErrorCode::Config,
+            format!("Invalid environment variable! {}", e),
+        );
     }
+    config
+}
 
-    let config =
-        fs::read_to_string(config_file).expect("Could not find config file! Run `gbt init`");
-    match toml::from_str(&config) {
-        Ok(v) => v,
+/// Overrides config values with the matching GBT_ environment variables, the lookup
+/// returns the value of a variable if it is set. Every value in the config has a variable,
+/// lists are comma separated and max_amounts is a comma separated list of erc20=amount
+fn apply_overrides<F: Fn(&str) -> Option<String>>(
+    config: &mut GravityBridgeToolsConfig,
+    lookup: F,
+) -> Result<(), String> {
+    let connection = &mut config.connection;
+    override_string(&lookup, "GBT_COSMOS_GRPC", &mut connection.cosmos_grpc);
+    override_string(&lookup, "GBT_ETHEREUM_RPC", &mut connection.ethereum_rpc);
+    override_string(
+        &lookup,
+        "GBT_ADDRESS_PREFIX",
+        &mut connection.address_prefix,
+    );
+    override_option(
+        &lookup,
+        "GBT_GRAVITY_CONTRACT_ADDRESS",
+        &mut connection.gravity_contract_address,
+    )?;
+
+    let keys = &mut config.keys;
+    override_option(&lookup, "GBT_COSMOS_PHRASE", &mut keys.cosmos_phrase)?;
+    override_option(&lookup, "GBT_ETHEREUM_KEY", &mut keys.ethereum_key)?;
+    override_value(&lookup, "GBT_COSMOS_COIN_TYPE", &mut keys.cosmos_coin_type)?;
+    override_option(&lookup, "GBT_COSMOS_HD_PATH", &mut keys.cosmos_hd_path)?;
+
+    let orchestrator = &mut config.orchestrator;
+    override_value(
+        &lookup,
+        "GBT_RELAYER_ENABLED",
+        &mut orchestrator.relayer_enabled,
+    )?;
+    override_value(
+        &lookup,
+        "GBT_MERGE_COSMOS_MESSAGES",
+        &mut orchestrator.merge_cosmos_messages,
+    )?;
+    override_list(
+        &lookup,
+        "GBT_SLASHING_WARNING_THRESHOLDS",
+        &mut orchestrator.slashing_warning_thresholds,
+    )?;
+
+    let relayer = &mut config.relayer;
+    override_value(
+        &lookup,
+        "GBT_VALSET_MARKET_ENABLED",
+        &mut relayer.valset_market_enabled,
+    )?;
+    override_value(
+        &lookup,
+        "GBT_BATCH_MARKET_ENABLED",
+        &mut relayer.batch_market_enabled,
+    )?;
+    override_value(
+        &lookup,
+        "GBT_LOGIC_CALL_MARKET_ENABLED",
+        &mut relayer.logic_call_market_enabled,
+    )?;
+
+    let fees = &mut config.fees;
+    override_option(&lookup, "GBT_GAS_PRICE", &mut fees.gas_price)?;
+    override_value(&lookup, "GBT_GAS_ADJUSTMENT", &mut fees.gas_adjustment)?;
+    override_option(&lookup, "GBT_MAX_GAS_PRICE", &mut fees.max_gas_price)?;
+
+    let policy = &mut config.signing_policy;
+    override_value(&lookup, "GBT_SIGNING_POLICY_ENABLED", &mut policy.enabled)?;
+    override_option(
+        &lookup,
+        "GBT_VERIFICATION_GRPC",
+        &mut policy.verification_grpc,
+    )?;
+    override_list(
+        &lookup,
+        "GBT_ALLOWED_LOGIC_CONTRACTS",
+        &mut policy.allowed_logic_contracts,
+    )?;
+    if let Some(v) = lookup("GBT_MAX_AMOUNTS") {
+        let mut max_amounts = HashMap::new();
+        for entry in v.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((erc20, amount)) => {
+                    max_amounts.insert(erc20.trim().to_string(), amount.trim().to_string())
+                }
+                None => return Err(format!("GBT_MAX_AMOUNTS {} is not erc20=amount", entry)),
+            };
+        }
+        policy.max_amounts = max_amounts;
+    }
+    Ok(())
+}
+
+fn override_string<F: Fn(&str) -> Option<String>>(lookup: &F, name: &str, value: &mut String) {
+    if let Some(v) = lookup(name) {
+        *value = v;
+    }
+}
+
+fn override_value<F, T>(lookup: &F, name: &str, value: &mut T) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+    T::Err: Debug,
+{
+    if let Some(v) = lookup(name) {
+        *value = v
+            .trim()
+            .parse()
+            .map_err(|e| format!("{} {} {:?}", name, v, e))?;
+    }
+    Ok(())
+}
+
+/// An empty variable clears an optional value
+fn override_option<F, T>(lookup: &F, name: &str, value: &mut Option<T>) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+    T::Err: Debug,
+{
+    if let Some(v) = lookup(name) {
+        *value = if v.trim().is_empty() {
+            None
+        } else {
+            Some(
+                v.trim()
+                    .parse()
+                    .map_err(|e| format!("{} {} {:?}", name, v, e))?,
+            )
+        };
+    }
+    Ok(())
+}
+
+fn override_list<F, T>(lookup: &F, name: &str, value: &mut Vec<T>) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+    T::Err: Debug,
+{
+    if let Some(v) = lookup(name) {
+        *value = v
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| item.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("{} {} {:?}", name, v, e))?;
+    }
+    Ok(())
+}
+
+/// Derives the key for a Cosmos phrase given on the command line, using the hd path and
+/// coin type from the config so that it's the same key the config phrase would give
+pub fn parse_cosmos_phrase(phrase: &str, keys: &KeysConfig) -> CosmosPrivateKey {
+    match cosmos_key_from_phrase(phrase, &keys.cosmos_hd_path()) {
+        Ok(k) => k,
         Err(e) => {
-            error!("Invalid config! {:?}", e);
-            exit(1);
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Invalid Cosmos phrase {}", e),
+            );
         }
     }
 }
 
+/// Parses the keys provided through the config or environment, exiting if they are invalid
+pub fn parse_config_keys(keys: &KeysConfig) -> (Option<CosmosPrivateKey>, Option<EthPrivateKey>) {
+    let cosmos_key = keys.cosmos_phrase.as_ref().map(|phrase| {
+        match cosmos_key_from_phrase(phrase, &keys.cosmos_hd_path()) {
+            Ok(k) => k,
+            Err(e) => {
+                fail(
+                    ErrorCode::Config,
+                    format!("Invalid cosmos_phrase in config {}", e),
+                );
+            }
+        }
+    });
+    let ethereum_key = keys.ethereum_key.as_ref().map(|key| match key.parse() {
+        Ok(k) => k,
+        Err(e) => {
+            fail(
+                ErrorCode::Config,
+                format!("Invalid ethereum_key in config {:?}", e),
+            );
+        }
+    });
+    (cosmos_key, ethereum_key)
+}
+
+/// Parses the Gravity contract address provided through the config or environment,
+/// exiting if it is invalid
+pub fn parse_config_gravity_address(connection: &ConnectionConfig) -> Option<EthAddress> {
+    connection
+        .gravity_contract_address
+        .as_ref()
+        .map(|address| match address.parse() {
+            Ok(a) => a,
+            Err(e) => {
+                fail(
+                    ErrorCode::Config,
+                    format!("Invalid gravity_contract_address in config {:?}", e),
+                );
+            }
+        })
+}
+
+/// Prints the effective config, after environment and command line overrides, with
+/// any keys redacted
+pub fn show_config(mut config: GravityBridgeToolsConfig, opts: ConfigShowOpts) {
+    if let Some(cosmos_grpc) = opts.cosmos_grpc {
+        config.connection.cosmos_grpc = cosmos_grpc;
+    }
+    if let Some(ethereum_rpc) = opts.ethereum_rpc {
+        config.connection.ethereum_rpc = ethereum_rpc;
+    }
+    if let Some(gravity_contract_address) = opts.gravity_contract_address {
+        config.connection.gravity_contract_address = Some(gravity_contract_address.to_string());
+    }
+    if let Some(gas_price) = opts.gas_price {
+        config.fees.gas_price = Some(gas_price);
+    }
+    let redacted = Some("<redacted>".to_string());
+    if config.keys.cosmos_phrase.is_some() {
+        config.keys.cosmos_phrase = redacted.clone();
+    }
+    if config.keys.ethereum_key.is_some() {
+        config.keys.ethereum_key = redacted;
+    }
+    if is_json() {
+        print_result(&config);
+        return;
+    }
+    // converting to a toml value first places plain values ahead of tables as toml requires
+    let config = toml::Value::try_from(&config).unwrap();
+    println!("{}", config);
+}
+
 /// Load the keys file, this operates at runtime
 pub fn load_keys(home_dir: &Path) -> KeyStorage {
     let keys_file = home_dir.join(CONFIG_FOLDER).with_file_name(KEYS_NAME);
     if !keys_file.exists() {
-        error!(
-            "Keys file at {} not detected, use `gbt init` to generate a config.",
-            keys_file.to_str().unwrap()
+        fail(
+            ErrorCode::Config,
+            format!(
+                "Keys file at {} not detected, use `gbt init` to generate a config.",
+                keys_file.to_str().unwrap()
+            ),
         );
-        exit(1);
     }
 
-    let keys = fs::read_to_string(keys_file).unwrap();
-    match toml::from_str(&keys) {
+    match read_keys(home_dir) {
         Ok(v) => v,
         Err(e) => {
-            error!("Invalid keys! {:?}", e);
-            exit(1);
+            fail(ErrorCode::Config, format!("Invalid keys! {}", e));
         }
     }
 }
 
-/// Saves the keys file, overwriting the existing one
+/// Reads the keys file without exiting on failure, used by long running
+/// processes that reload the keys after they have started
+pub fn read_keys(home_dir: &Path) -> Result<KeyStorage, String> {
+    let keys_file = home_dir.join(CONFIG_FOLDER).with_file_name(KEYS_NAME);
+    let keys = fs::read_to_string(keys_file).map_err(|e| format!("{:?}", e))?;
+    toml::from_str(&keys).map_err(|e| format!("{:?}", e))
+}
+
+/// Saves the keys file, overwriting the existing one. The new file is written
+/// next to the old one and then renamed over it so that a running Orchestrator
+/// never reads a partially written file
 pub fn save_keys(home_dir: &Path, updated_keys: KeyStorage) {
     let config_file = home_dir.join(CONFIG_FOLDER).with_file_name(KEYS_NAME);
     if !config_file.exists() {
@@ -124,11 +412,39 @@ pub fn save_keys(home_dir: &Path, updated_keys: KeyStorage) {
         );
     }
 
-    fs::write(
-        home_dir.join(KEYS_NAME),
-        toml::to_string(&updated_keys).unwrap(),
-    )
-    .expect("Unable to write config file");
+    let temp_file = home_dir.join(format!("{}.tmp", KEYS_NAME));
+    fs::write(&temp_file, toml::to_string(&updated_keys).unwrap())
+        .expect("Unable to write config file");
+    fs::rename(temp_file, home_dir.join(KEYS_NAME)).expect("Unable to write config file");
+}
+
+/// Saves a rendered config, replacing the file atomically like save_keys
+pub fn save_config(home_dir: &Path, contents: &str) {
+    let temp_file = home_dir.join(format!("{}.tmp", CONFIG_NAME));
+    fs::write(&temp_file, contents).expect("Unable to write config file");
+    fs::rename(temp_file, home_dir.join(CONFIG_NAME)).expect("Unable to write config file");
+}
+
+/// Loads the approved checkpoints, a missing or unreadable file means nothing is approved
+pub fn load_approvals(home_dir: &Path) -> ApprovalStorage {
+    match fs::read_to_string(home_dir.join(APPROVALS_NAME)) {
+        Ok(approvals) => match toml::from_str(&approvals) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("Invalid approvals file! {:?}", e);
+                ApprovalStorage::default()
+            }
+        },
+        Err(_) => ApprovalStorage::default(),
+    }
+}
+
+/// Saves the approved checkpoints, replacing the file atomically like save_keys
+pub fn save_approvals(home_dir: &Path, approvals: ApprovalStorage) {
+    let temp_file = home_dir.join(format!("{}.tmp", APPROVALS_NAME));
+    fs::write(&temp_file, toml::to_string(&approvals).unwrap())
+        .expect("Unable to write approvals file");
+    fs::rename(temp_file, home_dir.join(APPROVALS_NAME)).expect("Unable to write approvals file");
 }
 
 #[cfg(test)]
@@ -142,4 +458,130 @@ mod tests {
         let res: GravityBridgeToolsConfig = toml::from_str(&get_default_config()).unwrap();
         assert_eq!(res, GravityBridgeToolsConfig::default())
     }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut config = GravityBridgeToolsConfig::default();
+        apply_overrides(&mut config, |name| match name {
+            "GBT_ETHEREUM_RPC" => Some("http://eth:8545".to_string()),
+            "GBT_GAS_PRICE" => Some("0.025ugraviton".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.connection.ethereum_rpc, "http://eth:8545");
+        assert_eq!(
+            config.connection.cosmos_grpc,
+            GravityBridgeToolsConfig::default().connection.cosmos_grpc
+        );
+        assert!(config.fees.gas_price.is_some());
+
+        let res = apply_overrides(&mut config, |name| match name {
+            "GBT_GAS_PRICE" => Some("cheap".to_string()),
+            _ => None,
+        });
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_every_section() {
+        let erc20 = "0xD50c0953a99325d01cca655E57070F1be4983b6b";
+        let mut config = GravityBridgeToolsConfig {
+            fees: gravity_utils::types::FeeConfig {
+                gas_price: Some("0.025ugraviton".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        apply_overrides(&mut config, |name| {
+            let v = match name {
+                "GBT_COSMOS_COIN_TYPE" => "60",
+                "GBT_RELAYER_ENABLED" => "false",
+                "GBT_SLASHING_WARNING_THRESHOLDS" => "500, 50",
+                "GBT_VALSET_MARKET_ENABLED" => "true",
+                "GBT_GAS_ADJUSTMENT" => "1.5",
+                "GBT_MAX_GAS_PRICE" => "0.1ugraviton",
+                "GBT_GAS_PRICE" => "",
+                "GBT_SIGNING_POLICY_ENABLED" => "true",
+                "GBT_VERIFICATION_GRPC" => "http://verifier:9090",
+                "GBT_ALLOWED_LOGIC_CONTRACTS" => erc20,
+                "GBT_MAX_AMOUNTS" => "0xD50c0953a99325d01cca655E57070F1be4983b6b=1000",
+                _ => return None,
+            };
+            Some(v.to_string())
+        })
+        .unwrap();
+        assert_eq!(config.keys.cosmos_coin_type, 60);
+        assert!(!config.orchestrator.relayer_enabled);
+        assert_eq!(
+            config.orchestrator.slashing_warning_thresholds,
+            vec![500, 50]
+        );
+        assert!(config.relayer.valset_market_enabled);
+        assert!((config.fees.gas_adjustment - 1.5).abs() < f64::EPSILON);
+        assert_eq!(
+            config.fees.max_gas_price,
+            Some("0.1ugraviton".parse().unwrap())
+        );
+        // an empty variable clears an optional value
+        assert_eq!(config.fees.gas_price, None);
+        assert!(config.signing_policy.enabled);
+        assert_eq!(
+            config.signing_policy.verification_grpc,
+            Some("http://verifier:9090".to_string())
+        );
+        assert_eq!(config.signing_policy.allowed_logic_contracts, vec![erc20]);
+        assert_eq!(config.signing_policy.max_amounts[erc20], "1000");
+
+        for (name, value) in [
+            ("GBT_RELAYER_ENABLED", "maybe"),
+            ("GBT_SLASHING_WARNING_THRESHOLDS", "100,soon"),
+            ("GBT_MAX_AMOUNTS", "1000"),
+        ]
+        .iter()
+        {
+            let res = apply_overrides(&mut config, |n| {
+                if n == *name {
+                    Some(value.to_string())
+                } else {
+                    None
+                }
+            });
+            assert!(res.is_err(), "{} {}", name, value);
+        }
+    }
+
+    #[test]
+    fn test_render_config() {
+        let rendered = render_config(&[
+            ("connection", "cosmos_grpc", "http://grpc:9090".to_string()),
+            ("connection", "address_prefix", "gravity".to_string()),
+            (
+                "connection",
+                "gravity_contract_address",
+                "0xD50c0953a99325d01cca655E57070F1be4983b6b".to_string(),
+            ),
+            ("fees", "gas_price", "0.025ugraviton".to_string()),
+        ])
+        .unwrap();
+        let config: GravityBridgeToolsConfig = toml::from_str(&rendered).unwrap();
+        assert_eq!(config.connection.cosmos_grpc, "http://grpc:9090");
+        assert_eq!(config.connection.address_prefix, "gravity");
+        assert!(parse_config_gravity_address(&config.connection).is_some());
+        assert_eq!(
+            config.fees.gas_price,
+            Some("0.025ugraviton".parse().unwrap())
+        );
+        // the max_gas_price example must not be mistaken for gas_price
+        assert_eq!(config.fees.max_gas_price, None);
+        assert_eq!(
+            config.orchestrator,
+            GravityBridgeToolsConfig::default().orchestrator
+        );
+
+        let res = render_config(&[("connection", "grpc_url", "http://grpc:9090".to_string())]);
+        assert_eq!(
+            res,
+            Err("Unknown config entries connection.grpc_url".to_string())
+        );
+    }
 }
@@ -0,0 +1,668 @@
+//! Preflight checks for the Orchestrator, run by `gbt doctor` and automatically when the
+//! Orchestrator starts. Every check is run and reported together so that an operator can
+//! fix everything in one pass instead of discovering problems one restart at a time.
+
+use crate::args::DoctorOpts;
+use crate::client::track_deposit::get_balance;
+use crate::config::{load_keys, parse_config_gravity_address, parse_config_keys};
+use crate::output::{fail, print_result, ErrorCode};
+use crate::utils::{format_amount, get_fee_settings, TIMEOUT};
+use clarity::Address as EthAddress;
+use clarity::Uint256;
+use cosmos_gravity::query::{
+    get_attestations, get_delegate_keys_by_eth, get_delegate_keys_by_orchestrator,
+    get_gravity_params,
+};
+use deep_space::client::ChainStatus;
+use deep_space::{Address as CosmosAddress, Contact};
+use ethereum_gravity::utils::get_gravity_id;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_proto::gravity::Params;
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::error::GravityError;
+use gravity_utils::hd_wallet::cosmos_key_from_phrase;
+use gravity_utils::types::{FeeSettings, GravityBridgeToolsConfig};
+use serde_json::{json, Value};
+use std::path::Path;
+use tonic::transport::Channel;
+use tonic::Code;
+use web30::client::Web3;
+
+/// Cosmos gas used by a typical claim or confirm transaction, used to estimate fee usage
+const ESTIMATED_COSMOS_GAS_PER_TX: u64 = 200_000;
+/// Ethereum gas used by a typical valset update or batch submission, used to estimate
+/// the cost of relaying
+const ESTIMATED_ETH_GAS_PER_RELAY: u64 = 500_000;
+/// The number of days of fees the Orchestrator checks for when it starts
+pub const DEFAULT_BALANCE_DAYS: u64 = 7;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckResult {
+    Pass(String),
+    /// The Orchestrator can run, but the operator should act soon
+    Warn(String),
+    /// The Orchestrator can not work correctly
+    Fail(String),
+    /// The check could not be completed because a node did not respond, this may be
+    /// transient so the Orchestrator retries these when it starts
+    Unavailable(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub result: CheckResult,
+}
+
+impl Check {
+    fn new(name: &'static str, result: CheckResult) -> Check {
+        Check { name, result }
+    }
+
+    fn to_json(&self) -> Value {
+        let (status, message) = match &self.result {
+            CheckResult::Pass(msg) => ("pass", msg),
+            CheckResult::Warn(msg) => ("warn", msg),
+            CheckResult::Fail(msg) => ("fail", msg),
+            CheckResult::Unavailable(msg) => ("unavailable", msg),
+        };
+        json!({ "name": self.name, "status": status, "message": message })
+    }
+}
+
+/// What the checks are run against
+pub struct DoctorTarget {
+    /// The registered delegate Cosmos address
+    pub delegate_address: CosmosAddress,
+    pub ethereum_address: EthAddress,
+    /// The Gravity contract the Orchestrator is configured to use, None if it
+    /// is taken from the Gravity params
+    pub gravity_contract: Option<EthAddress>,
+    pub fee: Option<FeeSettings>,
+    /// If the Orchestrator relays and spends ETH doing so
+    pub relayer_enabled: bool,
+    /// The number of days the balances should last
+    pub days: u64,
+}
+
+pub async fn doctor(
+    opts: DoctorOpts,
+    address_prefix: String,
+    home_dir: &Path,
+    config: &GravityBridgeToolsConfig,
+) {
+    let (config_cosmos_key, config_ethereum_key) = parse_config_keys(&config.keys);
+    let stored_keys = if home_dir.exists() {
+        Some(load_keys(home_dir))
+    } else {
+        None
+    };
+    let stored_cosmos_key = stored_keys
+        .as_ref()
+        .and_then(|k| k.orchestrator_phrase.as_ref())
+        .and_then(|p| cosmos_key_from_phrase(p, &config.keys.cosmos_hd_path()).ok());
+    let stored_ethereum_key = stored_keys.as_ref().and_then(|k| k.ethereum_key);
+
+    let delegate_address = match opts.orchestrator_address.or_else(|| {
+        config_cosmos_key
+            .or(stored_cosmos_key)
+            .map(|k| k.to_address(&address_prefix).unwrap())
+    }) {
+        Some(address) => address,
+        None => {
+            fail(
+                ErrorCode::Config,
+                "No Orchestrator key is configured, pass --orchestrator-address",
+            );
+        }
+    };
+    let ethereum_address = match opts.ethereum_address.or_else(|| {
+        config_ethereum_key
+            .or(stored_ethereum_key)
+            .map(|k| k.to_public_key().unwrap())
+    }) {
+        Some(address) => address,
+        None => {
+            fail(
+                ErrorCode::Config,
+                "No Ethereum key is configured, pass --ethereum-address",
+            );
+        }
+    };
+    let fee = if opts.gas_price.is_some() || config.fees.gas_price.is_some() {
+        Some(get_fee_settings(opts.gas_price, &config.fees))
+    } else {
+        None
+    };
+
+    let cosmos_grpc = opts
+        .cosmos_grpc
+        .unwrap_or_else(|| config.connection.cosmos_grpc.clone());
+    // only the first of several failover servers is checked
+    let cosmos_grpc = cosmos_grpc.split(',').next().unwrap().trim().to_string();
+    let ethereum_rpc = opts
+        .ethereum_rpc
+        .unwrap_or_else(|| config.connection.ethereum_rpc.clone());
+    let connections = create_rpc_connections(
+        address_prefix,
+        Some(cosmos_grpc),
+        Some(ethereum_rpc),
+        TIMEOUT,
+    )
+    .await;
+    let contact = connections.contact.unwrap();
+    let mut grpc = connections.grpc.unwrap();
+    let web3 = connections.web3.unwrap();
+
+    let target = DoctorTarget {
+        delegate_address,
+        ethereum_address,
+        gravity_contract: opts
+            .gravity_contract_address
+            .or_else(|| parse_config_gravity_address(&config.connection)),
+        fee,
+        relayer_enabled: config.orchestrator.relayer_enabled,
+        days: opts.days,
+    };
+    let checks = run_checks(&contact, &mut grpc, &web3, &target).await;
+    // unlike at startup a check that could not be completed is a failure here
+    if !report(&checks) || has_unavailable(&checks) {
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|c| matches!(c.result, CheckResult::Fail(_) | CheckResult::Unavailable(_)))
+            .map(|c| c.name)
+            .collect();
+        fail(
+            ErrorCode::CheckFailed,
+            format!("Preflight checks failed: {}", failed.join(", ")),
+        );
+    }
+    let checks: Vec<Value> = checks.iter().map(Check::to_json).collect();
+    print_result(&json!({ "checks": checks }));
+}
+
+/// Runs every check, checks that depend on a connection that failed are reported as failed
+pub async fn run_checks(
+    contact: &Contact,
+    grpc: &mut GravityQueryClient<Channel>,
+    web3: &Web3,
+    target: &DoctorTarget,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    checks.push(Check::new("Cosmos node", check_node_status(contact).await));
+
+    let params = match get_gravity_params(grpc).await {
+        Ok(params) => params,
+        Err(e) => {
+            checks.push(Check::new(
+                "Gravity params",
+                query_error(format!("Failed to get the Gravity params {:?}", e), &e),
+            ));
+            return checks;
+        }
+    };
+
+    let (contract_check, gravity_contract) = check_contract_address(&params, target);
+    checks.push(Check::new("Gravity contract", contract_check));
+    if let Some(gravity_contract) = gravity_contract {
+        checks.push(Check::new(
+            "Gravity id",
+            check_gravity_id(&params, gravity_contract, target.ethereum_address, web3).await,
+        ));
+    }
+    checks.push(Check::new(
+        "Ethereum chain id",
+        check_chain_id(&params, web3).await,
+    ));
+    checks.push(Check::new(
+        "Delegate keys",
+        check_delegate_keys(grpc, target).await,
+    ));
+
+    let relays_per_day = estimate_events_per_day(grpc, &params).await;
+    checks.push(Check::new(
+        "Cosmos fee balance",
+        check_fee_balance(contact, target, relays_per_day).await,
+    ));
+    checks.push(Check::new(
+        "Ethereum balance",
+        check_eth_balance(web3, target, relays_per_day).await,
+    ));
+    checks
+}
+
+/// Logs every check, returns false if any of them failed. Checks that could not be
+/// completed are only warned about
+pub fn report(checks: &[Check]) -> bool {
+    let mut ok = true;
+    for check in checks {
+        match &check.result {
+            CheckResult::Pass(msg) => info!("[ OK ] {}: {}", check.name, msg),
+            CheckResult::Warn(msg) => warn!("[WARN] {}: {}", check.name, msg),
+            CheckResult::Unavailable(msg) => warn!("[ ?? ] {}: {}", check.name, msg),
+            CheckResult::Fail(msg) => {
+                error!("[FAIL] {}: {}", check.name, msg);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+/// If any check could not be completed because a node did not respond
+pub fn has_unavailable(checks: &[Check]) -> bool {
+    checks
+        .iter()
+        .any(|c| matches!(c.result, CheckResult::Unavailable(_)))
+}
+
+/// If a Gravity query failed because the node could not be reached rather than because
+/// of what it returned. Failing to connect shows up as an Unknown status from tonic
+fn is_unreachable(error: &GravityError) -> bool {
+    match error {
+        GravityError::GravityGrpcError(status) => match status.code() {
+            Code::Unavailable | Code::DeadlineExceeded | Code::Cancelled => true,
+            Code::Unknown => status.message().starts_with("Service was not ready"),
+            _ => false,
+        },
+        GravityError::CosmosGrpcError(_) | GravityError::EthereumRestError(_) => true,
+        _ => false,
+    }
+}
+
+fn query_error(message: String, error: &GravityError) -> CheckResult {
+    if is_unreachable(error) {
+        CheckResult::Unavailable(message)
+    } else {
+        CheckResult::Fail(message)
+    }
+}
+
+async fn check_node_status(contact: &Contact) -> CheckResult {
+    match contact.get_chain_status().await {
+        Ok(ChainStatus::Moving { block_height }) => {
+            CheckResult::Pass(format!("synced at block {}", block_height))
+        }
+        Ok(ChainStatus::Syncing) => CheckResult::Fail("the node is still syncing".to_string()),
+        Ok(ChainStatus::WaitingToStart) => {
+            CheckResult::Fail("the chain has not started yet".to_string())
+        }
+        Err(e) => CheckResult::Unavailable(format!("failed to get the node status {:?}", e)),
+    }
+}
+
+/// Compares the configured contract with bridge_ethereum_address, returns the
+/// contract to use for the remaining checks
+fn check_contract_address(
+    params: &Params,
+    target: &DoctorTarget,
+) -> (CheckResult, Option<EthAddress>) {
+    let bridge_address: Option<EthAddress> = params.bridge_ethereum_address.parse().ok();
+    match (target.gravity_contract, bridge_address) {
+        (Some(configured), Some(bridge)) if configured == bridge => (
+            CheckResult::Pass(format!("{} matches the Gravity params", configured)),
+            Some(configured),
+        ),
+        (Some(configured), Some(bridge)) => (
+            CheckResult::Fail(format!(
+                "configured contract {} does not match bridge_ethereum_address {}",
+                configured, bridge
+            )),
+            Some(configured),
+        ),
+        (None, Some(bridge)) => (
+            CheckResult::Pass(format!("using {} from the Gravity params", bridge)),
+            Some(bridge),
+        ),
+        (Some(configured), None) => (
+            CheckResult::Warn(format!(
+                "bridge_ethereum_address is not set, can not verify {}",
+                configured
+            )),
+            Some(configured),
+        ),
+        (None, None) => (
+            CheckResult::Fail(
+                "bridge_ethereum_address is not set, configure gravity_contract_address"
+                    .to_string(),
+            ),
+            None,
+        ),
+    }
+}
+
+async fn check_gravity_id(
+    params: &Params,
+    gravity_contract: EthAddress,
+    caller: EthAddress,
+    web3: &Web3,
+) -> CheckResult {
+    match get_gravity_id(gravity_contract, caller, web3).await {
+        Ok(contract_id) => {
+            // the contract stores the id as a null padded bytes32
+            let contract_id = contract_id.trim_end_matches('\0');
+            if contract_id == params.gravity_id {
+                CheckResult::Pass(format!("{} matches the contract", contract_id))
+            } else {
+                CheckResult::Fail(format!(
+                    "gravity_id {} does not match the contract's state_gravityId {}, signatures will be rejected",
+                    params.gravity_id, contract_id
+                ))
+            }
+        }
+        Err(e) => query_error(
+            format!(
+                "failed to get state_gravityId from {} {:?}",
+                gravity_contract, e
+            ),
+            &e,
+        ),
+    }
+}
+
+/// net_version is the network id, which is not the chain id on every network, so the
+/// chain id signatures are bound to is compared using eth_chainId
+async fn check_chain_id(params: &Params, web3: &Web3) -> CheckResult {
+    match web3.eth_chainid().await {
+        Ok(Some(chain_id)) => compare_chain_id(&chain_id, params.bridge_chain_id),
+        Ok(None) => CheckResult::Unavailable("the Ethereum node returned no chain id".to_string()),
+        Err(e) => CheckResult::Unavailable(format!("failed to get the Ethereum chain id {:?}", e)),
+    }
+}
+
+fn compare_chain_id(chain_id: &Uint256, bridge_chain_id: u64) -> CheckResult {
+    if *chain_id == Uint256::from(bridge_chain_id) {
+        CheckResult::Pass(format!("{} matches bridge_chain_id", chain_id))
+    } else {
+        CheckResult::Fail(format!(
+            "Ethereum node is on chain {} but bridge_chain_id is {}",
+            chain_id, bridge_chain_id
+        ))
+    }
+}
+
+async fn check_delegate_keys(
+    grpc: &mut GravityQueryClient<Channel>,
+    target: &DoctorTarget,
+) -> CheckResult {
+    let by_eth = get_delegate_keys_by_eth(grpc, target.ethereum_address).await;
+    let by_orchestrator = get_delegate_keys_by_orchestrator(grpc, target.delegate_address).await;
+    match (by_eth, by_orchestrator) {
+        (Ok((validator, orchestrator)), Ok((other_validator, eth_address))) => {
+            if orchestrator != target.delegate_address {
+                CheckResult::Fail(format!(
+                    "{} is registered with Orchestrator {}, not {}",
+                    target.ethereum_address, orchestrator, target.delegate_address
+                ))
+            } else if eth_address != target.ethereum_address {
+                CheckResult::Fail(format!(
+                    "{} is registered with Ethereum address {}, not {}",
+                    target.delegate_address, eth_address, target.ethereum_address
+                ))
+            } else if validator != other_validator {
+                CheckResult::Fail(format!(
+                    "the keys are registered to two different validators {} and {}",
+                    validator, other_validator
+                ))
+            } else {
+                CheckResult::Pass(format!("registered for validator {}", validator))
+            }
+        }
+        (Err(e), _) | (_, Err(e)) if is_unreachable(&e) => {
+            CheckResult::Unavailable(format!("failed to get the delegate keys {:?}", e))
+        }
+        (Err(_), Err(_)) => CheckResult::Fail(
+            "delegate keys are not registered, use `gbt keys register-orchestrator-address`"
+                .to_string(),
+        ),
+        (Err(_), Ok(_)) => CheckResult::Fail(format!(
+            "Ethereum address {} is not registered, check your Ethereum key",
+            target.ethereum_address
+        )),
+        (Ok(_), Err(_)) => CheckResult::Fail(format!(
+            "Orchestrator address {} is not registered, check your Cosmos phrase",
+            target.delegate_address
+        )),
+    }
+}
+
+/// Estimates how many Ethereum events the bridge processes per day from the heights of
+/// recent attestations, every event costs each Orchestrator a claim and may cost a
+/// relayer a submission. Returns None if there is not enough history
+async fn estimate_events_per_day(
+    grpc: &mut GravityQueryClient<Channel>,
+    params: &Params,
+) -> Option<f64> {
+    let attestations = get_attestations(grpc, None).await.ok()?;
+    let heights: Vec<u64> = attestations.iter().map(|a| a.height).collect();
+    events_per_day(&heights, params.average_block_time)
+}
+
+fn events_per_day(heights: &[u64], average_block_time_ms: u64) -> Option<f64> {
+    let min = *heights.iter().min()?;
+    let max = *heights.iter().max()?;
+    if max == min || average_block_time_ms == 0 {
+        return None;
+    }
+    let blocks_per_day = 86_400_000f64 / average_block_time_ms as f64;
+    Some(heights.len() as f64 / (max - min) as f64 * blocks_per_day)
+}
+
+async fn check_fee_balance(
+    contact: &Contact,
+    target: &DoctorTarget,
+    events_per_day: Option<f64>,
+) -> CheckResult {
+    let fee = match &target.fee {
+        Some(fee) => fee,
+        None => {
+            return CheckResult::Warn(
+                "no gas price is configured, can not check the fee balance".to_string(),
+            )
+        }
+    };
+    let denom = &fee.gas_price.denom;
+    let balance = match get_balance(contact, target.delegate_address, denom).await {
+        Some(balance) => balance,
+        None => {
+            return CheckResult::Unavailable(format!(
+                "failed to get balances for {}",
+                target.delegate_address
+            ))
+        }
+    };
+    if balance == Uint256::from(0u8) {
+        return CheckResult::Fail(format!("{} holds no {}", target.delegate_address, denom));
+    }
+    let daily_cost = events_per_day.map(|events| {
+        // every event needs a claim, valsets and batches also need a confirm
+        let txs = (events * 2f64).ceil() as u64;
+        let fee_per_tx = fee.fee_for_gas(fee.gas_limit(ESTIMATED_COSMOS_GAS_PER_TX));
+        fee_per_tx.amount * Uint256::from(txs)
+    });
+    check_balance_lasts(
+        format!("{}{}", balance, denom),
+        balance,
+        daily_cost.map(|c| (c.clone(), format!("{}{}", c, denom))),
+        target.days,
+    )
+}
+
+async fn check_eth_balance(
+    web3: &Web3,
+    target: &DoctorTarget,
+    events_per_day: Option<f64>,
+) -> CheckResult {
+    let balance = match web3.eth_get_balance(target.ethereum_address).await {
+        Ok(balance) => balance,
+        Err(e) => {
+            return CheckResult::Unavailable(format!("failed to get the ETH balance {:?}", e))
+        }
+    };
+    if balance == Uint256::from(0u8) {
+        return CheckResult::Fail(format!("{} holds no ETH", target.ethereum_address));
+    }
+    if !target.relayer_enabled {
+        return CheckResult::Pass(format!(
+            "{} ETH, the relayer is disabled",
+            format_amount(&balance, 18)
+        ));
+    }
+    // at worst our relayer submits everything
+    let daily_cost = match (events_per_day, web3.eth_gas_price().await) {
+        (Some(events), Ok(gas_price)) => {
+            let gas: Uint256 =
+                ((events * ESTIMATED_ETH_GAS_PER_RELAY as f64).ceil() as u128).into();
+            let cost = gas * gas_price;
+            let label = format!("{} ETH", format_amount(&cost, 18));
+            Some((cost, label))
+        }
+        _ => None,
+    };
+    check_balance_lasts(
+        format!("{} ETH", format_amount(&balance, 18)),
+        balance,
+        daily_cost,
+        target.days,
+    )
+}
+
+fn check_balance_lasts(
+    balance_label: String,
+    balance: Uint256,
+    daily_cost: Option<(Uint256, String)>,
+    days: u64,
+) -> CheckResult {
+    match daily_cost {
+        Some((cost, cost_label)) => {
+            let needed = cost * Uint256::from(days);
+            if balance >= needed {
+                CheckResult::Pass(format!(
+                    "{} covers {} days at about {} per day",
+                    balance_label, days, cost_label
+                ))
+            } else {
+                CheckResult::Warn(format!(
+                    "{} will not last {} days at about {} per day",
+                    balance_label, days, cost_label
+                ))
+            }
+        }
+        None => CheckResult::Pass(format!(
+            "{}, not enough bridge activity to estimate usage",
+            balance_label
+        )),
+    }
+}
+
+#[test]
+fn test_events_per_day() {
+    // 10 events over 1000 blocks of 5 seconds
+    let heights: Vec<u64> = (0..10).map(|i| 100 + i * 111).collect();
+    let rate = events_per_day(&heights, 5000).unwrap();
+    assert!((rate - 10f64 / 999f64 * 17280f64).abs() < 0.001);
+    assert_eq!(events_per_day(&[5], 5000), None);
+    assert_eq!(events_per_day(&[], 5000), None);
+}
+
+#[cfg(test)]
+fn test_target(gravity_contract: Option<EthAddress>) -> DoctorTarget {
+    let address: CosmosAddress = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu"
+        .parse()
+        .unwrap();
+    DoctorTarget {
+        delegate_address: address,
+        ethereum_address: "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+            .parse()
+            .unwrap(),
+        gravity_contract,
+        fee: None,
+        relayer_enabled: true,
+        days: DEFAULT_BALANCE_DAYS,
+    }
+}
+
+#[test]
+fn test_check_contract_address() {
+    let bridge: EthAddress = "0xD50c0953a99325d01cca655E57070F1be4983b6b"
+        .parse()
+        .unwrap();
+    let other: EthAddress = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+        .parse()
+        .unwrap();
+    let params = Params {
+        bridge_ethereum_address: bridge.to_string(),
+        ..Default::default()
+    };
+    let (result, contract) = check_contract_address(&params, &test_target(None));
+    assert!(matches!(result, CheckResult::Pass(_)));
+    assert_eq!(contract, Some(bridge));
+    let (result, _) = check_contract_address(&params, &test_target(Some(bridge)));
+    assert!(matches!(result, CheckResult::Pass(_)));
+    let (result, contract) = check_contract_address(&params, &test_target(Some(other)));
+    assert!(matches!(result, CheckResult::Fail(_)));
+    assert_eq!(contract, Some(other));
+
+    let unset = Params::default();
+    let (result, _) = check_contract_address(&unset, &test_target(Some(other)));
+    assert!(matches!(result, CheckResult::Warn(_)));
+    let (result, contract) = check_contract_address(&unset, &test_target(None));
+    assert!(matches!(result, CheckResult::Fail(_)));
+    assert_eq!(contract, None);
+}
+
+#[test]
+fn test_compare_chain_id() {
+    assert!(matches!(
+        compare_chain_id(&Uint256::from(5u8), 5),
+        CheckResult::Pass(_)
+    ));
+    assert!(matches!(
+        compare_chain_id(&Uint256::from(1u8), 5),
+        CheckResult::Fail(_)
+    ));
+}
+
+#[test]
+fn test_check_balance_lasts() {
+    let lasts = |balance: u64, daily: Option<u64>| {
+        check_balance_lasts(
+            String::new(),
+            balance.into(),
+            daily.map(|d| (d.into(), String::new())),
+            7,
+        )
+    };
+    assert!(matches!(lasts(70, Some(10)), CheckResult::Pass(_)));
+    assert!(matches!(lasts(69, Some(10)), CheckResult::Warn(_)));
+    // without enough history to estimate usage any balance passes
+    assert!(matches!(lasts(1, None), CheckResult::Pass(_)));
+}
+
+#[test]
+fn test_unavailable() {
+    use tonic::Status;
+    let unreachable = GravityError::GravityGrpcError(Status::unavailable("connection refused"));
+    assert!(is_unreachable(&unreachable));
+    let not_ready =
+        GravityError::GravityGrpcError(Status::unknown("Service was not ready: transport error"));
+    assert!(is_unreachable(&not_ready));
+    let rejected = GravityError::GravityGrpcError(Status::unknown("No validator"));
+    assert!(!is_unreachable(&rejected));
+    assert!(matches!(
+        query_error(String::new(), &rejected),
+        CheckResult::Fail(_)
+    ));
+
+    // a check that could not be completed does not fail the report, callers decide
+    let checks = vec![
+        Check::new("a", CheckResult::Pass(String::new())),
+        Check::new("b", CheckResult::Unavailable(String::new())),
+    ];
+    assert!(report(&checks));
+    assert!(has_unavailable(&checks));
+    let checks = vec![Check::new("a", CheckResult::Fail(String::new()))];
+    assert!(!report(&checks));
+    assert!(!has_unavailable(&checks));
+}
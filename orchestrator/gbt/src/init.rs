@@ -0,0 +1,447 @@
+//! The `gbt init` setup wizard, asks for and tests the connection details, detects what it
+//! can from the chain, sets up the delegate keys and writes a config ready to run the
+//! Orchestrator. With --yes nothing is asked, command line values and defaults are used
+
+use crate::args::InitOpts;
+use crate::config::{parse_cosmos_phrase, read_keys, render_config, save_config, save_keys};
+use crate::config::{KeyStorage, CONFIG_NAME};
+use crate::output::{fail, print_result, ErrorCode};
+use crate::utils::{confirm_prompt, get_fee_settings, input_prompt, TIMEOUT};
+use clarity::Address as EthAddress;
+use clarity::PrivateKey as EthPrivateKey;
+use cosmos_gravity::query::{get_delegate_keys_by_orchestrator, get_gravity_params};
+use cosmos_gravity::send::{set_gravity_delegate_addresses, ValidatorSigner};
+use deep_space::{mnemonic::Mnemonic, Contact};
+use gravity_proto::cosmos_sdk_proto::cosmos::staking::v1beta1::query_client::QueryClient as StakingQueryClient;
+use gravity_proto::cosmos_sdk_proto::cosmos::staking::v1beta1::QueryValidatorsRequest;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::hd_wallet::{
+    cosmos_key_from_phrase, ethereum_hd_path, ethereum_key_from_phrase,
+};
+use gravity_utils::types::{GasPrice, GravityBridgeToolsConfig};
+use serde_json::json;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+use tonic::transport::Channel;
+use url::Url;
+use web30::client::Web3;
+
+pub async fn init(opts: InitOpts, home_dir: PathBuf, config: &GravityBridgeToolsConfig) {
+    let yes = opts.yes;
+    if home_dir.join(CONFIG_NAME).exists() && !opts.overwrite {
+        let question = format!(
+            "{} already exists, overwrite it?",
+            home_dir.join(CONFIG_NAME).to_str().unwrap()
+        );
+        if yes || !confirm_prompt(&question) {
+            fail(
+                ErrorCode::Config,
+                "The Gravity bridge tools config already exists, pass --overwrite to replace it",
+            );
+        }
+    }
+
+    // endpoints are tested before we move on so a typo is caught while the user is here
+    let default_grpc = opts
+        .cosmos_grpc
+        .unwrap_or_else(|| config.connection.cosmos_grpc.clone());
+    let (cosmos_grpc, connections) = loop {
+        let url = ask(yes, "Cosmos gRPC server", &default_grpc);
+        match test_cosmos_connection(&config.connection.address_prefix, &url).await {
+            Ok(connection) => break (url, connection),
+            Err(e) if yes => {
+                fail(
+                    ErrorCode::Connection,
+                    format!("Could not connect to the Cosmos gRPC server {} {}", url, e),
+                );
+            }
+            Err(e) => warn!("Could not connect to {} {}, try again", url, e),
+        }
+    };
+    let (mut grpc, contact) = connections;
+
+    let default_eth = opts
+        .ethereum_rpc
+        .unwrap_or_else(|| config.connection.ethereum_rpc.clone());
+    let ethereum_rpc = loop {
+        let url = ask(yes, "Ethereum RPC server", &default_eth);
+        match test_ethereum_connection(&url).await {
+            Ok(()) => break url,
+            Err(e) if yes => {
+                fail(
+                    ErrorCode::Connection,
+                    format!("Could not connect to the Ethereum RPC server {} {}", url, e),
+                );
+            }
+            Err(e) => warn!("Could not connect to {} {}, try again", url, e),
+        }
+    };
+
+    let detected_prefix = detect_address_prefix(&contact).await;
+    match &detected_prefix {
+        Some(prefix) => info!("Detected address prefix {}", prefix),
+        None => warn!("Could not detect the address prefix, the chain has no validators"),
+    }
+    let address_prefix = ask(
+        yes,
+        "Address prefix",
+        detected_prefix
+            .as_deref()
+            .unwrap_or(&config.connection.address_prefix),
+    );
+    let contact = Contact::new(&contact.get_url(), TIMEOUT, &address_prefix).unwrap();
+
+    let params = match get_gravity_params(&mut grpc).await {
+        Ok(params) => params,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!(
+                    "Failed to get the Gravity params, is this a Gravity chain? {:?}",
+                    e
+                ),
+            );
+        }
+    };
+    let detected_contract: Option<EthAddress> = params.bridge_ethereum_address.parse().ok();
+    let gravity_contract = match (opts.gravity_contract_address, detected_contract) {
+        (Some(contract), _) => Some(contract),
+        (None, Some(contract)) => {
+            info!(
+                "Detected Gravity contract {} from the chain params",
+                contract
+            );
+            Some(contract)
+        }
+        (None, None) if yes => {
+            warn!("The Gravity contract is not set in the chain params, set gravity_contract_address once it is deployed");
+            None
+        }
+        (None, None) => loop {
+            let input = input_prompt(
+                "Gravity contract address, it is not set in the chain params (empty to skip)",
+                None,
+            );
+            if input.is_empty() {
+                break None;
+            }
+            match input.parse() {
+                Ok(contract) => break Some(contract),
+                Err(e) => warn!("Invalid address {:?}", e),
+            }
+        },
+    };
+
+    let default_gas_price = opts.gas_price.or_else(|| config.fees.gas_price.clone());
+    let gas_price: Option<GasPrice> = if yes {
+        default_gas_price
+    } else {
+        let default_gas_price = default_gas_price.map(|g| g.to_string());
+        loop {
+            let input = input_prompt(
+                "Gas price for Cosmos fees eg: 0.025ugraviton (empty to skip)",
+                default_gas_price.as_deref(),
+            );
+            if input.is_empty() {
+                break None;
+            }
+            match input.parse() {
+                Ok(gas_price) => break Some(gas_price),
+                Err(e) => warn!("Invalid gas price {}", e),
+            }
+        }
+    };
+    if gas_price.is_none() {
+        warn!("No gas price set, set gas_price in the [fees] section before starting the Orchestrator");
+    }
+
+    let stored_keys = read_keys(&home_dir).unwrap_or_default();
+    let (cosmos_phrase, cosmos_generated) = get_cosmos_phrase(
+        yes,
+        opts.cosmos_phrase,
+        stored_keys.orchestrator_phrase,
+        &config.keys.cosmos_hd_path(),
+    );
+    let (ethereum_key, ethereum_generated) = get_ethereum_key(
+        yes,
+        opts.ethereum_key,
+        stored_keys.ethereum_key,
+        &cosmos_phrase,
+    );
+    let cosmos_key = parse_cosmos_phrase(&cosmos_phrase, &config.keys);
+    let cosmos_address = cosmos_key.to_address(&address_prefix).unwrap();
+    let ethereum_address = ethereum_key.to_public_key().unwrap();
+    if cosmos_generated {
+        info!(
+            "Generated Cosmos key, back up this phrase!\n {} -> {}",
+            cosmos_phrase, cosmos_address
+        );
+    }
+    if ethereum_generated {
+        info!(
+            "Derived the Ethereum key from the Cosmos phrase at {}\n Private: {} -> Address: {}",
+            ethereum_hd_path(0),
+            ethereum_key,
+            ethereum_address
+        );
+    }
+
+    // write everything out before registering, so nothing is lost if registration fails
+    create_dir_all(&home_dir).expect("Failed to create config directory!");
+    let mut values = vec![
+        ("connection", "cosmos_grpc", cosmos_grpc),
+        ("connection", "ethereum_rpc", ethereum_rpc),
+        ("connection", "address_prefix", address_prefix),
+    ];
+    if let Some(contract) = gravity_contract {
+        values.push((
+            "connection",
+            "gravity_contract_address",
+            contract.to_string(),
+        ));
+    }
+    if let Some(gas_price) = &gas_price {
+        values.push(("fees", "gas_price", gas_price.to_string()));
+    }
+    match render_config(&values) {
+        Ok(rendered) => save_config(&home_dir, &rendered),
+        Err(e) => fail(ErrorCode::Config, e),
+    }
+    let keys = KeyStorage {
+        orchestrator_phrase: Some(cosmos_phrase),
+        ethereum_key: Some(ethereum_key),
+    };
+    save_keys(&home_dir, keys);
+    info!("Config written to {}", home_dir.to_str().unwrap());
+
+    match get_delegate_keys_by_orchestrator(&mut grpc, cosmos_address).await {
+        Ok((validator, registered_eth)) if registered_eth == ethereum_address => {
+            info!("Delegate keys are already registered for {}", validator)
+        }
+        Ok((validator, registered_eth)) => warn!(
+            "{} is registered for {} with Ethereum address {}, not {}",
+            cosmos_address, validator, registered_eth, ethereum_address
+        ),
+        Err(_) => match gas_price {
+            Some(gas_price) => {
+                register(
+                    yes,
+                    opts.validator_phrase,
+                    &contact,
+                    cosmos_address,
+                    ethereum_address,
+                    gas_price,
+                    config,
+                )
+                .await
+            }
+            None => warn!("Set a gas price and register your delegate keys with `gbt keys register-orchestrator-address`"),
+        },
+    }
+
+    info!("Check your setup with `gbt doctor` then start with `gbt orchestrator`");
+    print_result(&json!({
+        "config_dir": home_dir.to_str().unwrap(),
+        "orchestrator_address": cosmos_address.to_string(),
+        "ethereum_address": ethereum_address.to_string(),
+    }));
+}
+
+/// Asks for a value, or uses the default when running with --yes
+fn ask(yes: bool, question: &str, default: &str) -> String {
+    if yes {
+        default.to_string()
+    } else {
+        input_prompt(question, Some(default))
+    }
+}
+
+/// Connects to the Cosmos gRPC server exactly as given, unlike create_rpc_connections there
+/// are no fallback urls since the url entered here is the one saved in the config
+async fn test_cosmos_connection(
+    address_prefix: &str,
+    url: &str,
+) -> Result<(GravityQueryClient<Channel>, Contact), String> {
+    let url = url.trim_end_matches('/');
+    Url::parse(url).map_err(|e| format!("Invalid url {}", e))?;
+    let grpc = GravityQueryClient::connect(url.to_string())
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let contact = Contact::new(url, TIMEOUT, address_prefix).map_err(|e| format!("{:?}", e))?;
+    contact
+        .get_chain_status()
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    Ok((grpc, contact))
+}
+
+/// Checks that the Ethereum RPC server exactly as given answers requests
+async fn test_ethereum_connection(url: &str) -> Result<(), String> {
+    let url = url.trim_end_matches('/');
+    Url::parse(url).map_err(|e| format!("Invalid url {}", e))?;
+    Web3::new(url, TIMEOUT)
+        .eth_block_number()
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    Ok(())
+}
+
+/// Detects the address prefix of the chain from the operator address of a validator,
+/// validator operator addresses use the account prefix followed by valoper
+async fn detect_address_prefix(contact: &Contact) -> Option<String> {
+    let mut staking = StakingQueryClient::connect(contact.get_url()).await.ok()?;
+    let validators = staking
+        .validators(QueryValidatorsRequest {
+            status: String::new(),
+            pagination: None,
+        })
+        .await
+        .ok()?
+        .into_inner()
+        .validators;
+    let operator = &validators.first()?.operator_address;
+    prefix_from_operator_address(operator)
+}
+
+fn prefix_from_operator_address(operator: &str) -> Option<String> {
+    // the bech32 separator is the last 1 in the address
+    let (hrp, _) = operator.rsplit_once('1')?;
+    hrp.strip_suffix("valoper").map(|p| p.to_string())
+}
+
+/// Picks the Orchestrator's Cosmos phrase from the command line, the existing keys file, the
+/// user or generates one. Returns the phrase and if it was generated
+fn get_cosmos_phrase(
+    yes: bool,
+    arg: Option<String>,
+    stored: Option<String>,
+    cosmos_hd_path: &str,
+) -> (String, bool) {
+    if let Some(phrase) = arg {
+        return (phrase, false);
+    }
+    if let Some(phrase) = stored {
+        if yes || confirm_prompt("Keep the Cosmos key already in the keys file?") {
+            return (phrase, false);
+        }
+    }
+    if !yes {
+        loop {
+            let input = input_prompt(
+                "Orchestrator Cosmos phrase to import (empty to generate a new key)",
+                None,
+            );
+            if input.is_empty() {
+                break;
+            }
+            match cosmos_key_from_phrase(&input, cosmos_hd_path) {
+                Ok(_) => return (input, false),
+                Err(e) => warn!("Invalid phrase {}", e),
+            }
+        }
+    }
+    (Mnemonic::generate(24).unwrap().to_string(), true)
+}
+
+/// Picks the Orchestrator's Ethereum key the same way as get_cosmos_phrase, except that
+/// new keys are derived from the Cosmos phrase so both can be recovered from it
+fn get_ethereum_key(
+    yes: bool,
+    arg: Option<EthPrivateKey>,
+    stored: Option<EthPrivateKey>,
+    cosmos_phrase: &str,
+) -> (EthPrivateKey, bool) {
+    if let Some(key) = arg {
+        return (key, false);
+    }
+    if let Some(key) = stored {
+        if yes || confirm_prompt("Keep the Ethereum key already in the keys file?") {
+            return (key, false);
+        }
+    }
+    if !yes {
+        loop {
+            let input = input_prompt(
+                "Orchestrator Ethereum private key to import (empty to derive it from the Cosmos phrase)",
+                None,
+            );
+            if input.is_empty() {
+                break;
+            }
+            match input.parse() {
+                Ok(key) => return (key, false),
+                Err(e) => warn!("Invalid private key {:?}", e),
+            }
+        }
+    }
+    (ethereum_key_from_phrase(cosmos_phrase, 0).unwrap(), true)
+}
+
+/// Registers the delegate keys with the validator key, if the validator key was not provided
+/// on the command line the user is asked for it
+async fn register(
+    yes: bool,
+    validator_phrase: Option<String>,
+    contact: &Contact,
+    cosmos_address: deep_space::Address,
+    ethereum_address: EthAddress,
+    gas_price: GasPrice,
+    config: &GravityBridgeToolsConfig,
+) {
+    let cosmos_hd_path = config.keys.cosmos_hd_path();
+    let validator_key = match validator_phrase {
+        Some(phrase) => parse_cosmos_phrase(&phrase, &config.keys),
+        None if yes => {
+            warn!("The delegate keys are not registered, pass --validator-phrase to register them");
+            return;
+        }
+        None => {
+            if !confirm_prompt("The delegate keys are not registered, register them now? This requires your validator phrase") {
+                warn!("Register your delegate keys with `gbt keys register-orchestrator-address` before starting");
+                return;
+            }
+            loop {
+                let input = input_prompt("Validator phrase", None);
+                match cosmos_key_from_phrase(&input, &cosmos_hd_path) {
+                    Ok(key) => break key,
+                    Err(e) => warn!("Invalid phrase {}", e),
+                }
+            }
+        }
+    };
+    let fee = get_fee_settings(Some(gas_price), &config.fees);
+    let res = match set_gravity_delegate_addresses(
+        contact,
+        ethereum_address,
+        cosmos_address,
+        &mut ValidatorSigner::from(validator_key),
+        &fee,
+    )
+    .await
+    {
+        Ok(res) => contact.wait_for_tx(res, TIMEOUT).await,
+        Err(e) => Err(e),
+    };
+    match res {
+        Ok(_) => info!(
+            "Registered Delegate Ethereum address {} and Cosmos address {}",
+            ethereum_address, cosmos_address
+        ),
+        Err(e) => warn!(
+            "Failed to register the delegate keys {:?}, retry with `gbt keys register-orchestrator-address`",
+            e
+        ),
+    }
+}
+
+#[test]
+fn test_prefix_from_operator_address() {
+    assert_eq!(
+        prefix_from_operator_address("gravityvaloper1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4"),
+        Some("gravity".to_string())
+    );
+    assert_eq!(
+        prefix_from_operator_address("cosmos1jpz0ahls2chajf78nkqczdwwuqcu97w6z3plt4"),
+        None
+    );
+}
@@ -0,0 +1,118 @@
+//! Query and export the Orchestrator's signing journal
+
+use crate::args::{JournalExportOpts, JournalFilterOpts, JournalShowOpts};
+use crate::config::JOURNAL_NAME;
+use crate::output::{fail, is_json, print_result, ErrorCode};
+use orchestrator::signing_journal::{read_journal, JournalEntry, SignedObjectType};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+pub fn show_journal(home_dir: &Path, opts: JournalShowOpts) {
+    let entries = load_filtered(home_dir, &opts.filter);
+    if is_json() {
+        print_result(&json!({ "entries": entries }));
+        return;
+    }
+    if entries.is_empty() {
+        info!("No matching signing journal entries");
+        return;
+    }
+    for entry in entries {
+        println!(
+            "{} {:?} {} nonce {} checkpoint {} signer {} signature {} txhash {}",
+            entry.time,
+            entry.object_type,
+            entry.scope,
+            entry.nonce,
+            entry.checkpoint,
+            entry.eth_signer,
+            entry.signature,
+            entry.txhash.unwrap_or_else(|| "-".to_string())
+        );
+    }
+}
+
+pub fn export_journal(home_dir: &Path, opts: JournalExportOpts) {
+    let entries = load_filtered(home_dir, &opts.filter);
+    let contents = match opts.format.as_str() {
+        "json" => serde_json::to_string_pretty(&entries).unwrap(),
+        "csv" => {
+            let mut out =
+                "time,type,scope,nonce,checkpoint,eth_signer,signature,txhash\n".to_string();
+            for entry in entries.iter() {
+                out += &format!(
+                    "{},{:?},{},{},{},{},{},{}\n",
+                    entry.time,
+                    entry.object_type,
+                    entry.scope,
+                    entry.nonce,
+                    entry.checkpoint,
+                    entry.eth_signer,
+                    entry.signature,
+                    entry.txhash.clone().unwrap_or_default()
+                );
+            }
+            out
+        }
+        _ => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Unknown export format {}, use json or csv", opts.format),
+            );
+        }
+    };
+    if let Err(e) = fs::write(&opts.output, contents) {
+        fail(
+            ErrorCode::Io,
+            format!("Failed to write {} {:?}", opts.output.to_str().unwrap(), e),
+        );
+    }
+    info!(
+        "Exported {} journal entries to {}",
+        entries.len(),
+        opts.output.to_str().unwrap()
+    );
+    print_result(&json!({
+        "entries": entries.len(),
+        "output": opts.output.to_str().unwrap(),
+    }));
+}
+
+fn load_filtered(home_dir: &Path, filter: &JournalFilterOpts) -> Vec<JournalEntry> {
+    let object_type = match filter.object_type.as_deref() {
+        Some("valset") => Some(SignedObjectType::Valset),
+        Some("batch") => Some(SignedObjectType::Batch),
+        Some("logic-call") => Some(SignedObjectType::LogicCall),
+        Some(v) => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Unknown type {}, use valset, batch or logic-call", v),
+            );
+        }
+        None => None,
+    };
+    let checkpoint = filter
+        .checkpoint
+        .as_ref()
+        .map(|c| c.trim_start_matches("0x").to_lowercase());
+    let entries = match read_journal(&home_dir.join(JOURNAL_NAME)) {
+        Ok(v) => v,
+        Err(e) => {
+            fail(
+                ErrorCode::Io,
+                format!("Failed to read the signing journal {}", e),
+            );
+        }
+    };
+    entries
+        .into_iter()
+        .filter(|e| object_type.map_or(true, |t| e.object_type == t))
+        .filter(|e| filter.nonce.map_or(true, |n| e.nonce == n))
+        .filter(|e| {
+            checkpoint
+                .as_ref()
+                .map_or(true, |c| e.checkpoint.to_lowercase() == *c)
+        })
+        .collect()
+}
@@ -1,59 +1,211 @@
 pub mod register_orchestrator_address;
 
+use crate::output::{fail, print_result, ErrorCode};
 use crate::{
-    args::{SetEthereumKeyOpts, SetOrchestratorKeyOpts},
+    args::{RecoverKeysOpts, SetEthereumKeyOpts, SetOrchestratorKeyOpts},
     config::{config_exists, load_keys, save_keys},
+    utils::input_prompt,
 };
+use cosmos_gravity::ledger::{
+    open_usb_transport, LedgerSigner, LedgerTransport, SpeculosTransport,
+};
+use cosmos_gravity::send::ValidatorSigner;
 use deep_space::PrivateKey;
-use std::{path::Path, process::exit};
+use gravity_utils::hd_wallet::{
+    cosmos_key_from_phrase, ethereum_hd_path, ethereum_key_from_phrase,
+};
+use gravity_utils::types::GravityBridgeToolsConfig;
+use serde_json::json;
+use std::path::Path;
+
+/// Picks the validator key used to sign delegate key registrations, either the phrase
+/// given on the command line or a Ledger device (or Speculos emulator)
+pub fn get_validator_signer(
+    validator_phrase: Option<PrivateKey>,
+    ledger: bool,
+    ledger_hd_path: &str,
+    speculos: Option<String>,
+) -> ValidatorSigner {
+    let use_ledger = ledger || speculos.is_some();
+    match (validator_phrase, use_ledger) {
+        (Some(key), false) => ValidatorSigner::PrivateKey(key),
+        (None, true) => {
+            let transport: Result<Box<dyn LedgerTransport>, _> = match speculos {
+                Some(addr) => SpeculosTransport::connect(&addr).map(|t| Box::new(t) as _),
+                None => open_usb_transport(),
+            };
+            let signer = transport.and_then(|t| LedgerSigner::new(t, ledger_hd_path));
+            let mut signer = match signer {
+                Ok(s) => s,
+                Err(e) => {
+                    fail(
+                        ErrorCode::Connection,
+                        format!("Could not connect to the Ledger {}", e),
+                    );
+                }
+            };
+            match signer.version() {
+                Ok((major, minor, patch)) => {
+                    info!("Using Ledger Cosmos app v{}.{}.{}", major, minor, patch)
+                }
+                Err(e) => {
+                    fail(
+                        ErrorCode::Connection,
+                        format!("Please unlock the Ledger and open the Cosmos app {}", e),
+                    );
+                }
+            }
+            ValidatorSigner::Ledger(signer)
+        }
+        (Some(_), true) => {
+            fail(
+                ErrorCode::InvalidInput,
+                "Provide either --validator-phrase or --ledger, not both",
+            );
+        }
+        (None, false) => {
+            fail(
+                ErrorCode::InvalidInput,
+                "Please provide the validator key with --validator-phrase or --ledger",
+            );
+        }
+    }
+}
 
-pub fn show_keys(home_dir: &Path, prefix: &str) {
+pub fn show_keys(home_dir: &Path, prefix: &str, config: &GravityBridgeToolsConfig) {
     if !config_exists(home_dir) {
-        error!("Please run `gbt init` before running this command!");
-        exit(1);
+        fail(
+            ErrorCode::Config,
+            "Please run `gbt init` before running this command!",
+        );
     }
     let keys = load_keys(home_dir);
-    match keys.orchestrator_phrase {
+    let orchestrator_address = match keys.orchestrator_phrase {
         Some(v) => {
-            let key = PrivateKey::from_phrase(&v, "")
+            let key = cosmos_key_from_phrase(&v, &config.keys.cosmos_hd_path())
                 .expect("Failed to decode key in keyfile. Did you edit it manually?");
             let address = key.to_address(prefix).unwrap();
             info!("Your Orchestrator key, {}", address);
+            Some(address.to_string())
         }
-        None => info!("You do not have an Orchestrator key set"),
-    }
-    match keys.ethereum_key {
+        None => {
+            info!("You do not have an Orchestrator key set");
+            None
+        }
+    };
+    let ethereum_address = match keys.ethereum_key {
         Some(v) => {
             let address = v.to_public_key().unwrap();
             info!("Your Ethereum key, {}", address);
+            Some(address.to_string())
         }
-        None => info!("You do not have an Ethereum key set"),
-    }
+        None => {
+            info!("You do not have an Ethereum key set");
+            None
+        }
+    };
+    print_result(&json!({
+        "orchestrator_address": orchestrator_address,
+        "ethereum_address": ethereum_address,
+    }));
 }
 
 pub fn set_eth_key(home_dir: &Path, opts: SetEthereumKeyOpts) {
     if !config_exists(home_dir) {
-        error!("Please run `gbt init` before running this command!");
-        exit(1);
+        fail(
+            ErrorCode::Config,
+            "Please run `gbt init` before running this command!",
+        );
     }
     let mut keys = load_keys(home_dir);
     keys.ethereum_key = Some(opts.key);
     save_keys(home_dir, keys);
-    info!("Successfully updated Ethereum Key")
+    info!("Successfully updated Ethereum Key");
+    print_result(&json!({
+        "ethereum_address": opts.key.to_public_key().unwrap().to_string(),
+    }));
 }
 
-pub fn set_orchestrator_key(home_dir: &Path, opts: SetOrchestratorKeyOpts) {
+pub fn set_orchestrator_key(home_dir: &Path, opts: SetOrchestratorKeyOpts, prefix: &str) {
     if !config_exists(home_dir) {
-        error!("Please run `gbt init` before running this command!");
-        exit(1);
-    }
-    let res = PrivateKey::from_phrase(&opts.phrase, "");
-    if let Err(e) = res {
-        error!("Invalid Cosmos mnemonic phrase {} {:?}", opts.phrase, e);
-        exit(1);
+        fail(
+            ErrorCode::Config,
+            "Please run `gbt init` before running this command!",
+        );
     }
+    let key = match PrivateKey::from_phrase(&opts.phrase, "") {
+        Ok(key) => key,
+        Err(e) => fail(
+            ErrorCode::InvalidInput,
+            format!("Invalid Cosmos mnemonic phrase {} {:?}", opts.phrase, e),
+        ),
+    };
+    let address = key.to_address(prefix).unwrap();
     let mut keys = load_keys(home_dir);
     keys.orchestrator_phrase = Some(opts.phrase);
     save_keys(home_dir, keys);
-    info!("Successfully updated Orchestrator Key")
+    info!("Successfully updated Orchestrator Key");
+    print_result(&json!({ "orchestrator_address": address.to_string() }));
+}
+
+/// Rebuilds both delegate keys from a mnemonic phrase, the Cosmos key at the configured
+/// HD path and the Ethereum key at m/44'/60'/0'/0/n
+pub fn recover_keys(
+    home_dir: &Path,
+    opts: RecoverKeysOpts,
+    prefix: &str,
+    config: &GravityBridgeToolsConfig,
+) {
+    if !opts.no_save && !config_exists(home_dir) {
+        fail(
+            ErrorCode::Config,
+            "Please run `gbt init` before running this command!",
+        );
+    }
+    let phrase = match opts.phrase {
+        Some(phrase) => phrase,
+        None => input_prompt("Mnemonic phrase to recover", None),
+    };
+    let cosmos_hd_path = config.keys.cosmos_hd_path();
+    let cosmos_key = match cosmos_key_from_phrase(&phrase, &cosmos_hd_path) {
+        Ok(key) => key,
+        Err(e) => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Failed to recover the Cosmos key {}", e),
+            );
+        }
+    };
+    let ethereum_key = match ethereum_key_from_phrase(&phrase, opts.ethereum_index) {
+        Ok(key) => key,
+        Err(e) => {
+            fail(
+                ErrorCode::InvalidInput,
+                format!("Failed to recover the Ethereum key {}", e),
+            );
+        }
+    };
+    info!(
+        "Recovered Cosmos address {} at {}",
+        cosmos_key.to_address(prefix).unwrap(),
+        cosmos_hd_path
+    );
+    info!(
+        "Recovered Ethereum address {} at {}",
+        ethereum_key.to_public_key().unwrap(),
+        ethereum_hd_path(opts.ethereum_index)
+    );
+    let result = json!({
+        "orchestrator_address": cosmos_key.to_address(prefix).unwrap().to_string(),
+        "ethereum_address": ethereum_key.to_public_key().unwrap().to_string(),
+        "saved": !opts.no_save,
+    });
+    if !opts.no_save {
+        let mut keys = load_keys(home_dir);
+        keys.orchestrator_phrase = Some(phrase);
+        keys.ethereum_key = Some(ethereum_key);
+        save_keys(home_dir, keys);
+        info!("Successfully stored the recovered keys");
+    }
+    print_result(&result);
 }
@@ -1,64 +1,110 @@
 use std::path::PathBuf;
-use std::process::exit;
 
 use crate::args::RegisterOrchestratorAddressOpts;
 use crate::config::config_exists;
 use crate::config::load_keys;
+use crate::config::parse_cosmos_phrase;
 use crate::config::save_keys;
 use crate::config::KeyStorage;
+use crate::keys::get_validator_signer;
+use crate::output::{fail, print_result, ErrorCode};
+use crate::tx::generate_only;
+use crate::utils::get_fee_settings_or_fees;
 use crate::utils::TIMEOUT;
-use clarity::PrivateKey as EthPrivateKey;
-use cosmos_gravity::send::set_gravity_delegate_addresses;
-use deep_space::{mnemonic::Mnemonic, private_key::PrivateKey as CosmosPrivateKey};
-use gravity_utils::connection_prep::check_for_fee;
+use cosmos_gravity::send::{build_set_orchestrator_address_msg, set_gravity_delegate_addresses};
+use deep_space::mnemonic::Mnemonic;
+use gravity_utils::connection_prep::check_for_gas_price;
 use gravity_utils::connection_prep::{create_rpc_connections, wait_for_cosmos_node_ready};
-use rand::{thread_rng, Rng};
+use gravity_utils::hd_wallet::{
+    cosmos_key_from_phrase, ethereum_hd_path, ethereum_key_from_phrase,
+};
+use gravity_utils::types::GravityBridgeToolsConfig;
+use serde_json::json;
 
 pub async fn register_orchestrator_address(
     args: RegisterOrchestratorAddressOpts,
     prefix: String,
     home_dir: PathBuf,
+    config: &GravityBridgeToolsConfig,
 ) {
-    let fee = args.fees;
+    let fee = get_fee_settings_or_fees(args.gas_price, args.fees, &config.fees);
     let cosmos_grpc = args.cosmos_grpc;
-    let validator_key = args.validator_phrase;
+    let validator_key = args
+        .validator_phrase
+        .as_ref()
+        .map(|phrase| parse_cosmos_phrase(phrase, &config.keys));
+    // with --generate-only the validator signs later with `gbt tx sign`
+    let mut validator_signer = if args.generate.generate_only {
+        None
+    } else {
+        Some(get_validator_signer(
+            validator_key,
+            args.ledger,
+            &args.ledger_hd_path,
+            args.speculos,
+        ))
+    };
     let cosmos_phrase = args.cosmos_phrase;
     let mut generated_cosmos = None;
     let mut generated_eth = false;
 
     if !args.no_save && !config_exists(&home_dir) {
-        error!("Please run `gbt init` before running this command!");
-        exit(1);
+        fail(
+            ErrorCode::Config,
+            "Please run `gbt init` before running this command!",
+        );
     }
 
     let connections = create_rpc_connections(prefix, Some(cosmos_grpc), None, TIMEOUT).await;
     let contact = connections.contact.unwrap();
     wait_for_cosmos_node_ready(&contact).await;
 
-    let validator_addr = validator_key.to_address(&contact.get_prefix()).unwrap();
-    check_for_fee(&fee, validator_addr, &contact).await;
+    let validator_addr = match (validator_signer.as_mut(), args.generate.from) {
+        (Some(signer), _) => match signer.to_address(&contact.get_prefix()) {
+            Ok(a) => a,
+            Err(e) => {
+                fail(
+                    ErrorCode::Query,
+                    format!("Could not get the validator address {:?}", e),
+                );
+            }
+        },
+        (None, Some(from)) => from,
+        (None, None) => match validator_key {
+            Some(key) => key.to_address(&contact.get_prefix()).unwrap(),
+            None => {
+                fail(
+                    ErrorCode::InvalidInput,
+                    "Please provide the validator's address with --from",
+                );
+            }
+        },
+    };
+    if let Err(e) = check_for_gas_price(&fee.gas_price, validator_addr, &contact).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
 
-    // Set the cosmos key to either the cli value, the value in the config, or a generated
+    // Set the cosmos phrase to either the cli value, the value in the config, or a generated
     // value if the config has not been setup
-    let cosmos_key = if let Some(cosmos_phrase) = cosmos_phrase.clone() {
-        CosmosPrivateKey::from_phrase(&cosmos_phrase, "").expect("Failed to parse cosmos key")
+    let cosmos_hd_path = config.keys.cosmos_hd_path();
+    let phrase = if let Some(cosmos_phrase) = cosmos_phrase.clone() {
+        cosmos_phrase
     } else {
-        let mut key = None;
+        let mut phrase = None;
         if config_exists(&home_dir) {
-            let keys = load_keys(&home_dir);
-            if let Some(phrase) = keys.orchestrator_phrase {
-                key = Some(CosmosPrivateKey::from_phrase(phrase.as_str(), "").unwrap());
-            }
+            phrase = load_keys(&home_dir).orchestrator_phrase;
         }
-        if key.is_none() {
+        if phrase.is_none() {
             let new_phrase = Mnemonic::generate(24).unwrap();
-            key = Some(CosmosPrivateKey::from_phrase(new_phrase.as_str(), "").unwrap());
+            phrase = Some(new_phrase.to_string());
             generated_cosmos = Some(new_phrase);
         }
-        key.unwrap()
+        phrase.unwrap()
     };
-    // Set the ethereum key to either the cli value, the value in the config, or a generated
-    // value if the config has not been setup
+    let cosmos_key =
+        cosmos_key_from_phrase(&phrase, &cosmos_hd_path).expect("Failed to parse cosmos key");
+    // Set the ethereum key to either the cli value, the value in the config, or derive
+    // it from the cosmos phrase so that both keys can be recovered from the phrase
     let ethereum_key = if let Some(key) = args.ethereum_key {
         key
     } else {
@@ -71,30 +117,41 @@ pub async fn register_orchestrator_address(
         }
         if key.is_none() {
             generated_eth = true;
-            let mut rng = thread_rng();
-            let e: [u8; 32] = rng.gen();
-            key = Some(EthPrivateKey::from_slice(&e).unwrap())
+            key = Some(ethereum_key_from_phrase(&phrase, args.ethereum_index).unwrap())
         }
         key.unwrap()
     };
 
     let ethereum_address = ethereum_key.to_public_key().unwrap();
     let cosmos_address = cosmos_key.to_address(&contact.get_prefix()).unwrap();
-    let res = set_gravity_delegate_addresses(
-        &contact,
-        ethereum_address,
-        cosmos_address,
-        validator_key,
-        fee.clone(),
-    )
-    .await
-    .expect("Failed to update Eth address");
-    let res = contact.wait_for_tx(res, TIMEOUT).await;
-
-    if let Err(e) = res {
-        error!("Failed trying to register delegate addresses error {:?}, correct the error and try again", e);
-        exit(1);
-    }
+    let txhash = match validator_signer.as_mut() {
+        Some(signer) => {
+            let res = set_gravity_delegate_addresses(
+                &contact,
+                ethereum_address,
+                cosmos_address,
+                signer,
+                &fee,
+            )
+            .await
+            .expect("Failed to update Eth address");
+            match contact.wait_for_tx(res, TIMEOUT).await {
+                Ok(res) => Some(res.txhash),
+                Err(e) => fail(ErrorCode::Transaction, format!("Failed trying to register delegate addresses error {:?}, correct the error and try again", e)),
+            }
+        }
+        None => {
+            let msg = build_set_orchestrator_address_msg(
+                validator_addr,
+                &contact.get_prefix(),
+                ethereum_address,
+                cosmos_address,
+            )
+            .unwrap();
+            generate_only(&contact, validator_addr, vec![msg], &args.generate, &fee).await;
+            None
+        }
+    };
 
     if let Some(phrase) = generated_cosmos.clone() {
         info!(
@@ -105,34 +162,37 @@ pub async fn register_orchestrator_address(
     }
     if generated_eth {
         info!(
-            "No Ethereum key provided, your generated key is\n Private: {} -> Address: {}",
+            "No Ethereum key provided, derived your key from the Cosmos phrase at {}\n Private: {} -> Address: {}",
+            ethereum_hd_path(args.ethereum_index),
             ethereum_key,
             ethereum_key.to_public_key().unwrap()
         );
     }
 
     let eth_address = ethereum_key.to_public_key().unwrap();
-    info!(
-        "Registered Delegate Ethereum address {} and Cosmos address {}",
-        eth_address, cosmos_address
-    );
+    if validator_signer.is_some() {
+        info!(
+            "Registered Delegate Ethereum address {} and Cosmos address {}",
+            eth_address, cosmos_address
+        );
+    }
     if !args.no_save {
-        info!("Keys saved! You can now run `gbt orchestrator --fees <your fee value>`");
-        let phrase = match (generated_cosmos, cosmos_phrase) {
-            (Some(v), None) => v.to_string(),
-            (None, Some(s)) => s,
-            (_, _) => {
-                // in this case the user has set keys in the config
-                // and then registered them so lets just load the config
-                // value again
-                let keys = load_keys(&home_dir);
-                keys.orchestrator_phrase.unwrap()
-            }
-        };
+        info!("Keys saved! You can now run `gbt orchestrator --gas-price <your gas price>`");
         let new_keys = KeyStorage {
             orchestrator_phrase: Some(phrase),
             ethereum_key: Some(ethereum_key),
         };
         save_keys(&home_dir, new_keys);
     }
+    // with --generate-only the unsigned transaction is the result
+    if let Some(txhash) = txhash {
+        print_result(&json!({
+            "txhash": txhash,
+            "validator_address": validator_addr.to_string(),
+            "orchestrator_address": cosmos_address.to_string(),
+            "ethereum_address": eth_address.to_string(),
+            "generated_phrase": generated_cosmos.map(|p| p.to_string()),
+            "saved": !args.no_save,
+        }));
+    }
 }
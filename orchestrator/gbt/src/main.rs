@@ -3,42 +3,70 @@ extern crate log;
 #[macro_use]
 extern crate serde_derive;
 
-use crate::args::{ClientSubcommand, KeysSubcommand, SubCommand};
-use crate::config::init_config;
+use crate::args::{
+    ClientSubcommand, ConfigSubcommand, JournalSubcommand, KeysSubcommand, OrchestratorSubcommand,
+    SubCommand, TxSubcommand,
+};
+use crate::config::show_config;
+use crate::journal::{export_journal, show_journal};
 use crate::keys::show_keys;
-use crate::{orchestrator::orchestrator, relayer::relayer};
+use crate::query::query;
+use crate::{
+    orchestrator::{approve, orchestrator, status},
+    relayer::relayer,
+};
 use args::Opts;
 use clap::Clap;
+use client::bulk_send::bulk_send;
+use client::cancel_send_to_eth::cancel_send_to_eth;
 use client::cosmos_to_eth::cosmos_to_eth;
 use client::deploy_erc20_representation::deploy_erc20_representation;
 use client::eth_to_cosmos::eth_to_cosmos;
+use client::request_batch::request_batch;
+use client::track::track;
 use config::{get_home_dir, load_config};
+use doctor::doctor;
 use env_logger::Env;
+use init::init;
+use keys::recover_keys;
 use keys::register_orchestrator_address::register_orchestrator_address;
 use keys::set_eth_key;
 use keys::set_orchestrator_key;
+use output::{report_panics, set_output_format};
 
 mod args;
 mod client;
 mod config;
+mod doctor;
+mod init;
+mod journal;
 mod keys;
 mod orchestrator;
+mod output;
+mod query;
 mod relayer;
+mod tx;
 mod utils;
 
-#[actix_rt::main]
-async fn main() {
+fn main() {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     // On Linux static builds we need to probe ssl certs path to be able to
     // do TLS stuff.
     openssl_probe::init_ssl_cert_env_vars();
     // parse the arguments
     let opts: Opts = Opts::parse();
+    set_output_format(opts.output);
+    report_panics(|| actix_rt::System::new().block_on(run(opts)));
+}
 
+async fn run(opts: Opts) {
     // handle global config here
-    let address_prefix = opts.address_prefix;
     let home_dir = get_home_dir(opts.home);
-    let config = load_config(&home_dir);
+    let mut config = load_config(&home_dir);
+    if let Some(address_prefix) = opts.address_prefix {
+        config.connection.address_prefix = address_prefix;
+    }
+    let address_prefix = config.connection.address_prefix.clone();
 
     // control flow for the command structure
     match opts.subcmd {
@@ -47,11 +75,21 @@ async fn main() {
                 eth_to_cosmos(eth_to_cosmos_opts, address_prefix).await
             }
             ClientSubcommand::CosmosToEth(cosmos_to_eth_opts) => {
-                cosmos_to_eth(cosmos_to_eth_opts, address_prefix).await
+                cosmos_to_eth(cosmos_to_eth_opts, address_prefix, &config).await
             }
             ClientSubcommand::DeployErc20Representation(deploy_erc20_opts) => {
                 deploy_erc20_representation(deploy_erc20_opts, address_prefix).await
             }
+            ClientSubcommand::CancelSendToEth(cancel_opts) => {
+                cancel_send_to_eth(cancel_opts, address_prefix, &config).await
+            }
+            ClientSubcommand::RequestBatch(request_batch_opts) => {
+                request_batch(request_batch_opts, address_prefix, &config).await
+            }
+            ClientSubcommand::Track(track_opts) => track(track_opts, address_prefix).await,
+            ClientSubcommand::BulkSend(bulk_send_opts) => {
+                bulk_send(bulk_send_opts, address_prefix, &config).await
+            }
         },
         SubCommand::Keys(key_opts) => match key_opts.subcmd {
             KeysSubcommand::RegisterOrchestratorAddress(set_orchestrator_address_opts) => {
@@ -59,23 +97,48 @@ async fn main() {
                     set_orchestrator_address_opts,
                     address_prefix,
                     home_dir,
+                    &config,
                 )
                 .await
             }
-            KeysSubcommand::Show => show_keys(&home_dir, &address_prefix),
+            KeysSubcommand::Recover(recover_opts) => {
+                recover_keys(&home_dir, recover_opts, &address_prefix, &config)
+            }
+            KeysSubcommand::Show => show_keys(&home_dir, &address_prefix, &config),
             KeysSubcommand::SetEthereumKey(set_eth_key_opts) => {
                 set_eth_key(&home_dir, set_eth_key_opts)
             }
             KeysSubcommand::SetOrchestratorKey(set_orch_key_opts) => {
-                set_orchestrator_key(&home_dir, set_orch_key_opts)
+                set_orchestrator_key(&home_dir, set_orch_key_opts, &address_prefix)
             }
         },
-        SubCommand::Orchestrator(orchestrator_opts) => {
-            orchestrator(orchestrator_opts, address_prefix, &home_dir, config).await
-        }
+        SubCommand::Orchestrator(mut orchestrator_opts) => match orchestrator_opts.subcmd.take() {
+            Some(OrchestratorSubcommand::Approve(approve_opts)) => approve(approve_opts, &home_dir),
+            Some(OrchestratorSubcommand::Status(status_opts)) => {
+                status(status_opts, address_prefix, &home_dir, &config).await
+            }
+            None => orchestrator(orchestrator_opts, address_prefix, &home_dir, config).await,
+        },
         SubCommand::Relayer(relayer_opts) => {
-            relayer(relayer_opts, address_prefix, &home_dir, &config.relayer).await
+            relayer(relayer_opts, address_prefix, &home_dir, &config).await
         }
-        SubCommand::Init(init_opts) => init_config(init_opts, home_dir),
+        SubCommand::Journal(journal_opts) => match journal_opts.subcmd {
+            JournalSubcommand::Show(show_opts) => show_journal(&home_dir, show_opts),
+            JournalSubcommand::Export(export_opts) => export_journal(&home_dir, export_opts),
+        },
+        SubCommand::Query(query_opts) => query(query_opts, address_prefix).await,
+        SubCommand::Config(config_opts) => match config_opts.subcmd {
+            ConfigSubcommand::Show(show_opts) => show_config(config, show_opts),
+        },
+        SubCommand::Doctor(doctor_opts) => {
+            doctor(doctor_opts, address_prefix, &home_dir, &config).await
+        }
+        SubCommand::Init(init_opts) => init(init_opts, home_dir, &config).await,
+        SubCommand::Tx(tx_opts) => match tx_opts.subcmd {
+            TxSubcommand::Sign(sign_opts) => tx::sign(sign_opts, address_prefix, &config.keys),
+            TxSubcommand::Broadcast(broadcast_opts) => {
+                tx::broadcast(broadcast_opts, address_prefix).await
+            }
+        },
     }
 }
@@ -1,19 +1,37 @@
-use crate::args::OrchestratorOpts;
+use crate::args::{ApproveOpts, OrchestratorOpts, OrchestratorStatusOpts};
 use crate::config::config_exists;
 use crate::config::load_keys;
+use crate::config::{load_approvals, save_approvals, JOURNAL_NAME};
+use crate::config::{parse_config_gravity_address, parse_config_keys, parse_cosmos_phrase};
+use crate::doctor::{has_unavailable, report, run_checks, DoctorTarget, DEFAULT_BALANCE_DAYS};
+use crate::output::{fail, is_json, print_result, ErrorCode};
+use crate::utils::get_fee_settings_or_fees;
+use crate::utils::TIMEOUT;
 use cosmos_gravity::query::get_gravity_params;
-use deep_space::PrivateKey as CosmosPrivateKey;
-use gravity_utils::connection_prep::{
-    check_delegate_addresses, check_for_eth, wait_for_cosmos_node_ready,
-};
-use gravity_utils::connection_prep::{check_for_fee, create_rpc_connections};
+use deep_space::client::ChainStatus;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::connection_pool::{connect_cosmos_pool, normalize_url, split_grpc_urls};
+use gravity_utils::connection_prep::{create_rpc_connections, wait_for_cosmos_node_ready};
+use gravity_utils::hd_wallet::cosmos_key_from_phrase;
 use gravity_utils::types::GravityBridgeToolsConfig;
 use orchestrator::main_loop::orchestrator_main_loop;
 use orchestrator::main_loop::{ETH_ORACLE_LOOP_SPEED, ETH_SIGNER_LOOP_SPEED};
+use orchestrator::signing_journal::SigningJournal;
+use orchestrator::signing_policy::{ApprovalLoader, SigningPolicy};
+use orchestrator::slashing_window::get_pending_signatures;
 use relayer::main_loop::LOOP_SPEED as RELAYER_LOOP_SPEED;
+use serde_json::{json, Value};
 use std::cmp::min;
 use std::path::Path;
-use std::process::exit;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep as delay_for;
+
+/// How many times the preflight checks are run when a node does not respond before
+/// the Orchestrator starts anyway, the main loop handles unresponsive nodes itself
+const PREFLIGHT_ATTEMPTS: u32 = 5;
+/// The delay between preflight check attempts
+const PREFLIGHT_RETRY_DELAY: Duration = Duration::from_secs(10);
 
 pub async fn orchestrator(
     args: OrchestratorOpts,
@@ -21,11 +39,66 @@ pub async fn orchestrator(
     home_dir: &Path,
     config: GravityBridgeToolsConfig,
 ) {
-    let fee = args.fees;
-    let cosmos_grpc = args.cosmos_grpc;
-    let ethereum_rpc = args.ethereum_rpc;
-    let ethereum_key = args.ethereum_key;
-    let cosmos_key = args.cosmos_phrase;
+    let fee = get_fee_settings_or_fees(args.gas_price, args.fees, &config.fees);
+    let approvals_home = home_dir.to_path_buf();
+    let approval_loader: ApprovalLoader =
+        Arc::new(move || load_approvals(&approvals_home).checkpoints);
+    let verifier = match &config.signing_policy.verification_grpc {
+        Some(url) if config.signing_policy.enabled => {
+            match GravityQueryClient::connect(normalize_url(url)).await {
+                Ok(client) => Some(client),
+                Err(e) => {
+                    fail(
+                        ErrorCode::Connection,
+                        format!("Could not connect to the verification node {} {:?}", url, e),
+                    );
+                }
+            }
+        }
+        _ => None,
+    };
+    let signing_policy =
+        match SigningPolicy::new(&config.signing_policy, Some(approval_loader), verifier) {
+            Ok(policy) => policy,
+            Err(e) => {
+                fail(ErrorCode::Config, format!("Invalid signing policy {}", e));
+            }
+        };
+    let signing_journal = if home_dir.exists() {
+        match SigningJournal::open(&home_dir.join(JOURNAL_NAME)) {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                fail(
+                    ErrorCode::Io,
+                    format!("Failed to open the signing journal {}", e),
+                );
+            }
+        }
+    } else {
+        warn!("No gbt home directory, signatures will not be journaled. Run `gbt init`");
+        None
+    };
+    if signing_policy.is_some() {
+        info!("Signing policy enabled, held batches and logic calls must be approved with `gbt orchestrator approve`");
+    }
+    // the command line takes precedence over the config and environment
+    let (config_cosmos_key, config_ethereum_key) = parse_config_keys(&config.keys);
+    let cosmos_grpc = args
+        .cosmos_grpc
+        .unwrap_or_else(|| config.connection.cosmos_grpc.clone());
+    let (cosmos_grpc, cosmos_grpc_fallbacks) = split_grpc_urls(&cosmos_grpc);
+    let ethereum_rpc = args
+        .ethereum_rpc
+        .unwrap_or_else(|| config.connection.ethereum_rpc.clone());
+    let gravity_contract_address = args
+        .gravity_contract_address
+        .or_else(|| parse_config_gravity_address(&config.connection));
+    let ethereum_key = args.ethereum_key.or(config_ethereum_key);
+    let cosmos_key = args
+        .cosmos_phrase
+        .map(|phrase| parse_cosmos_phrase(&phrase, &config.keys))
+        .or(config_cosmos_key);
+    let cosmos_hd_path = config.keys.cosmos_hd_path();
 
     let cosmos_key = if let Some(k) = cosmos_key {
         k
@@ -34,15 +107,14 @@ pub async fn orchestrator(
         if config_exists(home_dir) {
             let keys = load_keys(home_dir);
             if let Some(stored_key) = keys.orchestrator_phrase {
-                k = Some(CosmosPrivateKey::from_phrase(&stored_key, "").unwrap())
+                k = Some(cosmos_key_from_phrase(&stored_key, &cosmos_hd_path).unwrap())
             }
         }
         if k.is_none() {
-            error!("You must specify a Cosmos key phrase!");
             error!("To generate, register, and store a key use `gbt keys register-orchestrator-address`");
             error!("Store an already registered key using 'gbt keys set-orchestrator-key`");
             error!("To run from the command line, with no key storage use 'gbt orchestrator --cosmos-phrase your phrase' ");
-            exit(1);
+            fail(ErrorCode::Config, "You must specify a Cosmos key phrase!");
         }
         k.unwrap()
     };
@@ -57,11 +129,10 @@ pub async fn orchestrator(
             }
         }
         if k.is_none() {
-            error!("You must specify an Ethereum key!");
             error!("To generate, register, and store a key use `gbt keys register-orchestrator-address`");
             error!("Store an already registered key using 'gbt keys set-ethereum-key`");
             error!("To run from the command line, with no key storage use 'gbt orchestrator --ethereum-key your key' ");
-            exit(1);
+            fail(ErrorCode::Config, "You must specify an Ethereum key!");
         }
         k.unwrap()
     };
@@ -73,16 +144,14 @@ pub async fn orchestrator(
 
     trace!("Probing RPC connections");
     // probe all rpc connections and see if they are valid
-    let connections = create_rpc_connections(
-        address_prefix,
-        Some(cosmos_grpc),
-        Some(ethereum_rpc),
-        timeout,
-    )
-    .await;
+    let connections =
+        create_rpc_connections(address_prefix.clone(), None, Some(ethereum_rpc), timeout).await;
+    // starts on a fallback endpoint if the primary is down
+    let cosmos =
+        connect_cosmos_pool(address_prefix, cosmos_grpc, cosmos_grpc_fallbacks, timeout).await;
 
-    let mut grpc = connections.grpc.clone().unwrap();
-    let contact = connections.contact.clone().unwrap();
+    let mut grpc = cosmos.get_grpc();
+    let contact = cosmos.get_contact();
     let web3 = connections.web3.clone().unwrap();
 
     let public_eth_key = ethereum_key
@@ -94,34 +163,58 @@ pub async fn orchestrator(
         "Ethereum Address: {} Cosmos Address {}",
         public_eth_key, public_cosmos_key
     );
-
     // check if the cosmos node is syncing, if so wait for it
     // we can't move any steps above this because they may fail on an incorrect
     // historic chain state while syncing occurs
     wait_for_cosmos_node_ready(&contact).await;
 
-    // check if the delegate addresses are correctly configured
-    check_delegate_addresses(
-        &mut grpc,
-        public_eth_key,
-        public_cosmos_key,
-        &contact.get_prefix(),
-    )
-    .await;
-
-    // check if we actually have the promised balance of tokens to pay fees
-    check_for_fee(&fee, public_cosmos_key, &contact).await;
-    check_for_eth(public_eth_key, &web3).await;
+    // check that the keys, contract, chains and balances are all configured correctly
+    // before we start, the same checks can be run without starting using `gbt doctor`
+    let target = DoctorTarget {
+        delegate_address: public_cosmos_key,
+        ethereum_address: public_eth_key,
+        gravity_contract: gravity_contract_address,
+        fee: Some(fee.clone()),
+        relayer_enabled: config.orchestrator.relayer_enabled,
+        days: DEFAULT_BALANCE_DAYS,
+    };
+    let mut attempt = 1;
+    let checks = loop {
+        let checks = run_checks(&contact, &mut grpc, &web3, &target).await;
+        if !has_unavailable(&checks) || attempt >= PREFLIGHT_ATTEMPTS {
+            break checks;
+        }
+        warn!(
+            "Some preflight checks could not reach a node, retrying in {} seconds",
+            PREFLIGHT_RETRY_DELAY.as_secs()
+        );
+        attempt += 1;
+        delay_for(PREFLIGHT_RETRY_DELAY).await;
+    };
+    if !report(&checks) {
+        fail(
+            ErrorCode::CheckFailed,
+            "Preflight checks failed, fix the problems above and restart",
+        );
+    }
+    if has_unavailable(&checks) {
+        warn!("Starting without completing every preflight check, run `gbt doctor` once the nodes respond");
+    }
 
     // get the gravity contract address, if not provided
-    let contract_address = if let Some(c) = args.gravity_contract_address {
+    let contract_address = if let Some(c) = gravity_contract_address {
         c
     } else {
-        let params = get_gravity_params(&mut grpc).await.unwrap();
+        let params = match get_gravity_params(&mut grpc).await {
+            Ok(params) => params,
+            Err(e) => fail(
+                ErrorCode::Query,
+                format!("Failed to get the Gravity params {:?}", e),
+            ),
+        };
         let c = params.bridge_ethereum_address.parse();
         if c.is_err() {
-            error!("The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
-            exit(1);
+            fail(ErrorCode::Query, "The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
         }
         c.unwrap()
     };
@@ -130,11 +223,135 @@ pub async fn orchestrator(
         cosmos_key,
         ethereum_key,
         connections.web3.unwrap(),
-        connections.contact.unwrap(),
-        connections.grpc.unwrap(),
+        cosmos,
         contract_address,
         fee,
         config,
+        signing_policy,
+        signing_journal,
     )
     .await;
 }
+
+/// Approves a checkpoint held by the signing policy, the running Orchestrator
+/// reads the approvals file every time it checks a held batch or logic call
+pub fn approve(opts: ApproveOpts, home_dir: &Path) {
+    let checkpoint = opts.checkpoint.trim_start_matches("0x").to_lowercase();
+    if checkpoint.len() != 64 || !checkpoint.chars().all(|c| c.is_ascii_hexdigit()) {
+        fail(
+            ErrorCode::InvalidInput,
+            format!("{} is not a valid checkpoint hash", opts.checkpoint),
+        );
+    }
+    let mut approvals = load_approvals(home_dir);
+    if approvals.checkpoints.contains(&checkpoint) {
+        info!("Checkpoint {} is already approved", checkpoint);
+    } else {
+        approvals.checkpoints.push(checkpoint.clone());
+        save_approvals(home_dir, approvals);
+        info!("Approved checkpoint {}", checkpoint);
+    }
+    print_result(&json!({ "checkpoint": checkpoint }));
+}
+
+/// Lists everything the Orchestrator has not yet signed and how long it has left
+pub async fn status(
+    opts: OrchestratorStatusOpts,
+    address_prefix: String,
+    home_dir: &Path,
+    config: &GravityBridgeToolsConfig,
+) {
+    let connections =
+        create_rpc_connections(address_prefix, Some(opts.cosmos_grpc), None, TIMEOUT).await;
+    let contact = connections.contact.unwrap();
+    let mut grpc = connections.grpc.unwrap();
+
+    let address = match opts.orchestrator_address {
+        Some(address) => address,
+        None => {
+            let phrase = if config_exists(home_dir) {
+                load_keys(home_dir).orchestrator_phrase
+            } else {
+                None
+            };
+            match phrase {
+                Some(phrase) => cosmos_key_from_phrase(&phrase, &config.keys.cosmos_hd_path())
+                    .expect("Failed to decode key in keyfile. Did you edit it manually?")
+                    .to_address(&contact.get_prefix())
+                    .unwrap(),
+                None => {
+                    fail(
+                        ErrorCode::Config,
+                        "No Orchestrator key stored, provide --orchestrator-address",
+                    );
+                }
+            }
+        }
+    };
+    let current_height = match contact.get_chain_status().await {
+        Ok(ChainStatus::Moving { block_height }) => block_height,
+        Ok(_) => {
+            fail(
+                ErrorCode::Query,
+                "The Cosmos node is syncing, try again later or use another node",
+            );
+        }
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get the Cosmos chain status {:?}", e),
+            );
+        }
+    };
+    let pending = match get_pending_signatures(&mut grpc, address, contact.get_prefix()).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get unsigned objects {:?}", e),
+            );
+        }
+    };
+
+    info!(
+        "Orchestrator {} at Cosmos block {}",
+        address, current_height
+    );
+    if is_json() {
+        let pending: Vec<Value> = pending
+            .iter()
+            .map(|item| {
+                json!({
+                    "description": item.description,
+                    "created_at": item.created_at,
+                    "deadline": item.deadline,
+                    "blocks_remaining": item.blocks_remaining(current_height),
+                })
+            })
+            .collect();
+        print_result(&json!({
+            "orchestrator_address": address.to_string(),
+            "block_height": current_height,
+            "pending": pending,
+        }));
+        return;
+    }
+    if pending.is_empty() {
+        info!("Nothing left to sign, the Orchestrator is caught up");
+        return;
+    }
+    for item in pending {
+        let remaining = item.blocks_remaining(current_height);
+        if remaining == 0 {
+            println!(
+                "{} created at block {} UNSIGNED PAST DEADLINE {}, slashable!",
+                item.description, item.created_at, item.deadline
+            );
+        } else {
+            println!(
+                "{} created at block {} must be signed by block {}, {} blocks remaining",
+                item.description, item.created_at, item.deadline, remaining
+            );
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Machine readable output for automation. With `--output json` every command prints a single
+//! JSON object to stdout when it finishes, either {"success": true, "result": ...} or
+//! {"success": false, "error": {"code": ..., "message": ...}}, log lines stay on stderr
+
+use serde::Serialize;
+use serde_json::json;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The message of the last panic on this thread, reported if it ends the process
+    static LAST_PANIC: RefCell<Option<String>> = RefCell::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format {}, use text or json", s)),
+        }
+    }
+}
+
+/// Why a command failed, stable values automation can match on
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Invalid or missing command line arguments
+    InvalidInput,
+    /// gbt is not initialized or the config or keys file is invalid
+    Config,
+    /// A Cosmos node, Ethereum node or Ledger could not be reached
+    Connection,
+    /// Querying the chain failed or returned unexpected data
+    Query,
+    /// A transaction was rejected or did not execute
+    Transaction,
+    /// The sender does not hold enough tokens
+    InsufficientFunds,
+    /// Reading or writing a local file failed
+    Io,
+    /// A preflight check failed
+    CheckFailed,
+    /// gbt panicked
+    Internal,
+}
+
+pub fn set_output_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+    if format == OutputFormat::Json {
+        // the message is kept for report_panics, a panic in a task that is recovered from
+        // must not print a failure object
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(info.to_string()));
+        }));
+    }
+}
+
+/// Runs the command, with `--output json` a panic that ends the process is reported as a
+/// failure object rather than only a message on stderr
+pub fn report_panics(command: impl FnOnce()) {
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(command)) {
+        if is_json() {
+            let message = LAST_PANIC
+                .with(|last| last.borrow_mut().take())
+                .unwrap_or_else(|| "gbt panicked".to_string());
+            print_error(ErrorCode::Internal, &message);
+        }
+        panic::resume_unwind(payload);
+    }
+}
+
+pub fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Prints the result of a successful command, human readable output is logged as the command
+/// runs so this only prints with `--output json`
+pub fn print_result<T: Serialize>(result: &T) {
+    if is_json() {
+        let out = json!({
+            "success": true,
+            "result": result,
+        });
+        println!("{}", out);
+    }
+}
+
+fn print_error(code: ErrorCode, message: &str) {
+    let out = json!({
+        "success": false,
+        "error": {
+            "code": code,
+            "message": message,
+        },
+    });
+    println!("{}", out);
+}
+
+/// Logs the error and exits, with `--output json` the error is also printed as the result
+pub fn fail(code: ErrorCode, message: impl Display) -> ! {
+    let message = message.to_string();
+    error!("{}", message);
+    if is_json() {
+        print_error(code, &message);
+    }
+    exit(1);
+}
+
+#[test]
+fn test_output_format_parse() {
+    assert_eq!("json".parse(), Ok(OutputFormat::Json));
+    assert_eq!("text".parse(), Ok(OutputFormat::Text));
+    assert!("yaml".parse::<OutputFormat>().is_err());
+    assert_eq!(
+        serde_json::to_value(ErrorCode::InsufficientFunds).unwrap(),
+        json!("insufficient_funds")
+    );
+}
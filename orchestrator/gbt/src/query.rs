@@ -0,0 +1,492 @@
+//! Read only queries against the Gravity module, every query prints human readable output or
+//! json if --json or the global `--output json` is passed
+
+use crate::args::{
+    AttestationsQueryOpts, BatchesQueryOpts, DelegateKeysQueryOpts, DenomToErc20QueryOpts,
+    Erc20ToDenomQueryOpts, LogicCallsQueryOpts, PendingSendsQueryOpts, QueryOpts, QuerySubcommand,
+};
+use crate::output::{fail, is_json, print_result, ErrorCode};
+use crate::utils::TIMEOUT;
+use clarity::utils::bytes_to_hex_str;
+use cosmos_gravity::attestations::{
+    get_bonded_validators, get_typed_attestations, resolve_votes, AttestationVotes,
+};
+use cosmos_gravity::query::{
+    get_current_valset, get_delegate_keys_by_eth, get_delegate_keys_by_orchestrator,
+    get_delegate_keys_by_validator, get_denom_to_erc20, get_erc20_to_denom, get_gravity_params,
+    get_latest_logic_calls, get_latest_transaction_batches, get_latest_valsets,
+    get_logic_call_signatures, get_pending_send_to_eth, get_transaction_batch_signatures,
+};
+use gravity_proto::cosmos_sdk_proto::cosmos::staking::v1beta1::query_client::QueryClient as StakingQueryClient;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::error::GravityError;
+use gravity_utils::types::{BatchTransaction, Valset};
+use serde::Serialize;
+use serde_json::json;
+use std::convert::TryFrom;
+use tonic::transport::Channel;
+
+pub async fn query(opts: QueryOpts, address_prefix: String) {
+    let cosmos_grpc = opts.cosmos_grpc.clone();
+    let connections =
+        create_rpc_connections(address_prefix, Some(opts.cosmos_grpc), None, TIMEOUT).await;
+    let mut grpc = connections.grpc.unwrap();
+    let json = opts.json || is_json();
+
+    let res = match opts.subcmd {
+        QuerySubcommand::Params => query_params(&mut grpc, json).await,
+        QuerySubcommand::CurrentValset => query_current_valset(&mut grpc, json).await,
+        QuerySubcommand::PendingValsets => query_pending_valsets(&mut grpc, json).await,
+        QuerySubcommand::Batches(batch_opts) => query_batches(&mut grpc, batch_opts, json).await,
+        QuerySubcommand::LogicCalls(call_opts) => {
+            query_logic_calls(&mut grpc, call_opts, json).await
+        }
+        QuerySubcommand::Attestations(attestation_opts) => {
+            query_attestations(&mut grpc, cosmos_grpc, attestation_opts, json).await
+        }
+        QuerySubcommand::DelegateKeys(key_opts) => {
+            query_delegate_keys(&mut grpc, key_opts, json).await
+        }
+        QuerySubcommand::Erc20ToDenom(erc20_opts) => {
+            query_erc20_to_denom(&mut grpc, erc20_opts, json).await
+        }
+        QuerySubcommand::DenomToErc20(denom_opts) => {
+            query_denom_to_erc20(&mut grpc, denom_opts, json).await
+        }
+        QuerySubcommand::PendingSends(send_opts) => {
+            query_pending_sends(&mut grpc, send_opts, json).await
+        }
+    };
+    if let Err(e) = res {
+        fail(ErrorCode::Query, format!("Query failed {:?}", e));
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    if is_json() {
+        print_result(value)
+    } else {
+        println!("{}", serde_json::to_string_pretty(value).unwrap())
+    }
+}
+
+async fn query_params(
+    grpc: &mut GravityQueryClient<Channel>,
+    json: bool,
+) -> Result<(), GravityError> {
+    let params = get_gravity_params(grpc).await?;
+    let valset_reward = params
+        .valset_reward
+        .map(|c| format!("{}{}", c.amount, c.denom))
+        .unwrap_or_default();
+    let value = json!({
+        "gravity_id": params.gravity_id,
+        "contract_source_hash": params.contract_source_hash,
+        "bridge_ethereum_address": params.bridge_ethereum_address,
+        "bridge_chain_id": params.bridge_chain_id,
+        "signed_valsets_window": params.signed_valsets_window,
+        "signed_batches_window": params.signed_batches_window,
+        "signed_logic_calls_window": params.signed_logic_calls_window,
+        "target_batch_timeout": params.target_batch_timeout,
+        "average_block_time": params.average_block_time,
+        "average_ethereum_block_time": params.average_ethereum_block_time,
+        "slash_fraction_valset": String::from_utf8_lossy(&params.slash_fraction_valset),
+        "slash_fraction_batch": String::from_utf8_lossy(&params.slash_fraction_batch),
+        "slash_fraction_logic_call": String::from_utf8_lossy(&params.slash_fraction_logic_call),
+        "unbond_slashing_valsets_window": params.unbond_slashing_valsets_window,
+        "slash_fraction_bad_eth_signature": String::from_utf8_lossy(&params.slash_fraction_bad_eth_signature),
+        "valset_reward": valset_reward,
+    });
+    if json {
+        print_json(&value);
+    } else {
+        for (key, value) in value.as_object().unwrap() {
+            println!("{}: {}", key, value);
+        }
+    }
+    Ok(())
+}
+
+fn print_valset(valset: &Valset) {
+    println!(
+        "Valset {} with {} members, reward {} {}",
+        valset.nonce,
+        valset.members.len(),
+        valset.reward_amount,
+        valset
+            .reward_token
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "none".to_string())
+    );
+    for member in valset.members.iter() {
+        println!(
+            "  {} power {}",
+            member
+                .eth_address
+                .map(|a| a.to_string())
+                .unwrap_or_else(|| "no address".to_string()),
+            member.power
+        );
+    }
+}
+
+async fn query_current_valset(
+    grpc: &mut GravityQueryClient<Channel>,
+    json: bool,
+) -> Result<(), GravityError> {
+    let valset = get_current_valset(grpc).await?;
+    if json {
+        print_json(&valset);
+    } else {
+        print_valset(&valset);
+    }
+    Ok(())
+}
+
+async fn query_pending_valsets(
+    grpc: &mut GravityQueryClient<Channel>,
+    json: bool,
+) -> Result<(), GravityError> {
+    let valsets = get_latest_valsets(grpc).await?;
+    if json {
+        print_json(&valsets);
+    } else if valsets.is_empty() {
+        println!("No valset requests");
+    } else {
+        for valset in valsets.iter() {
+            print_valset(valset);
+        }
+    }
+    Ok(())
+}
+
+async fn query_batches(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: BatchesQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let batches = get_latest_transaction_batches(grpc).await?;
+    let mut out = Vec::new();
+    for batch in batches {
+        let confirms = if opts.confirms {
+            Some(get_transaction_batch_signatures(grpc, batch.nonce, batch.token_contract).await?)
+        } else {
+            None
+        };
+        out.push((batch, confirms));
+    }
+    if json {
+        let value: Vec<_> = out
+            .iter()
+            .map(|(batch, confirms)| json!({"batch": batch, "confirms": confirms}))
+            .collect();
+        print_json(&value);
+        return Ok(());
+    }
+    if out.is_empty() {
+        println!("No outgoing batches");
+    }
+    for (batch, confirms) in out {
+        println!(
+            "Batch {}:{} created at block {} with {} transactions and {} in fees, times out at Ethereum block {}",
+            batch.token_contract,
+            batch.nonce,
+            batch.block,
+            batch.transactions.len(),
+            batch.total_fee.amount,
+            batch.batch_timeout
+        );
+        if let Some(confirms) = confirms {
+            println!("  {} confirms", confirms.len());
+            for confirm in confirms {
+                println!(
+                    "  {} signed with {}",
+                    confirm.orchestrator, confirm.ethereum_signer
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn query_logic_calls(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: LogicCallsQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let calls = get_latest_logic_calls(grpc).await?;
+    let mut out = Vec::new();
+    for call in calls {
+        let confirms = if opts.confirms {
+            Some(
+                get_logic_call_signatures(
+                    grpc,
+                    call.invalidation_id.clone(),
+                    call.invalidation_nonce,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+        out.push((call, confirms));
+    }
+    if json {
+        let value: Vec<_> = out
+            .iter()
+            .map(|(call, confirms)| json!({"logic_call": call, "confirms": confirms}))
+            .collect();
+        print_json(&value);
+        return Ok(());
+    }
+    if out.is_empty() {
+        println!("No outgoing logic calls");
+    }
+    for (call, confirms) in out {
+        println!(
+            "Logic call {}:{} to {} created at block {}, times out at Ethereum block {}",
+            bytes_to_hex_str(&call.invalidation_id),
+            call.invalidation_nonce,
+            call.logic_contract_address,
+            call.block,
+            call.timeout
+        );
+        for transfer in call.transfers.iter() {
+            println!(
+                "  transfers {} of {}",
+                transfer.amount, transfer.token_contract_address
+            );
+        }
+        for fee in call.fees.iter() {
+            println!("  pays {} of {}", fee.amount, fee.token_contract_address);
+        }
+        if let Some(confirms) = confirms {
+            println!("  {} confirms", confirms.len());
+            for confirm in confirms {
+                println!(
+                    "  {} signed with {}",
+                    confirm.orchestrator, confirm.ethereum_signer
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn query_attestations(
+    grpc: &mut GravityQueryClient<Channel>,
+    cosmos_grpc: String,
+    opts: AttestationsQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let mut staking = match StakingQueryClient::connect(cosmos_grpc.clone()).await {
+        Ok(client) => client,
+        Err(e) => fail(
+            ErrorCode::Connection,
+            format!("Could not connect to {} {:?}", cosmos_grpc, e),
+        ),
+    };
+    let validators = get_bonded_validators(&mut staking).await?;
+    let attestations: Vec<AttestationVotes> = get_typed_attestations(grpc, opts.limit)
+        .await?
+        .into_iter()
+        .filter(|a| {
+            opts.event_nonce
+                .map_or(true, |n| a.claim.event_nonce() == n)
+        })
+        .map(|a| resolve_votes(a, &validators))
+        .collect();
+    if json {
+        let value: Vec<_> = attestations
+            .iter()
+            .map(|a| {
+                json!({
+                    "event_nonce": a.attestation.claim.event_nonce(),
+                    "block_height": a.attestation.claim.block_height(),
+                    "claim_type": a.attestation.claim.claim_type(),
+                    "description": a.attestation.claim.describe(),
+                    "observed": a.attestation.observed,
+                    "height": a.attestation.height,
+                    "voted": a.voted,
+                    "not_voted": a.not_voted,
+                    "voted_power": a.voted_power,
+                    "total_power": a.total_power,
+                })
+            })
+            .collect();
+        print_json(&value);
+        return Ok(());
+    }
+    if attestations.is_empty() {
+        println!("No attestations");
+    }
+    for a in attestations {
+        let claim = &a.attestation.claim;
+        println!(
+            "Event nonce {} at Ethereum block {}, {}, {} of {} validators voted ({:.2}% of power)",
+            claim.event_nonce(),
+            claim.block_height(),
+            if a.attestation.observed {
+                "observed"
+            } else {
+                "not observed"
+            },
+            a.voted.len(),
+            validators.len(),
+            power_percent(a.voted_power, a.total_power),
+        );
+        println!("  {} {}", claim.claim_type(), claim.describe());
+        for v in a.not_voted {
+            println!(
+                "  Not voted: {} {} power {}",
+                v.moniker, v.operator_address, v.power
+            );
+        }
+    }
+    Ok(())
+}
+
+fn power_percent(power: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        power as f64 / total as f64 * 100.0
+    }
+}
+
+async fn query_delegate_keys(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: DelegateKeysQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let (validator, orchestrator, eth_address) =
+        match (opts.validator, opts.eth_address, opts.orchestrator) {
+            (Some(validator), None, None) => {
+                let (orchestrator, eth_address) =
+                    get_delegate_keys_by_validator(grpc, validator.clone()).await?;
+                (validator, orchestrator, eth_address)
+            }
+            (None, Some(eth_address), None) => {
+                let (validator, orchestrator) = get_delegate_keys_by_eth(grpc, eth_address).await?;
+                (validator, orchestrator, eth_address)
+            }
+            (None, None, Some(orchestrator)) => {
+                let (validator, eth_address) =
+                    get_delegate_keys_by_orchestrator(grpc, orchestrator).await?;
+                (validator, orchestrator, eth_address)
+            }
+            _ => {
+                fail(
+                    ErrorCode::InvalidInput,
+                    "Provide exactly one of --validator, --eth-address or --orchestrator",
+                );
+            }
+        };
+    if json {
+        print_json(&json!({
+            "validator": validator,
+            "orchestrator": orchestrator.to_string(),
+            "eth_address": eth_address.to_string(),
+        }));
+    } else {
+        println!("Validator: {}", validator);
+        println!("Orchestrator: {}", orchestrator);
+        println!("Ethereum: {}", eth_address);
+    }
+    Ok(())
+}
+
+async fn query_erc20_to_denom(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: Erc20ToDenomQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let res = get_erc20_to_denom(grpc, opts.erc20).await?;
+    if json {
+        print_json(&json!({
+            "erc20": opts.erc20.to_string(),
+            "denom": res.denom,
+            "cosmos_originated": res.cosmos_originated,
+        }));
+    } else {
+        println!(
+            "{} is represented on Cosmos by {}{}",
+            opts.erc20,
+            res.denom,
+            if res.cosmos_originated {
+                ", it originated on Cosmos"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
+
+async fn query_denom_to_erc20(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: DenomToErc20QueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let res = get_denom_to_erc20(grpc, opts.denom.clone()).await?;
+    if json {
+        print_json(&json!({
+            "denom": opts.denom,
+            "erc20": res.erc20,
+            "cosmos_originated": res.cosmos_originated,
+        }));
+    } else {
+        println!(
+            "{} is represented on Ethereum by {}{}",
+            opts.denom,
+            res.erc20,
+            if res.cosmos_originated {
+                ", it originated on Cosmos"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
+
+async fn query_pending_sends(
+    grpc: &mut GravityQueryClient<Channel>,
+    opts: PendingSendsQueryOpts,
+    json: bool,
+) -> Result<(), GravityError> {
+    let res = get_pending_send_to_eth(grpc, opts.address).await?;
+    let mut in_batches = Vec::new();
+    for tx in res.transfers_in_batches {
+        in_batches.push(BatchTransaction::try_from(tx)?);
+    }
+    let mut unbatched = Vec::new();
+    for tx in res.unbatched_transfers {
+        unbatched.push(BatchTransaction::try_from(tx)?);
+    }
+    if json {
+        print_json(&json!({
+            "transfers_in_batches": in_batches,
+            "unbatched_transfers": unbatched,
+        }));
+        return Ok(());
+    }
+    if in_batches.is_empty() && unbatched.is_empty() {
+        println!("No pending transfers for {}", opts.address);
+    }
+    for (label, txs) in [("in a batch", in_batches), ("unbatched", unbatched)].iter() {
+        for tx in txs.iter() {
+            println!(
+                "{} id {} sending {} of {} to {} with {} fee",
+                label,
+                tx.id,
+                tx.erc20_token.amount,
+                tx.erc20_token.token_contract_address,
+                tx.destination,
+                tx.erc20_fee.amount
+            );
+        }
+    }
+    Ok(())
+}
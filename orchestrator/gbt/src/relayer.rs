@@ -1,29 +1,44 @@
 use crate::args::RelayerOpts;
 use crate::config::config_exists;
 use crate::config::load_keys;
+use crate::config::{parse_config_gravity_address, parse_config_keys};
+use crate::output::{fail, ErrorCode};
 use cosmos_gravity::query::get_gravity_params;
+use gravity_utils::connection_pool::{connect_cosmos_pool, split_grpc_urls};
 use gravity_utils::connection_prep::{
     check_for_eth, create_rpc_connections, wait_for_cosmos_node_ready,
 };
-use gravity_utils::types::RelayerConfig;
+use gravity_utils::types::GravityBridgeToolsConfig;
 use relayer::main_loop::relayer_main_loop;
 use relayer::main_loop::LOOP_SPEED;
 use std::path::Path;
-use std::process::exit;
 
 pub async fn relayer(
     args: RelayerOpts,
     address_prefix: String,
     home_dir: &Path,
-    config: &RelayerConfig,
+    config: &GravityBridgeToolsConfig,
 ) {
-    let cosmos_grpc = args.cosmos_grpc;
-    let ethereum_rpc = args.ethereum_rpc;
-    let ethereum_key = args.ethereum_key;
-    let connections = create_rpc_connections(
+    // the command line takes precedence over the config and environment
+    let (_, config_ethereum_key) = parse_config_keys(&config.keys);
+    let cosmos_grpc = args
+        .cosmos_grpc
+        .unwrap_or_else(|| config.connection.cosmos_grpc.clone());
+    let (cosmos_grpc, cosmos_grpc_fallbacks) = split_grpc_urls(&cosmos_grpc);
+    let ethereum_rpc = args
+        .ethereum_rpc
+        .unwrap_or_else(|| config.connection.ethereum_rpc.clone());
+    let gravity_contract_address = args
+        .gravity_contract_address
+        .or_else(|| parse_config_gravity_address(&config.connection));
+    let ethereum_key = args.ethereum_key.or(config_ethereum_key);
+    let connections =
+        create_rpc_connections(address_prefix.clone(), None, Some(ethereum_rpc), LOOP_SPEED).await;
+    // starts on a fallback endpoint if the primary is down
+    let cosmos = connect_cosmos_pool(
         address_prefix,
-        Some(cosmos_grpc),
-        Some(ethereum_rpc),
+        cosmos_grpc,
+        cosmos_grpc_fallbacks,
         LOOP_SPEED,
     )
     .await;
@@ -39,11 +54,10 @@ pub async fn relayer(
             }
         }
         if k.is_none() {
-            error!("You must specify an Ethereum key!");
             error!("To generate, register, and store a key use `gbt keys register-orchestrator-address`");
             error!("Store an already registered key using 'gbt keys set-ethereum-key`");
             error!("To run from the command line, with no key storage use 'gbt orchestrator --ethereum-key your key' ");
-            exit(1);
+            fail(ErrorCode::Config, "You must specify an Ethereum key!");
         }
         k.unwrap()
     };
@@ -54,28 +68,36 @@ pub async fn relayer(
     info!("Starting Gravity Relayer");
     info!("Ethereum Address: {}", public_eth_key);
 
-    let contact = connections.contact.clone().unwrap();
+    let contact = cosmos.get_contact();
     let web3 = connections.web3.unwrap();
-    let mut grpc = connections.grpc.unwrap();
+    let mut grpc = cosmos.get_grpc();
 
     // check if the cosmos node is syncing, if so wait for it
     // we can't move any steps above this because they may fail on an incorrect
     // historic chain state while syncing occurs
     wait_for_cosmos_node_ready(&contact).await;
-    check_for_eth(public_eth_key, &web3).await;
+    if let Err(e) = check_for_eth(public_eth_key, &web3).await {
+        fail(ErrorCode::InsufficientFunds, e);
+    }
 
     // get the gravity contract address, if not provided
-    let contract_address = if let Some(c) = args.gravity_contract_address {
+    let contract_address = if let Some(c) = gravity_contract_address {
         c
     } else {
         let params = get_gravity_params(&mut grpc).await.unwrap();
         let c = params.bridge_ethereum_address.parse();
         if c.is_err() {
-            error!("The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
-            exit(1);
+            fail(ErrorCode::Query, "The Gravity address is not yet set as a chain parameter! You must specify --gravity-contract-address");
         }
         c.unwrap()
     };
 
-    relayer_main_loop(ethereum_key, web3, grpc, contract_address, config).await
+    relayer_main_loop(
+        ethereum_key,
+        web3,
+        cosmos,
+        contract_address,
+        &config.relayer,
+    )
+    .await
 }
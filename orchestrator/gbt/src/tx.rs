@@ -0,0 +1,190 @@
+//! Offline signing, commands that support --generate-only print an unsigned transaction
+//! that is signed with `gbt tx sign` on a machine without network access and submitted
+//! later with `gbt tx broadcast`
+
+use crate::args::{GenerateOnlyOpts, TxBroadcastOpts, TxSignOpts};
+use crate::config::parse_cosmos_phrase;
+use crate::output::{fail, is_json, print_result, ErrorCode};
+use crate::utils::{confirm_prompt, TIMEOUT};
+use clarity::utils::{bytes_to_hex_str, hex_str_to_bytes};
+use cosmos_gravity::offline::UnsignedTx;
+use cosmos_gravity::send::MEMO;
+use deep_space::address::Address as CosmosAddress;
+use deep_space::{Contact, Fee, Msg, PrivateKey as CosmosPrivateKey};
+use gravity_proto::cosmos_sdk_proto::cosmos::tx::v1beta1::BroadcastMode;
+use gravity_utils::connection_prep::create_rpc_connections;
+use gravity_utils::types::{FeeSettings, KeysConfig};
+use serde_json::json;
+use std::fs;
+
+/// Returns the key that signs a transaction and the address sending it. With --generate-only
+/// no key is needed and the address comes from --from
+pub fn get_sender(
+    phrase: Option<String>,
+    opts: &GenerateOnlyOpts,
+    prefix: &str,
+    keys: &KeysConfig,
+) -> (Option<CosmosPrivateKey>, CosmosAddress) {
+    let key = phrase.map(|phrase| parse_cosmos_phrase(&phrase, keys));
+    match (key, opts.generate_only, opts.from) {
+        (Some(key), false, _) => (Some(key), key.to_address(prefix).unwrap()),
+        (Some(key), true, None) => (None, key.to_address(prefix).unwrap()),
+        (None, true, Some(from)) => (None, from),
+        (_, true, Some(_)) => {
+            fail(
+                ErrorCode::InvalidInput,
+                "Provide either a key or --from with --generate-only, not both",
+            );
+        }
+        (None, false, _) => {
+            fail(
+                ErrorCode::InvalidInput,
+                "Please provide a Cosmos phrase, or use --generate-only with --from",
+            );
+        }
+        (None, true, None) => {
+            fail(
+                ErrorCode::InvalidInput,
+                "Please provide the signing address with --from",
+            );
+        }
+    }
+}
+
+/// Prints the unsigned transaction JSON for the given messages, the fee is computed from
+/// the gas price and the provided gas limit as the transaction can not be simulated
+pub async fn generate_only(
+    contact: &Contact,
+    sender: CosmosAddress,
+    msgs: Vec<Msg>,
+    opts: &GenerateOnlyOpts,
+    fee: &FeeSettings,
+) {
+    let fee = Fee {
+        amount: vec![fee.fee_for_gas(opts.gas_limit)],
+        gas_limit: opts.gas_limit,
+        granter: None,
+        payer: None,
+    };
+    let args = match contact.get_message_args(sender, fee.clone()).await {
+        Ok(args) => args,
+        Err(e) => {
+            fail(
+                ErrorCode::Query,
+                format!("Failed to get the account of {} {:?}", sender, e),
+            );
+        }
+    };
+    let tx = UnsignedTx::new(args.chain_id, sender, msgs, fee, MEMO);
+    if is_json() {
+        print_result(&json!({
+            "unsigned_tx": tx,
+            "account_number": args.account_number,
+            "sequence": args.sequence,
+        }));
+    } else {
+        println!("{}", serde_json::to_string_pretty(&tx).unwrap());
+    }
+    info!(
+        "Sign this transaction offline with `gbt tx sign <file> --account-number {} --sequence {}`",
+        args.account_number, args.sequence
+    );
+    info!(
+        "The sequence increases with every transaction sent by {} before this one is broadcast",
+        sender
+    );
+}
+
+pub fn sign(args: TxSignOpts, prefix: String, keys: &KeysConfig) {
+    let tx: UnsignedTx = match fs::read_to_string(&args.file)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            fail(
+                ErrorCode::Io,
+                format!("Failed to read {} {}", args.file.display(), e),
+            );
+        }
+    };
+    let msgs = match tx.decode_msgs() {
+        Ok(msgs) => msgs,
+        Err(e) => {
+            fail(ErrorCode::InvalidInput, format!("{:?}", e));
+        }
+    };
+
+    info!(
+        "Transaction for chain {} signed by {}",
+        tx.chain_id, tx.signer
+    );
+    for (_, description) in msgs.iter() {
+        info!("{}", description);
+    }
+    info!(
+        "Fee {} with a gas limit of {}",
+        tx.fee_amount.join(", "),
+        tx.gas_limit
+    );
+    if !args.yes && !confirm_prompt("Sign this transaction?") {
+        info!("Nothing was signed");
+        return;
+    }
+
+    let key = parse_cosmos_phrase(&args.cosmos_phrase, keys);
+    let signed = match tx.sign(key, &prefix, args.account_number, args.sequence) {
+        Ok(bytes) => bytes_to_hex_str(&bytes),
+        Err(e) => {
+            fail(ErrorCode::InvalidInput, format!("Failed to sign {:?}", e));
+        }
+    };
+    match args.output {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, signed) {
+                fail(
+                    ErrorCode::Io,
+                    format!("Failed to write {} {:?}", path.display(), e),
+                );
+            }
+            info!("Signed transaction written to {}", path.display());
+            print_result(&json!({ "output": path.display().to_string() }));
+        }
+        None if is_json() => print_result(&json!({ "signed_tx": signed })),
+        None => println!("{}", signed),
+    }
+}
+
+pub async fn broadcast(args: TxBroadcastOpts, prefix: String) {
+    let bytes = match fs::read_to_string(&args.file)
+        .map_err(|e| e.to_string())
+        .and_then(|s| hex_str_to_bytes(s.trim()).map_err(|e| format!("{:?}", e)))
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            fail(
+                ErrorCode::Io,
+                format!("Failed to read {} {}", args.file.display(), e),
+            );
+        }
+    };
+
+    let connections = create_rpc_connections(prefix, Some(args.cosmos_grpc), None, TIMEOUT).await;
+    let contact = connections.contact.unwrap();
+    let res = match contact.send_transaction(bytes, BroadcastMode::Sync).await {
+        Ok(res) => contact.wait_for_tx(res, TIMEOUT).await,
+        Err(e) => Err(e),
+    };
+    match res {
+        Ok(res) => {
+            info!("Transaction included with txid {}", res.txhash);
+            print_result(&json!({ "txhash": res.txhash, "height": res.height }));
+        }
+        Err(e) => {
+            fail(
+                ErrorCode::Transaction,
+                format!("Failed to broadcast transaction {:?}", e),
+            );
+        }
+    }
+}
@@ -1,55 +1,310 @@
+use crate::output::{fail, is_json, ErrorCode};
+use clarity::Address as EthAddress;
 use clarity::Uint256;
+use cosmos_gravity::query::get_denom_to_erc20;
+use deep_space::{Coin, Contact};
+use gravity_proto::cosmos_sdk_proto::cosmos::bank::v1beta1::query_client::QueryClient as BankQueryClient;
+use gravity_proto::cosmos_sdk_proto::cosmos::bank::v1beta1::QueryDenomMetadataRequest;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+pub use gravity_utils::num_conversion::{format_amount, parse_amount};
+use gravity_utils::types::{FeeConfig, FeeSettings, GasPrice};
+use std::collections::HashMap;
+use std::io::{stderr, stdin, stdout, Write};
+use std::str::FromStr;
 use std::time::Duration;
+use tonic::transport::Channel;
+use web30::client::Web3;
 
 pub const TIMEOUT: Duration = Duration::from_secs(60);
 
-pub fn one_eth() -> f64 {
-    1000000000000000000f64
+/// Takes a connection that was requested from `create_rpc_connections`, exiting with a
+/// connection error if it could not be made
+pub fn require_connection<T>(connection: Option<T>, name: &str) -> T {
+    match connection {
+        Some(connection) => connection,
+        None => fail(
+            ErrorCode::Connection,
+            format!("Could not connect to the {}", name),
+        ),
+    }
+}
+
+/// Combines the gas price provided on the command line with the fee options in the config
+/// file, exiting with an error if no gas price has been provided in either place
+pub fn get_fee_settings(gas_price: Option<GasPrice>, config: &FeeConfig) -> FeeSettings {
+    if gas_price.is_none() && config.gas_price.is_none() {
+        error!("You must specify a gas price to pay Cosmos chain fees!");
+        fail(
+            ErrorCode::Config,
+            "Use --gas-price 0.025ugraviton or set gas_price in the [fees] section of your config",
+        );
+    }
+    match config.get_fee_settings(gas_price) {
+        Ok(settings) => settings,
+        Err(e) => {
+            fail(
+                ErrorCode::Config,
+                format!("Invalid [fees] configuration {}", e),
+            );
+        }
+    }
+}
+
+/// Like `get_fee_settings` but also accepts the deprecated --fees flag, which pays the same fixed
+/// fee for every transaction regardless of the gas it uses
+pub fn get_fee_settings_or_fees(
+    gas_price: Option<GasPrice>,
+    fees: Option<Coin>,
+    config: &FeeConfig,
+) -> FeeSettings {
+    let fees = match fees {
+        Some(fees) => fees,
+        None => return get_fee_settings(gas_price, config),
+    };
+    warn!("--fees is deprecated and will be removed, use --gas-price so the fee follows the gas each transaction uses");
+    let config = FeeConfig {
+        // a fixed fee can not be adjusted
+        max_gas_price: None,
+        ..config.clone()
+    };
+    let gas_price = GasPrice {
+        amount: 0u8.into(),
+        denom: fees.denom.clone(),
+    };
+    let mut settings = get_fee_settings(Some(gas_price), &config);
+    settings.fixed_fee = Some(fees);
+    settings
+}
+
+/// Shows a prompt on the terminal, with `--output json` stdout is reserved for the result
+/// so prompts go to stderr
+fn show_prompt(prompt: &str) {
+    if is_json() {
+        eprint!("{}", prompt);
+        stderr().flush().unwrap();
+    } else {
+        print!("{}", prompt);
+        stdout().flush().unwrap();
+    }
 }
 
-pub fn one_atom() -> f64 {
-    1000000f64
+/// Asks the user a yes or no question on the terminal, anything other than y or yes is a no
+pub fn confirm_prompt(question: &str) -> bool {
+    show_prompt(&format!("{} [y/N] ", question));
+    let mut input = String::new();
+    if stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
-/// TODO revisit this for higher precision while
-/// still representing the number to the user as a float
-/// this takes a number like 0.37 eth and turns it into wei
-/// or any erc20 with arbitrary decimals
-pub fn fraction_to_exponent(num: f64, exponent: u8) -> Uint256 {
-    let mut res = num;
-    // in order to avoid floating point rounding issues we
-    // multiply only by 10 each time. this reduces the rounding
-    // errors enough to be ignored
-    for _ in 0..exponent {
-        res *= 10f64
+/// Asks the user for a value on the terminal, an empty answer returns the default. Stops
+/// if stdin is closed rather than silently accepting the default for every question
+pub fn input_prompt(question: &str, default: Option<&str>) -> String {
+    match default {
+        Some(default) => show_prompt(&format!("{} [{}]: ", question, default)),
+        None => show_prompt(&format!("{}: ", question)),
+    }
+    let mut input = String::new();
+    match stdin().read_line(&mut input) {
+        Ok(0) => fail(
+            ErrorCode::InvalidInput,
+            format!("No answer to \"{}\", stdin was closed", question),
+        ),
+        Ok(_) => {}
+        Err(e) => fail(
+            ErrorCode::Io,
+            format!("Failed to read the answer to \"{}\" {:?}", question, e),
+        ),
+    }
+    match input.trim() {
+        "" => default.unwrap_or_default().to_string(),
+        v => v.to_string(),
     }
-    (res as u128).into()
 }
 
-pub fn print_eth(input: Uint256) -> String {
-    let float: f64 = input.to_string().parse().unwrap();
-    let res = float / one_eth();
-    format!("{}", res)
+/// How amounts of a Cosmos denom are shown to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDisplay {
+    /// The number of decimals between the base denom and the display unit
+    pub decimals: u8,
+    /// The name of the display unit
+    pub name: String,
 }
 
-pub fn print_atom(input: Uint256) -> String {
-    let float: f64 = input.to_string().parse().unwrap();
-    let res = float / one_atom();
-    format!("{}", res)
+/// Looks up how Cosmos denoms should be displayed, one bank connection is kept for every
+/// lookup and each denom is only looked up once
+pub struct TokenDisplays {
+    bank: Option<BankQueryClient<Channel>>,
+    cache: HashMap<String, Option<TokenDisplay>>,
+}
+
+impl TokenDisplays {
+    pub async fn new(contact: &Contact) -> TokenDisplays {
+        TokenDisplays {
+            bank: BankQueryClient::connect(contact.get_url()).await.ok(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Looks up how a Cosmos denom should be displayed. The bank denom metadata is used if the
+    /// chain has any for the denom, otherwise if web3 is provided and the denom represents an
+    /// ERC20 the ERC20's decimals are used. Returns None if neither is available
+    pub async fn get(
+        &mut self,
+        grpc: &mut GravityQueryClient<Channel>,
+        web3: Option<&Web3>,
+        denom: &str,
+    ) -> Option<TokenDisplay> {
+        if let Some(display) = self.cache.get(denom) {
+            return display.clone();
+        }
+        let display = match self.get_metadata(denom).await {
+            Some(display) => Some(display),
+            None => get_erc20_display(grpc, web3, denom).await,
+        };
+        self.cache.insert(denom.to_string(), display.clone());
+        display
+    }
+
+    async fn get_metadata(&mut self, denom: &str) -> Option<TokenDisplay> {
+        let metadata = self
+            .bank
+            .as_mut()?
+            .denom_metadata(QueryDenomMetadataRequest {
+                denom: denom.to_string(),
+            })
+            .await
+            .ok()?
+            .into_inner()
+            .metadata?;
+        let unit = metadata
+            .denom_units
+            .iter()
+            .find(|u| u.denom == metadata.display)?;
+        if unit.exponent > u8::MAX as u32 {
+            return None;
+        }
+        Some(TokenDisplay {
+            decimals: unit.exponent as u8,
+            name: metadata.display.clone(),
+        })
+    }
+}
+
+async fn get_erc20_display(
+    grpc: &mut GravityQueryClient<Channel>,
+    web3: Option<&Web3>,
+    denom: &str,
+) -> Option<TokenDisplay> {
+    let web3 = web3?;
+    let erc20: EthAddress = get_denom_to_erc20(grpc, denom.to_string())
+        .await
+        .ok()?
+        .erc20
+        .parse()
+        .ok()?;
+    let decimals = web3.get_erc20_decimals(erc20, erc20).await.ok()?;
+    Some(TokenDisplay {
+        decimals: decimals.to_string().parse().ok()?,
+        name: denom.to_string(),
+    })
+}
+
+/// A token amount given as a number followed by the denom eg: 100ugraviton or 1.5ugraviton,
+/// the number is kept as text until the decimals of the token are known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAmount {
+    pub amount: String,
+    pub denom: String,
+}
+
+impl FromStr for TokenAmount {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .ok_or_else(|| format!("{} has no denom, use the format 100ugraviton", s))?;
+        let (amount, denom) = s.split_at(split);
+        if amount.is_empty() {
+            return Err(format!("{} has no amount, use the format 100ugraviton", s));
+        }
+        Ok(TokenAmount {
+            amount: amount.to_string(),
+            denom: denom.to_string(),
+        })
+    }
+}
+
+/// Parses an amount of a token, amounts are in the token's display units unless base_units
+/// is set. Display units need the token's decimals, when they are unknown the amount is
+/// rejected rather than guessing, as is a fractional amount of base units
+pub fn parse_token_amount(
+    input: &str,
+    decimals: Option<u8>,
+    base_units: bool,
+) -> Result<Uint256, String> {
+    if base_units {
+        if input.contains('.') {
+            return Err(format!(
+                "{} has a decimal point but base units are whole numbers",
+                input
+            ));
+        }
+        return parse_amount(input, 0);
+    }
+    match decimals {
+        Some(decimals) => parse_amount(input, decimals),
+        None => Err(format!(
+            "the decimals of the token are unknown so {} can't be read as display units, pass --base-units and give the amount in base units",
+            input
+        )),
+    }
+}
+
+/// Formats an amount of a Cosmos denom, in display units if we know them and in
+/// base units otherwise
+pub fn print_coin(amount: &Uint256, denom: &str, display: &Option<TokenDisplay>) -> String {
+    match display {
+        Some(display) => format!(
+            "{} {}",
+            format_amount(amount, display.decimals),
+            display.name
+        ),
+        None => format!("{}{}", amount, denom),
+    }
 }
 
 #[test]
-fn even_f32_rounding() {
-    let one_eth: Uint256 = 1000000000000000000u128.into();
-    let one_point_five_eth: Uint256 = 1500000000000000000u128.into();
-    let one_point_one_five_eth: Uint256 = 1150000000000000000u128.into();
-    let a_high_precision_number: Uint256 = 1150100000000000000u128.into();
-    let res = fraction_to_exponent(1f64, 18);
-    assert_eq!(one_eth, res);
-    let res = fraction_to_exponent(1.5f64, 18);
-    assert_eq!(one_point_five_eth, res);
-    let res = fraction_to_exponent(1.15f64, 18);
-    assert_eq!(one_point_one_five_eth, res);
-    let res = fraction_to_exponent(1.1501f64, 18);
-    assert_eq!(a_high_precision_number, res);
+fn test_token_amount() {
+    let amount: TokenAmount = "1.5ugraviton".parse().unwrap();
+    assert_eq!(amount.amount, "1.5");
+    assert_eq!(amount.denom, "ugraviton");
+    let amount: TokenAmount = "100gravity0xD50c0953a99325d01cca655E57070F1be4983b6b"
+        .parse()
+        .unwrap();
+    assert_eq!(amount.amount, "100");
+    assert!("100".parse::<TokenAmount>().is_err());
+    assert!("ugraviton".parse::<TokenAmount>().is_err());
+
+    // display units unless base units are asked for, whether or not there is a decimal point
+    assert_eq!(
+        parse_token_amount("1", Some(6), false).unwrap(),
+        1000000u64.into()
+    );
+    assert_eq!(
+        parse_token_amount("1.5", Some(6), false).unwrap(),
+        1500000u64.into()
+    );
+    assert_eq!(
+        parse_token_amount("100", Some(6), true).unwrap(),
+        100u8.into()
+    );
+    assert_eq!(parse_token_amount("100", None, true).unwrap(), 100u8.into());
+    // ambiguous amounts are rejected
+    assert!(parse_token_amount("100", None, false).is_err());
+    assert!(parse_token_amount("1.5", None, false).is_err());
+    assert!(parse_token_amount("1.5", Some(6), true).is_err());
+    assert!(parse_token_amount("1.5", Some(0), false).is_err());
 }
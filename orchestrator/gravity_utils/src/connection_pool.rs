@@ -0,0 +1,319 @@
+//! The Cosmos connection pool allows the Orchestrator and Relayer to use more than one Cosmos gRPC
+//! endpoint. Long running loops fetch the current Contact and GravityQueryClient from the pool at the
+//! start of every iteration, when a node is found to be syncing or unreachable the pool is asked to
+//! fail over to the next endpoint that is online and caught up. This allows a validator to recover
+//! from a failed full node without restarting the Orchestrator.
+
+use crate::connection_prep::create_rpc_connections;
+use deep_space::client::ChainStatus;
+use deep_space::Contact;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tonic::transport::Channel;
+
+/// A single Cosmos gRPC endpoint and it's connections
+#[derive(Clone)]
+struct CosmosConnection {
+    url: String,
+    contact: Contact,
+    grpc: GravityQueryClient<Channel>,
+}
+
+struct PoolState {
+    /// All endpoints provided by the user, the first is the primary
+    endpoints: Vec<String>,
+    /// Index into endpoints of the connection currently in use
+    current: usize,
+    connection: CosmosConnection,
+}
+
+/// A set of Cosmos gRPC endpoints, only one of which is in use at any given time.
+/// This struct is cheap to clone, all clones share the same state so a failover triggered
+/// by one loop is picked up by all others on their next iteration.
+#[derive(Clone)]
+pub struct CosmosConnectionPool {
+    address_prefix: String,
+    timeout: Duration,
+    state: Arc<RwLock<PoolState>>,
+}
+
+impl CosmosConnectionPool {
+    /// Creates a new pool from an already established primary connection, usually obtained from
+    /// `create_rpc_connections`. Fallback endpoints are only connected to when they are needed.
+    pub fn new(
+        address_prefix: String,
+        primary_url: String,
+        contact: Contact,
+        grpc: GravityQueryClient<Channel>,
+        fallback_urls: Vec<String>,
+        timeout: Duration,
+    ) -> CosmosConnectionPool {
+        let endpoints = endpoint_list(&primary_url, &fallback_urls);
+        CosmosConnectionPool {
+            address_prefix,
+            timeout,
+            state: Arc::new(RwLock::new(PoolState {
+                connection: CosmosConnection {
+                    url: endpoints[0].clone(),
+                    contact,
+                    grpc,
+                },
+                endpoints,
+                current: 0,
+            })),
+        }
+    }
+
+    /// Creates a new pool connected to the first endpoint that is online and caught up, trying the
+    /// primary first. If every reachable endpoint is still syncing the first reachable one is used
+    /// so that the caller can wait for it. Returns None if no endpoint can be reached at all.
+    pub async fn connect(
+        address_prefix: String,
+        primary_url: String,
+        fallback_urls: Vec<String>,
+        timeout: Duration,
+    ) -> Option<CosmosConnectionPool> {
+        let endpoints = endpoint_list(&primary_url, &fallback_urls);
+        let mut reachable = None;
+        for (index, url) in endpoints.iter().enumerate() {
+            match connect_endpoint(url, &address_prefix, timeout).await {
+                Some((connection, true)) => {
+                    if index != 0 {
+                        warn!(
+                            "Primary Cosmos gRPC {} is not usable, starting with {}",
+                            endpoints[0], url
+                        );
+                    }
+                    reachable = Some((index, connection));
+                    break;
+                }
+                Some((connection, false)) => {
+                    if reachable.is_none() {
+                        reachable = Some((index, connection))
+                    }
+                }
+                None => warn!("Cosmos gRPC {} can not be reached", url),
+            }
+        }
+        let (current, connection) = reachable?;
+        Some(CosmosConnectionPool {
+            address_prefix,
+            timeout,
+            state: Arc::new(RwLock::new(PoolState {
+                endpoints,
+                current,
+                connection,
+            })),
+        })
+    }
+
+    /// Gets the Contact for the endpoint currently in use
+    pub fn get_contact(&self) -> Contact {
+        self.state.read().unwrap().connection.contact.clone()
+    }
+
+    /// Gets the Gravity gRPC query client for the endpoint currently in use
+    pub fn get_grpc(&self) -> GravityQueryClient<Channel> {
+        self.state.read().unwrap().connection.grpc.clone()
+    }
+
+    /// Gets the url of the endpoint currently in use
+    pub fn get_url(&self) -> String {
+        self.state.read().unwrap().connection.url.clone()
+    }
+
+    /// Gets the index of the endpoint currently in use, to be passed to `failover` if
+    /// the endpoint turns out not to be usable
+    pub fn get_endpoint(&self) -> usize {
+        self.state.read().unwrap().current
+    }
+
+    /// Checks the status of the endpoint currently in use and fails over to another endpoint
+    /// if it is syncing or can not be reached. Returns true if the pool has a usable endpoint
+    /// at the end of this call
+    pub async fn check_health(&self) -> bool {
+        let endpoint = self.get_endpoint();
+        match self.get_contact().get_chain_status().await {
+            Ok(ChainStatus::Moving { .. }) => true,
+            _ => self.failover(endpoint).await,
+        }
+    }
+
+    /// Attempts to switch away from the endpoint `failed`, as returned by `get_endpoint`, to the
+    /// next endpoint that is reachable and has a moving chain. Endpoints are tried in the order
+    /// they where provided starting after the failed one. If another task has already switched
+    /// away from `failed` nothing is done, if no other endpoint is usable the current connection
+    /// is kept. Returns true if the pool is no longer using the failed endpoint.
+    pub async fn failover(&self, failed: usize) -> bool {
+        let endpoints = {
+            let state = self.state.read().unwrap();
+            if state.current != failed {
+                return true;
+            }
+            state.endpoints.clone()
+        };
+        if endpoints.len() < 2 {
+            return false;
+        }
+
+        for offset in 1..endpoints.len() {
+            let index = (failed + offset) % endpoints.len();
+            let url = &endpoints[index];
+            match self.connect(url).await {
+                Some(connection) => {
+                    let mut state = self.state.write().unwrap();
+                    // another task may have failed over while we where connecting, keep the
+                    // endpoint it picked
+                    if state.current != failed {
+                        return true;
+                    }
+                    warn!(
+                        "Cosmos gRPC {} is not usable, failing over to {}",
+                        endpoints[failed], url
+                    );
+                    state.current = index;
+                    state.connection = connection;
+                    return true;
+                }
+                None => warn!("Fallback Cosmos gRPC {} is also not usable", url),
+            }
+        }
+        error!("No usable Cosmos gRPC endpoint! Check your Cosmos nodes");
+        false
+    }
+
+    /// Connects to a given endpoint and returns the connection only if the chain is moving
+    /// and the node is not syncing
+    async fn connect(&self, url: &str) -> Option<CosmosConnection> {
+        match connect_endpoint(url, &self.address_prefix, self.timeout).await {
+            Some((connection, true)) => Some(connection),
+            _ => None,
+        }
+    }
+}
+
+/// Connects to a given endpoint, returning the connection and whether the chain is moving and
+/// the node caught up, or None if the endpoint can not be reached
+async fn connect_endpoint(
+    url: &str,
+    address_prefix: &str,
+    timeout: Duration,
+) -> Option<(CosmosConnection, bool)> {
+    let grpc = GravityQueryClient::connect(url.to_string()).await.ok()?;
+    let contact = Contact::new(url, timeout, address_prefix).ok()?;
+    let moving = match contact.get_chain_status().await {
+        Ok(ChainStatus::Moving { .. }) => true,
+        Ok(ChainStatus::Syncing) => {
+            info!("Cosmos gRPC {} is syncing", url);
+            false
+        }
+        Ok(ChainStatus::WaitingToStart) => {
+            info!("Cosmos gRPC {} is waiting for chain start", url);
+            false
+        }
+        Err(_) => return None,
+    };
+    Some((
+        CosmosConnection {
+            url: url.to_string(),
+            contact,
+            grpc,
+        },
+        moving,
+    ))
+}
+
+/// Connects the Cosmos connection pool at startup. If none of the endpoints can be reached the
+/// primary is connected with the usual localhost and https corrections of `create_rpc_connections`,
+/// which panics with a helpful message if that also fails
+pub async fn connect_cosmos_pool(
+    address_prefix: String,
+    primary_url: String,
+    fallback_urls: Vec<String>,
+    timeout: Duration,
+) -> CosmosConnectionPool {
+    if let Some(pool) = CosmosConnectionPool::connect(
+        address_prefix.clone(),
+        primary_url.clone(),
+        fallback_urls.clone(),
+        timeout,
+    )
+    .await
+    {
+        return pool;
+    }
+    let connections =
+        create_rpc_connections(address_prefix.clone(), Some(primary_url), None, timeout).await;
+    let contact = connections.contact.unwrap();
+    // the url may have been corrected, keep the one that worked as the primary
+    let url = contact.get_url();
+    CosmosConnectionPool::new(
+        address_prefix,
+        url,
+        contact,
+        connections.grpc.unwrap(),
+        fallback_urls,
+        timeout,
+    )
+}
+
+/// Normalizes a Cosmos gRPC url so the same endpoint is always written the same way
+pub fn normalize_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+/// The primary url followed by the fallback urls, normalized and without duplicates
+fn endpoint_list(primary_url: &str, fallback_urls: &[String]) -> Vec<String> {
+    let mut endpoints = vec![normalize_url(primary_url)];
+    for url in fallback_urls {
+        let url = normalize_url(url);
+        if !url.is_empty() && !endpoints.contains(&url) {
+            endpoints.push(url);
+        }
+    }
+    endpoints
+}
+
+/// Splits a comma separated list of Cosmos gRPC urls as provided on the command line
+/// into the primary url and a list of fallback urls
+pub fn split_grpc_urls(input: &str) -> (String, Vec<String>) {
+    let mut urls: Vec<String> = input
+        .split(',')
+        .map(normalize_url)
+        .filter(|v| !v.is_empty())
+        .collect();
+    if urls.is_empty() {
+        return (normalize_url(input), Vec::new());
+    }
+    let primary = urls.remove(0);
+    (primary, urls)
+}
+
+#[test]
+fn test_split_grpc_urls() {
+    let (primary, fallbacks) = split_grpc_urls("http://localhost:9090");
+    assert_eq!(primary, "http://localhost:9090");
+    assert!(fallbacks.is_empty());
+
+    let (primary, fallbacks) =
+        split_grpc_urls("http://localhost:9090, https://node.example.com:9090,");
+    assert_eq!(primary, "http://localhost:9090");
+    assert_eq!(fallbacks, vec!["https://node.example.com:9090".to_string()]);
+
+    let (primary, fallbacks) = split_grpc_urls("http://localhost:9090/,https://node.example.com/");
+    assert_eq!(primary, "http://localhost:9090");
+    assert_eq!(fallbacks, vec!["https://node.example.com".to_string()]);
+    let fallbacks = vec![
+        "http://localhost:9090".to_string(),
+        "https://node.example.com/".to_string(),
+        "https://node.example.com".to_string(),
+    ];
+    assert_eq!(
+        endpoint_list("http://localhost:9090/", &fallbacks),
+        vec![
+            "http://localhost:9090".to_string(),
+            "https://node.example.com".to_string()
+        ]
+    );
+}
@@ -9,7 +9,6 @@ use deep_space::{client::ChainStatus, Coin};
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
 use gravity_proto::gravity::QueryDelegateKeysByEthAddress;
 use gravity_proto::gravity::QueryDelegateKeysByOrchestratorAddress;
-use std::process::exit;
 use std::time::Duration;
 use tokio::time::sleep as delay_for;
 use tonic::transport::Channel;
@@ -18,6 +17,7 @@ use web30::client::Web3;
 
 use crate::get_with_retry::get_balances_with_retry;
 use crate::get_with_retry::get_eth_balances_with_retry;
+use crate::types::GasPrice;
 
 pub struct Connections {
     pub web3: Option<Web3>,
@@ -237,7 +237,7 @@ pub async fn check_delegate_addresses(
     delegate_eth_address: EthAddress,
     delegate_orchestrator_address: CosmosAddress,
     prefix: &str,
-) {
+) -> Result<(), String> {
     let eth_response = client
         .get_delegate_key_by_eth(QueryDelegateKeysByEthAddress {
             eth_address: delegate_eth_address.to_string(),
@@ -253,84 +253,82 @@ pub async fn check_delegate_addresses(
         (Ok(e), Ok(o)) => {
             let e = e.into_inner();
             let o = o.into_inner();
-            let req_delegate_orchestrator_address: CosmosAddress =
-                e.orchestrator_address.parse().unwrap();
-            let req_delegate_eth_address: EthAddress = o.eth_address.parse().unwrap();
+            let req_delegate_orchestrator_address: CosmosAddress = e
+                .orchestrator_address
+                .parse()
+                .map_err(|e| format!("Chain returned an invalid orchestrator address {:?}", e))?;
+            let req_delegate_eth_address: EthAddress = o
+                .eth_address
+                .parse()
+                .map_err(|e| format!("Chain returned an invalid Ethereum address {:?}", e))?;
             if req_delegate_eth_address != delegate_eth_address
                 && req_delegate_orchestrator_address != delegate_orchestrator_address
             {
-                error!("Your Delegate Ethereum and Orchestrator addresses are both incorrect!");
-                error!(
-                    "You provided {}  Correct Value {}",
-                    delegate_eth_address, req_delegate_eth_address
-                );
-                error!(
-                    "You provided {}  Correct Value {}",
-                    delegate_orchestrator_address, req_delegate_orchestrator_address
-                );
-                error!("In order to resolve this issue you should double check your input value or re-register your delegate keys");
-                exit(1);
+                return Err(format!(
+                    "Your Delegate Ethereum and Orchestrator addresses are both incorrect! You provided {} Correct Value {}, you provided {} Correct Value {}. In order to resolve this issue you should double check your input value or re-register your delegate keys",
+                    delegate_eth_address,
+                    req_delegate_eth_address,
+                    delegate_orchestrator_address,
+                    req_delegate_orchestrator_address
+                ));
             } else if req_delegate_eth_address != delegate_eth_address {
-                error!("Your Delegate Ethereum address is incorrect!");
-                error!(
-                    "You provided {}  Correct Value {}",
+                return Err(format!(
+                    "Your Delegate Ethereum address is incorrect! You provided {} Correct Value {}. In order to resolve this issue you should double check how you input your eth private key",
                     delegate_eth_address, req_delegate_eth_address
-                );
-                error!("In order to resolve this issue you should double check how you input your eth private key");
-                exit(1);
+                ));
             } else if req_delegate_orchestrator_address != delegate_orchestrator_address {
-                error!("Your Delegate Orchestrator address is incorrect!");
-                error!(
-                    "You provided {}  Correct Value {}",
-                    delegate_eth_address, req_delegate_eth_address
-                );
-                error!("In order to resolve this issue you should double check how you input your Orchestrator address phrase, make sure you didn't use your Validator phrase!");
-                exit(1);
+                return Err(format!(
+                    "Your Delegate Orchestrator address is incorrect! You provided {} Correct Value {}. In order to resolve this issue you should double check how you input your Orchestrator address phrase, make sure you didn't use your Validator phrase!",
+                    delegate_orchestrator_address, req_delegate_orchestrator_address
+                ));
             }
 
             if e.validator_address != o.validator_address {
-                error!("You are using delegate keys from two different validator addresses!");
-                error!("If you get this error message I would just blow everything away and start again");
-                exit(1);
+                return Err("You are using delegate keys from two different validator addresses! If you get this error message I would just blow everything away and start again".to_string());
             }
+            Ok(())
         }
-        (Err(e), Ok(_)) => {
-            error!("Your delegate Ethereum address is incorrect, please double check you private key. If you can't locate the correct private key register your delegate keys again and use the new value {:?}", e);
-            exit(1);
-        }
-        (Ok(_), Err(e)) => {
-            error!("Your delegate Cosmos address is incorrect, please double check your phrase. If you can't locate the correct phrase register your delegate keys again and use the new value {:?}", e);
-            exit(1);
-        }
+        (Err(e), Ok(_)) => Err(format!("Your delegate Ethereum address is incorrect, please double check you private key. If you can't locate the correct private key register your delegate keys again and use the new value {:?}", e)),
+        (Ok(_), Err(e)) => Err(format!("Your delegate Cosmos address is incorrect, please double check your phrase. If you can't locate the correct phrase register your delegate keys again and use the new value {:?}", e)),
         (Err(_), Err(_)) => {
-            error!("Delegate keys are not set! Please Register your delegate keys");
-            exit(1);
+            Err("Delegate keys are not set! Please Register your delegate keys".to_string())
         }
     }
 }
 
 /// Checks if a given Coin, used for fees is in the provided address in a sufficient quantity
-pub async fn check_for_fee(fee: &Coin, address: CosmosAddress, contact: &Contact) {
+pub async fn check_for_fee(
+    fee: &Coin,
+    address: CosmosAddress,
+    contact: &Contact,
+) -> Result<(), String> {
     let balances = get_balances_with_retry(address, contact).await;
     for balance in balances {
         if balance.denom.contains(&fee.denom) {
             if balance.amount < fee.amount {
-                error!("You have specified a fee that is greater than your balance of that coin! {}{} > {}{} ", fee.amount, fee.denom, balance.amount, balance.denom);
-                exit(1);
+                return Err(format!("You have specified a fee that is greater than your balance of that coin! {}{} > {}{} ", fee.amount, fee.denom, balance.amount, balance.denom));
             } else {
-                return;
+                return Ok(());
             }
         }
     }
-    error!("You have specified that fees should be paid in {} but account {} has no balance of that token!", fee.denom, address);
-    exit(1);
+    Err(format!("You have specified that fees should be paid in {} but account {} has no balance of that token!", fee.denom, address))
+}
+
+/// Checks that the provided address holds the token used to pay fees at the given gas price
+pub async fn check_for_gas_price(
+    gas_price: &GasPrice,
+    address: CosmosAddress,
+    contact: &Contact,
+) -> Result<(), String> {
+    check_for_fee(&gas_price.fee_for_gas(1), address, contact).await
 }
 
 /// Checks the user has some Ethereum in their address to pay for things
-pub async fn check_for_eth(address: EthAddress, web3: &Web3) {
+pub async fn check_for_eth(address: EthAddress, web3: &Web3) -> Result<(), String> {
     let balance = get_eth_balances_with_retry(address, web3).await;
     if balance == 0u8.into() {
-        error!("You don't have any Ethereum! You will need to send some to {} for this program to work. Dust will do for basic operations, more info about average relaying costs will be presented as the program runs", address);
-        exit(1);
+        return Err(format!("You don't have any Ethereum! You will need to send some to {} for this program to work. Dust will do for basic operations, more info about average relaying costs will be presented as the program runs", address));
     }
+    Ok(())
 }
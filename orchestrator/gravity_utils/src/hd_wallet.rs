@@ -0,0 +1,169 @@
+//! BIP32 hierarchical deterministic key derivation from BIP39 mnemonic phrases. Cosmos keys
+//! default to the m/44'/118'/0'/0/0 path that deep_space uses, but chains may use another
+//! coin type. Ethereum keys use the standard m/44'/60'/0'/0/n path so that the delegate
+//! Ethereum key can be recovered from the same phrase as the Cosmos key
+
+use clarity::PrivateKey as EthPrivateKey;
+use deep_space::mnemonic::Mnemonic;
+use deep_space::PrivateKey as CosmosPrivateKey;
+use hmac::{Hmac, Mac, NewMac};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use sha2::Sha512;
+use std::str::FromStr;
+
+/// The coin type used by deep_space and most Cosmos chains
+pub const DEFAULT_COSMOS_COIN_TYPE: u32 = 118;
+/// The coin type used by Ethereum wallets
+pub const ETHEREUM_COIN_TYPE: u32 = 60;
+
+/// Indexes at or above this value are hardened
+const HARDENED: u32 = 0x8000_0000;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// The standard path of the first account of the given coin type
+pub fn default_hd_path(coin_type: u32) -> String {
+    format!("m/44'/{}'/0'/0/0", coin_type)
+}
+
+/// The standard path of the nth Ethereum key, as used by Metamask and hardware wallets
+pub fn ethereum_hd_path(index: u32) -> String {
+    format!("m/44'/{}'/0'/0/{}", ETHEREUM_COIN_TYPE, index)
+}
+
+/// Parses a path such as m/44'/118'/0'/0/0, hardened indexes are marked with ' or h
+pub fn parse_hd_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut parts = path.trim().split('/');
+    if parts.next() != Some("m") {
+        return Err(format!("HD path {} must start with m/", path));
+    }
+    parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'').or_else(|| part.strip_suffix('h'))
+            {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            match index.parse::<u32>() {
+                Ok(i) if i < HARDENED => Ok(if hardened { i + HARDENED } else { i }),
+                _ => Err(format!("Invalid index {} in HD path {}", part, path)),
+            }
+        })
+        .collect()
+}
+
+/// Derives the secret key at the given path from a mnemonic phrase
+pub fn derive_secret(phrase: &str, passphrase: &str, path: &str) -> Result<[u8; 32], String> {
+    let mnemonic =
+        Mnemonic::from_str(phrase).map_err(|e| format!("Invalid mnemonic phrase {:?}", e))?;
+    let seed = mnemonic.to_seed(passphrase);
+    derive_secret_from_seed(&seed, &parse_hd_path(path)?)
+}
+
+/// BIP32 private key derivation from a seed
+pub fn derive_secret_from_seed(seed: &[u8], path: &[u32]) -> Result<[u8; 32], String> {
+    let secp = Secp256k1::new();
+    let (mut key, mut chain_code) = hmac_split(b"Bitcoin seed", &[seed]);
+    let mut secret = SecretKey::from_slice(&key).map_err(|e| format!("{:?}", e))?;
+    for index in path {
+        let index_bytes = index.to_be_bytes();
+        let (tweak, next_chain_code) = if *index >= HARDENED {
+            hmac_split(&chain_code, &[&[0u8], &secret[..], &index_bytes])
+        } else {
+            let public = PublicKey::from_secret_key(&secp, &secret).serialize();
+            hmac_split(&chain_code, &[&public, &index_bytes])
+        };
+        // fails for the vanishingly rare indexes with no valid key, as BIP32 specifies
+        secret
+            .add_assign(&tweak)
+            .map_err(|e| format!("Invalid child key at index {} {:?}", index, e))?;
+        key.copy_from_slice(&secret[..]);
+        chain_code = next_chain_code;
+    }
+    Ok(key)
+}
+
+fn hmac_split(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts any key length");
+    for d in data {
+        mac.update(d);
+    }
+    let result = mac.finalize().into_bytes();
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&result[..32]);
+    right.copy_from_slice(&result[32..]);
+    (left, right)
+}
+
+/// Derives a Cosmos key from a mnemonic phrase at the given path
+pub fn cosmos_key_from_phrase(phrase: &str, path: &str) -> Result<CosmosPrivateKey, String> {
+    let secret = derive_secret(phrase, "", path)?;
+    Ok(CosmosPrivateKey::from_secret(&secret))
+}
+
+/// Derives the Ethereum key at m/44'/60'/0'/0/index from a mnemonic phrase
+pub fn ethereum_key_from_phrase(phrase: &str, index: u32) -> Result<EthPrivateKey, String> {
+    let secret = derive_secret(phrase, "", &ethereum_hd_path(index))?;
+    EthPrivateKey::from_slice(&secret).map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity::utils::bytes_to_hex_str;
+
+    const TEST_PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_parse_hd_path() {
+        assert_eq!(
+            parse_hd_path("m/44'/60'/0'/0/3").unwrap(),
+            vec![44 + HARDENED, 60 + HARDENED, HARDENED, 0, 3]
+        );
+        assert_eq!(parse_hd_path("m/1h/2").unwrap(), vec![1 + HARDENED, 2]);
+        assert!(parse_hd_path("44'/60'").is_err());
+        assert!(parse_hd_path("m/abc").is_err());
+        assert!(parse_hd_path("m/2147483648").is_err());
+    }
+
+    /// BIP32 test vector 1
+    #[test]
+    fn test_bip32_vector() {
+        let seed = [0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+        let master = derive_secret_from_seed(&seed, &[]).unwrap();
+        assert_eq!(
+            bytes_to_hex_str(&master),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+        );
+        let path = parse_hd_path("m/0'/1/2'/2/1000000000").unwrap();
+        let child = derive_secret_from_seed(&seed, &path).unwrap();
+        assert_eq!(
+            bytes_to_hex_str(&child),
+            "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8"
+        );
+    }
+
+    #[test]
+    fn test_ethereum_key_from_phrase() {
+        let key = ethereum_key_from_phrase(TEST_PHRASE, 0).unwrap();
+        assert_eq!(
+            key.to_public_key().unwrap(),
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+                .parse()
+                .unwrap()
+        );
+        assert!(ethereum_key_from_phrase("not a phrase", 0).is_err());
+    }
+
+    #[test]
+    fn test_cosmos_key_matches_deep_space() {
+        let ours = cosmos_key_from_phrase(TEST_PHRASE, &default_hd_path(DEFAULT_COSMOS_COIN_TYPE))
+            .unwrap();
+        let theirs = CosmosPrivateKey::from_phrase(TEST_PHRASE, "").unwrap();
+        assert_eq!(
+            ours.to_address("cosmos").unwrap(),
+            theirs.to_address("cosmos").unwrap()
+        );
+    }
+}
@@ -5,7 +5,10 @@ extern crate serde_derive;
 #[macro_use]
 extern crate log;
 
+pub mod connection_pool;
 pub mod connection_prep;
 pub mod error;
 pub mod get_with_retry;
+pub mod hd_wallet;
+pub mod num_conversion;
 pub mod types;
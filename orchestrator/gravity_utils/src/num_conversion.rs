@@ -0,0 +1,98 @@
+//! Exact conversions between decimal amounts as written by users and integer base units
+
+use num256::Uint256;
+
+/// Parses a decimal amount such as 1.5 into the base units of a token with the given number
+/// of decimals. The conversion is exact, amounts with more decimal places than the token
+/// supports are rejected rather than rounded
+pub fn parse_amount(input: &str, decimals: u8) -> Result<Uint256, String> {
+    let input = input.trim();
+    let mut parts = input.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next().unwrap_or("");
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(format!("{} is not an amount", input));
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("{} is not an amount", input));
+    }
+    // trailing zeros do not change the value
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "{} has more than the {} decimal places this token supports",
+            input, decimals
+        ));
+    }
+    let digits = format!(
+        "{}{}{}",
+        whole,
+        fraction,
+        "0".repeat(decimals as usize - fraction.len())
+    );
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0u8.into());
+    }
+    digits
+        .parse()
+        .map_err(|e| format!("{} is not an amount {:?}", input, e))
+}
+
+/// Formats base units of a token with the given number of decimals as a decimal amount,
+/// trailing zeros are removed
+pub fn format_amount(amount: &Uint256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+#[test]
+fn test_parse_amount() {
+    let one_eth: Uint256 = 1000000000000000000u128.into();
+    assert_eq!(parse_amount("1", 18).unwrap(), one_eth);
+    assert_eq!(parse_amount("1.0", 18).unwrap(), one_eth);
+    assert_eq!(
+        parse_amount("1.15", 18).unwrap(),
+        1150000000000000000u128.into()
+    );
+    assert_eq!(parse_amount(".5", 6).unwrap(), 500000u64.into());
+    assert_eq!(parse_amount("0", 6).unwrap(), 0u8.into());
+    // precision well beyond what an f64 can hold
+    assert_eq!(
+        parse_amount("123456789012345678.123456789012345678", 18).unwrap(),
+        "123456789012345678123456789012345678".parse().unwrap()
+    );
+    assert!(parse_amount("1.0000001", 6).is_err());
+    assert!(parse_amount("1.5eth", 18).is_err());
+    assert!(parse_amount("-1", 18).is_err());
+    assert!(parse_amount("", 18).is_err());
+}
+
+#[test]
+fn test_format_amount() {
+    assert_eq!(format_amount(&1500000u64.into(), 6), "1.5");
+    assert_eq!(format_amount(&1000000u64.into(), 6), "1");
+    assert_eq!(format_amount(&1u8.into(), 6), "0.000001");
+    assert_eq!(format_amount(&0u8.into(), 6), "0");
+    assert_eq!(format_amount(&42u8.into(), 0), "42");
+    let amount: Uint256 = "123456789012345678123456789012345678".parse().unwrap();
+    assert_eq!(
+        format_amount(&amount, 18),
+        "123456789012345678.123456789012345678"
+    );
+}
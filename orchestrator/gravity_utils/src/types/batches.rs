@@ -39,6 +39,8 @@ pub struct TransactionBatch {
     /// the ERC20 token contract shared by all transactions
     /// and fees in this batch
     pub token_contract: EthAddress,
+    /// the Cosmos block height at which this batch was created
+    pub block: u64,
 }
 
 impl TransactionBatch {
@@ -108,6 +110,7 @@ impl TryFrom<gravity_proto::gravity::OutgoingTxBatch> for TransactionBatch {
                 transactions,
                 token_contract: total_fee.token_contract_address,
                 total_fee,
+                block: input.block,
             })
         } else {
             Err(GravityError::InvalidBridgeStateError(
@@ -181,7 +184,7 @@ impl Into<gravity_proto::gravity::OutgoingTxBatch> for &TransactionBatch {
             batch_timeout: self.batch_timeout,
             transactions: self.transactions.iter().map(|v| v.into()).collect(),
             token_contract: self.token_contract.to_string(),
-            block: 0,
+            block: self.block,
         }
     }
 }
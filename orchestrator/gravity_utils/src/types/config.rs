@@ -1,12 +1,108 @@
 //! contains configuration structs that need to be accessed across crates.
 
+use super::{FeeSettings, GasPrice};
+use crate::hd_wallet::{default_hd_path, DEFAULT_COSMOS_COIN_TYPE};
+use std::collections::HashMap;
+
 /// Global configuration struct for Gravity bridge tools
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
 pub struct GravityBridgeToolsConfig {
     #[serde(default = "RelayerConfig::default")]
     pub relayer: RelayerConfig,
     #[serde(default = "OrchestratorConfig::default")]
     pub orchestrator: OrchestratorConfig,
+    #[serde(default = "FeeConfig::default")]
+    pub fees: FeeConfig,
+    #[serde(default = "SigningPolicyConfig::default")]
+    pub signing_policy: SigningPolicyConfig,
+    #[serde(default = "ConnectionConfig::default")]
+    pub connection: ConnectionConfig,
+    #[serde(default = "KeysConfig::default")]
+    pub keys: KeysConfig,
+}
+
+/// Connection options shared by the Orchestrator and Relayer, every value may be overridden
+/// by an environment variable, which may in turn be overridden on the command line
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ConnectionConfig {
+    /// The Cosmos gRPC server, a comma separated list of servers may be
+    /// provided for failover
+    #[serde(default = "default_cosmos_grpc")]
+    pub cosmos_grpc: String,
+    #[serde(default = "default_ethereum_rpc")]
+    pub ethereum_rpc: String,
+    /// The address prefix of the Cosmos chain
+    #[serde(default = "default_address_prefix")]
+    pub address_prefix: String,
+    /// The address of the Gravity contract on Ethereum, if not set it is
+    /// read from the Gravity module params
+    #[serde(default)]
+    pub gravity_contract_address: Option<String>,
+}
+
+fn default_cosmos_grpc() -> String {
+    "http://localhost:9090".to_string()
+}
+
+fn default_ethereum_rpc() -> String {
+    "http://localhost:8545".to_string()
+}
+
+fn default_address_prefix() -> String {
+    "cosmos".to_string()
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        ConnectionConfig {
+            cosmos_grpc: default_cosmos_grpc(),
+            ethereum_rpc: default_ethereum_rpc(),
+            address_prefix: default_address_prefix(),
+            gravity_contract_address: None,
+        }
+    }
+}
+
+/// Delegate keys provided through the config, these take precedence over the keys
+/// stored by the `gbt keys` commands
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct KeysConfig {
+    /// The Orchestrator's Cosmos mnemonic phrase
+    #[serde(default)]
+    pub cosmos_phrase: Option<String>,
+    /// The Ethereum private key used to sign and to pay relaying fees
+    #[serde(default)]
+    pub ethereum_key: Option<String>,
+    /// The BIP44 coin type used to derive the Orchestrator's Cosmos key from it's phrase
+    #[serde(default = "default_cosmos_coin_type")]
+    pub cosmos_coin_type: u32,
+    /// The full HD path of the Orchestrator's Cosmos key, overrides cosmos_coin_type
+    #[serde(default)]
+    pub cosmos_hd_path: Option<String>,
+}
+
+fn default_cosmos_coin_type() -> u32 {
+    DEFAULT_COSMOS_COIN_TYPE
+}
+
+impl Default for KeysConfig {
+    fn default() -> Self {
+        KeysConfig {
+            cosmos_phrase: None,
+            ethereum_key: None,
+            cosmos_coin_type: default_cosmos_coin_type(),
+            cosmos_hd_path: None,
+        }
+    }
+}
+
+impl KeysConfig {
+    /// The HD path used to derive the Orchestrator's Cosmos key
+    pub fn cosmos_hd_path(&self) -> String {
+        self.cosmos_hd_path
+            .clone()
+            .unwrap_or_else(|| default_hd_path(self.cosmos_coin_type))
+    }
 }
 
 /// Relayer configuration options
@@ -51,16 +147,145 @@ pub struct OrchestratorConfig {
     /// If this Orchestrator should run an integrated relayer or not
     #[serde(default = "default_relayer_enabled")]
     pub relayer_enabled: bool,
+    /// If confirms and claims that are ready at the same time should be merged
+    /// into a single Cosmos transaction
+    #[serde(default = "default_merge_cosmos_messages")]
+    pub merge_cosmos_messages: bool,
+    /// The Orchestrator warns when anything it has not signed is this many blocks
+    /// away from the slashing window closing, once for each threshold
+    #[serde(default = "default_slashing_warning_thresholds")]
+    pub slashing_warning_thresholds: Vec<u64>,
 }
 
 fn default_relayer_enabled() -> bool {
     true
 }
 
+fn default_merge_cosmos_messages() -> bool {
+    false
+}
+
+fn default_slashing_warning_thresholds() -> Vec<u64> {
+    vec![1000, 100]
+}
+
 impl Default for OrchestratorConfig {
     fn default() -> Self {
         OrchestratorConfig {
             relayer_enabled: default_relayer_enabled(),
+            merge_cosmos_messages: default_merge_cosmos_messages(),
+            slashing_warning_thresholds: default_slashing_warning_thresholds(),
+        }
+    }
+}
+
+/// Cosmos transaction fee configuration options
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FeeConfig {
+    /// The gas price used to compute Cosmos transaction fees, may be
+    /// overridden on the command line
+    #[serde(default)]
+    pub gas_price: Option<GasPrice>,
+    /// Every transaction is simulated before it is sent, the simulated gas
+    /// usage is multiplied by this value to produce the gas limit
+    #[serde(default = "default_gas_adjustment")]
+    pub gas_adjustment: f64,
+    /// If the node reports that our fees are insufficient the Orchestrator raises the
+    /// gas price to the minimum the node requires, up to this value
+    #[serde(default)]
+    pub max_gas_price: Option<GasPrice>,
+}
+
+fn default_gas_adjustment() -> f64 {
+    1.3
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        FeeConfig {
+            gas_price: None,
+            gas_adjustment: default_gas_adjustment(),
+            max_gas_price: None,
+        }
+    }
+}
+
+impl FeeConfig {
+    /// Produces the fee settings for sending transactions, a gas price provided on the
+    /// command line takes precedence over the configured one
+    pub fn get_fee_settings(&self, gas_price: Option<GasPrice>) -> Result<FeeSettings, String> {
+        let gas_price = match gas_price.or_else(|| self.gas_price.clone()) {
+            Some(v) => v,
+            None => return Err("No gas price has been provided".to_string()),
+        };
+        if let Some(max_gas_price) = &self.max_gas_price {
+            if max_gas_price.denom != gas_price.denom {
+                return Err(format!(
+                    "max_gas_price {} must be in the same denom as the gas price {}",
+                    max_gas_price, gas_price
+                ));
+            }
+        }
+        Ok(FeeSettings {
+            gas_price,
+            gas_adjustment: self.gas_adjustment,
+            max_gas_price: self.max_gas_price.clone(),
+            fixed_fee: None,
+        })
+    }
+}
+
+/// Local signing policy options, when enabled every batch and logic call is checked
+/// against these rules before the Orchestrator signs it. Anything that fails a check
+/// is held until the operator approves it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SigningPolicyConfig {
+    /// If the signing policy should be applied at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// A second Cosmos gRPC node, every batch and logic call is fetched again from this
+    /// node and must produce the same checkpoint as the one we are about to sign. This
+    /// should be a node operated independently of the one the Orchestrator uses
+    #[serde(default)]
+    pub verification_grpc: Option<String>,
+    /// The maximum total amount of an ERC20, by contract address, that a single
+    /// batch or logic call may send out of the bridge, fees included
+    #[serde(default)]
+    pub max_amounts: HashMap<String, String>,
+    /// Logic contracts that logic calls may target, calls to any other
+    /// contract are held for approval
+    #[serde(default)]
+    pub allowed_logic_contracts: Vec<String>,
+}
+
+impl Default for SigningPolicyConfig {
+    fn default() -> Self {
+        SigningPolicyConfig {
+            enabled: false,
+            verification_grpc: None,
+            max_amounts: HashMap::new(),
+            allowed_logic_contracts: Vec::new(),
         }
     }
 }
+
+#[test]
+fn test_get_fee_settings() {
+    let config = FeeConfig {
+        gas_price: Some("0.01ugraviton".parse().unwrap()),
+        ..Default::default()
+    };
+    let fee = config.get_fee_settings(None).unwrap();
+    assert_eq!(fee.gas_price, "0.01ugraviton".parse().unwrap());
+    let fee = config
+        .get_fee_settings(Some("0.5ugraviton".parse().unwrap()))
+        .unwrap();
+    assert_eq!(fee.gas_price, "0.5ugraviton".parse().unwrap());
+
+    assert!(FeeConfig::default().get_fee_settings(None).is_err());
+    let config = FeeConfig {
+        max_gas_price: Some("1footoken".parse().unwrap()),
+        ..config
+    };
+    assert!(config.get_fee_settings(None).is_err());
+}
@@ -0,0 +1,155 @@
+use crate::num_conversion::{format_amount, parse_amount};
+use deep_space::Coin;
+use num256::Uint256;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Gas prices are kept with the 18 decimal places of precision the Cosmos SDK uses for decimals
+pub const GAS_PRICE_DECIMALS: u8 = 18;
+
+/// A Cosmos gas price, written as a decimal amount directly followed by
+/// a denom, eg: 0.025ugraviton
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct GasPrice {
+    /// The price of one unit of gas in base units of the denom, scaled by 10^GAS_PRICE_DECIMALS
+    pub amount: Uint256,
+    pub denom: String,
+}
+
+impl GasPrice {
+    /// The fee for a transaction with the given gas limit at this price, rounded up
+    pub fn fee_for_gas(&self, gas_limit: u64) -> Coin {
+        Coin {
+            denom: self.denom.clone(),
+            amount: div_ceil(Uint256::from(gas_limit) * self.amount.clone(), scale()),
+        }
+    }
+
+    /// The lowest gas price at which a transaction with the given gas limit pays at least `fee`
+    pub fn for_fee(fee: &Coin, gas_limit: u64) -> GasPrice {
+        GasPrice {
+            amount: div_ceil(
+                fee.amount.clone() * scale(),
+                Uint256::from(gas_limit.max(1)),
+            ),
+            denom: fee.denom.clone(),
+        }
+    }
+}
+
+fn scale() -> Uint256 {
+    let mut scale = Uint256::from(1u8);
+    for _ in 0..GAS_PRICE_DECIMALS {
+        scale = scale * Uint256::from(10u8);
+    }
+    scale
+}
+
+fn div_ceil(numerator: Uint256, denominator: Uint256) -> Uint256 {
+    (numerator + denominator.clone() - Uint256::from(1u8)) / denominator
+}
+
+impl FromStr for GasPrice {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .ok_or_else(|| format!("{} has no denom, use the format 0.025ugraviton", s))?;
+        let (amount, denom) = s.split_at(split);
+        let amount = parse_amount(amount, GAS_PRICE_DECIMALS)
+            .map_err(|e| format!("Invalid gas price amount {}", e))?;
+        Ok(GasPrice {
+            amount,
+            denom: denom.to_string(),
+        })
+    }
+}
+
+impl TryFrom<String> for GasPrice {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<GasPrice> for String {
+    fn from(value: GasPrice) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for GasPrice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            format_amount(&self.amount, GAS_PRICE_DECIMALS),
+            self.denom
+        )
+    }
+}
+
+/// The settings used to determine the gas limit and fee of a Cosmos transaction. Transactions
+/// are simulated, the simulated gas usage is multiplied by the gas adjustment to produce the
+/// gas limit and the fee is the gas limit multiplied by the gas price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSettings {
+    pub gas_price: GasPrice,
+    pub gas_adjustment: f64,
+    /// The highest gas price the Orchestrator may automatically raise the gas price
+    /// to when the node reports insufficient fees, None disables adjustment
+    pub max_gas_price: Option<GasPrice>,
+    /// A fixed fee paid by every transaction in place of the gas limit times the gas price,
+    /// only set by the deprecated --fees flag
+    pub fixed_fee: Option<Coin>,
+}
+
+impl FeeSettings {
+    /// The fee paid by a transaction with the given gas limit
+    pub fn fee_for_gas(&self, gas_limit: u64) -> Coin {
+        match &self.fixed_fee {
+            Some(fee) => fee.clone(),
+            None => self.gas_price.fee_for_gas(gas_limit),
+        }
+    }
+
+    /// The gas limit to use for a transaction that used the given amount of gas in simulation
+    pub fn gas_limit(&self, simulated_gas: u64) -> u64 {
+        (simulated_gas as f64 * self.gas_adjustment).ceil() as u64
+    }
+}
+
+#[test]
+fn test_gas_price_parse() {
+    let price: GasPrice = "0.025ugraviton".parse().unwrap();
+    assert_eq!(price.denom, "ugraviton");
+    assert_eq!(price.to_string(), "0.025ugraviton");
+    assert_eq!(price.fee_for_gas(200_000).amount, 5000u64.into());
+
+    let price: GasPrice = "1footoken".parse().unwrap();
+    assert_eq!(price.fee_for_gas(3).amount, 3u64.into());
+
+    // 0.1 has no exact f64 representation, 0.1 * 30 rounded up as a float is 4
+    let price: GasPrice = "0.1ugraviton".parse().unwrap();
+    assert_eq!(price.fee_for_gas(30).amount, 3u64.into());
+    assert_eq!(price.fee_for_gas(31).amount, 4u64.into());
+    let price: GasPrice = "0.000000000000000001ugraviton".parse().unwrap();
+    assert_eq!(price.fee_for_gas(1).amount, 1u64.into());
+
+    let min_fee = Coin {
+        denom: "ugraviton".to_string(),
+        amount: 5001u64.into(),
+    };
+    let price = GasPrice::for_fee(&min_fee, 200_000);
+    assert_eq!(price.to_string(), "0.025005ugraviton");
+    assert_eq!(price.fee_for_gas(200_000).amount, 5001u64.into());
+
+    assert!("0.025".parse::<GasPrice>().is_err());
+    assert!("ugraviton".parse::<GasPrice>().is_err());
+    assert!("0.0000000000000000001ugraviton"
+        .parse::<GasPrice>()
+        .is_err());
+}
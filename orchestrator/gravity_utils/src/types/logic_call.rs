@@ -17,6 +17,8 @@ pub struct LogicCall {
     pub timeout: u64,
     pub invalidation_id: Vec<u8>,
     pub invalidation_nonce: u64,
+    /// the Cosmos block height at which this logic call was created
+    pub block: u64,
 }
 
 /// the response we get when querying for a logic call confirmation
@@ -80,6 +82,7 @@ impl TryFrom<gravity_proto::gravity::OutgoingLogicCall> for LogicCall {
             timeout: input.timeout,
             invalidation_id: input.invalidation_id,
             invalidation_nonce: input.invalidation_nonce,
+            block: input.block,
         })
     }
 }
@@ -95,9 +98,7 @@ impl Into<gravity_proto::gravity::OutgoingLogicCall> for &LogicCall {
             timeout: self.timeout,
             invalidation_id: self.invalidation_id.clone(),
             invalidation_nonce: self.invalidation_nonce,
-            // note the dummy value, this is not used in signatures
-            // so it's not required for our evidence based slashing implementation
-            block: 0,
+            block: self.block,
         }
     }
 }
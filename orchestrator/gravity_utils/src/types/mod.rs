@@ -5,6 +5,7 @@ mod batches;
 mod config;
 mod ethereum_events;
 pub mod event_signatures;
+mod fees;
 mod logic_call;
 mod signatures;
 mod valsets;
@@ -13,6 +14,7 @@ use crate::error::GravityError;
 pub use batches::*;
 pub use config::*;
 pub use ethereum_events::*;
+pub use fees::*;
 pub use logic_call::*;
 pub use signatures::*;
 pub use valsets::*;
@@ -2,9 +2,10 @@
 //! or a transaction batch update. It then responds to these events by performing actions on the Cosmos chain if required
 
 use clarity::{utils::bytes_to_hex_str, Address as EthAddress, Uint256};
-use cosmos_gravity::{query::get_last_event_nonce_for_validator, send::send_ethereum_claims};
+use cosmos_gravity::broadcast::TxBroadcaster;
+use cosmos_gravity::send::TIMEOUT;
+use cosmos_gravity::{query::get_last_event_nonce_for_validator, send::build_ethereum_claim_msgs};
 use deep_space::Contact;
-use deep_space::{coin::Coin, private_key::PrivateKey as CosmosPrivateKey};
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
 use gravity_utils::get_with_retry::get_block_number_with_retry;
 use gravity_utils::get_with_retry::get_net_version_with_retry;
@@ -25,11 +26,10 @@ pub async fn check_for_events(
     contact: &Contact,
     grpc_client: &mut GravityQueryClient<Channel>,
     gravity_contract_address: EthAddress,
-    our_private_key: CosmosPrivateKey,
-    fee: Coin,
+    broadcaster: &TxBroadcaster,
     starting_block: Uint256,
 ) -> Result<Uint256, GravityError> {
-    let our_cosmos_address = our_private_key.to_address(&contact.get_prefix()).unwrap();
+    let our_cosmos_address = broadcaster.get_address(&contact.get_prefix());
     let latest_block = get_block_number_with_retry(web3).await;
     let latest_block = latest_block - get_block_delay(web3).await;
 
@@ -160,17 +160,18 @@ pub async fn check_for_events(
             || !logic_calls.is_empty()
             || !valsets.is_empty()
         {
-            let res = send_ethereum_claims(
-                contact,
-                our_private_key,
+            let msgs = build_ethereum_claim_msgs(
+                our_cosmos_address,
+                contact.get_prefix(),
                 deposits,
                 withdraws,
                 erc20_deploys,
                 logic_calls,
                 valsets,
-                fee,
-            )
-            .await?;
+            );
+            let res = broadcaster
+                .send_messages(contact, msgs, Some(TIMEOUT))
+                .await?;
             let new_event_nonce = get_last_event_nonce_for_validator(
                 grpc_client,
                 our_cosmos_address,
@@ -1,6 +1,11 @@
 #[macro_use]
 extern crate log;
+#[macro_use]
+extern crate serde_derive;
 
 pub mod ethereum_event_watcher;
 pub mod main_loop;
 pub mod oracle_resync;
+pub mod signing_journal;
+pub mod signing_policy;
+pub mod slashing_window;
@@ -2,32 +2,37 @@
 //! that can only be run by a validator. This single binary the 'Orchestrator' runs not only these two rules but also the untrusted role of a relayer, that does not need any permissions and has it's
 //! own crate and binary so that anyone may run it.
 
+use crate::signing_journal::{JournalEntry, SigningJournal};
+use crate::signing_policy::SigningPolicy;
+use crate::slashing_window::SlashingWindowMonitor;
 use crate::{ethereum_event_watcher::check_for_events, oracle_resync::get_last_checked_block};
 use clarity::{address::Address as EthAddress, Uint256};
 use clarity::{utils::bytes_to_hex_str, PrivateKey as EthPrivateKey};
 use cosmos_gravity::{
+    broadcast::TxBroadcaster,
     query::{
-        get_oldest_unsigned_logic_call, get_oldest_unsigned_transaction_batch,
-        get_oldest_unsigned_valsets,
+        get_all_unsigned_logic_calls, get_all_unsigned_transaction_batches,
+        get_oldest_unsigned_valsets_with_heights,
+    },
+    send::{
+        build_batch_confirm_msgs, build_logic_call_confirm_msgs, build_valset_confirm_msgs, TIMEOUT,
     },
-    send::{send_batch_confirm, send_logic_call_confirm, send_valset_confirms},
 };
 use deep_space::error::CosmosGrpcError;
-use deep_space::Contact;
 use deep_space::{client::ChainStatus, utils::FeeInfo};
 use deep_space::{coin::Coin, private_key::PrivateKey as CosmosPrivateKey};
+use deep_space::{Contact, Msg};
 use ethereum_gravity::utils::get_gravity_id;
 use futures::future::join;
 use futures::future::join3;
 use gravity_proto::cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
-use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
-use gravity_utils::types::GravityBridgeToolsConfig;
+use gravity_utils::connection_pool::CosmosConnectionPool;
+use gravity_utils::types::{FeeSettings, GravityBridgeToolsConfig, Valset};
 use relayer::main_loop::relayer_main_loop;
 use std::process::exit;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::time::sleep as delay_for;
-use tonic::transport::Channel;
 use web30::client::Web3;
 
 /// The execution speed governing all loops in this file
@@ -41,40 +46,48 @@ pub const ETH_ORACLE_LOOP_SPEED: Duration = Duration::from_secs(13);
 /// meaning they will occupy the same thread, but since they do
 /// very little actual cpu bound work and spend the vast majority
 /// of all execution time sleeping this shouldn't be an issue at all.
+/// All three loops share the same Cosmos connection pool, so a failover
+/// triggered by any one of them is picked up by the others. The Oracle and
+/// Signer also share a single transaction broadcaster so that they do not
+/// collide on the account sequence of the Orchestrator key.
+/// If a signing policy is provided batches and logic calls are checked against it before
+/// they are signed, see `signing_policy`. If a signing journal is provided every
+/// Ethereum signature is recorded in it, see `signing_journal`.
 #[allow(clippy::too_many_arguments)]
 pub async fn orchestrator_main_loop(
     cosmos_key: CosmosPrivateKey,
     ethereum_key: EthPrivateKey,
     web3: Web3,
-    contact: Contact,
-    grpc_client: GravityQueryClient<Channel>,
+    cosmos: CosmosConnectionPool,
     gravity_contract_address: EthAddress,
-    user_fee_amount: Coin,
+    fee: FeeSettings,
     config: GravityBridgeToolsConfig,
+    signing_policy: Option<SigningPolicy>,
+    signing_journal: Option<SigningJournal>,
 ) {
-    let fee = user_fee_amount;
+    let broadcaster =
+        TxBroadcaster::new(cosmos_key, fee, config.orchestrator.merge_cosmos_messages);
 
     let a = eth_oracle_main_loop(
-        cosmos_key,
+        broadcaster.clone(),
         web3.clone(),
-        contact.clone(),
-        grpc_client.clone(),
+        cosmos.clone(),
         gravity_contract_address,
-        fee.clone(),
     );
     let b = eth_signer_main_loop(
-        cosmos_key,
+        broadcaster,
         ethereum_key,
         web3.clone(),
-        contact.clone(),
-        grpc_client.clone(),
+        cosmos.clone(),
         gravity_contract_address,
-        fee.clone(),
+        signing_policy,
+        signing_journal,
+        config.orchestrator.slashing_warning_thresholds.clone(),
     );
     let c = relayer_main_loop(
         ethereum_key,
         web3,
-        grpc_client.clone(),
+        cosmos,
         gravity_contract_address,
         &config.relayer,
     );
@@ -92,28 +105,29 @@ const DELAY: Duration = Duration::from_secs(5);
 /// This function is responsible for making sure that Ethereum events are retrieved from the Ethereum blockchain
 /// and ferried over to Cosmos where they will be used to issue tokens or process batches.
 pub async fn eth_oracle_main_loop(
-    cosmos_key: CosmosPrivateKey,
+    broadcaster: TxBroadcaster,
     web3: Web3,
-    contact: Contact,
-    grpc_client: GravityQueryClient<Channel>,
+    cosmos: CosmosConnectionPool,
     gravity_contract_address: EthAddress,
-    fee: Coin,
 ) {
-    let our_cosmos_address = cosmos_key.to_address(&contact.get_prefix()).unwrap();
+    let prefix = cosmos.get_contact().get_prefix();
+    let our_cosmos_address = broadcaster.get_address(&prefix);
     let long_timeout_web30 = Web3::new(&web3.get_url(), Duration::from_secs(120));
     let mut last_checked_block: Uint256 = get_last_checked_block(
-        grpc_client.clone(),
+        cosmos.get_grpc(),
         our_cosmos_address,
-        contact.get_prefix(),
+        prefix,
         gravity_contract_address,
         &long_timeout_web30,
     )
     .await;
     info!("Oracle resync complete, Oracle now operational");
-    let mut grpc_client = grpc_client;
 
     loop {
         let loop_start = Instant::now();
+        let endpoint = cosmos.get_endpoint();
+        let contact = cosmos.get_contact();
+        let mut grpc_client = cosmos.get_grpc();
 
         let latest_eth_block = web3.eth_block_number().await;
         let latest_cosmos_block = contact.get_chain_status().await;
@@ -127,6 +141,7 @@ pub async fn eth_oracle_main_loop(
             }
             (Ok(_latest_eth_block), Ok(ChainStatus::Syncing)) => {
                 warn!("Cosmos node syncing, Eth oracle paused");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
@@ -137,6 +152,7 @@ pub async fn eth_oracle_main_loop(
             }
             (Ok(_), Err(_)) => {
                 warn!("Could not contact Cosmos grpc, trying again");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
@@ -147,6 +163,7 @@ pub async fn eth_oracle_main_loop(
             }
             (Err(_), Err(_)) => {
                 error!("Could not reach Ethereum or Cosmos rpc!");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
@@ -158,8 +175,7 @@ pub async fn eth_oracle_main_loop(
             &contact,
             &mut grpc_client,
             gravity_contract_address,
-            cosmos_key,
-            fee.clone(),
+            &broadcaster,
             last_checked_block.clone(),
         )
         .await
@@ -183,19 +199,22 @@ pub async fn eth_oracle_main_loop(
 
 /// The eth_signer simply signs off on any batches or validator sets provided by the validator
 /// since these are provided directly by a trusted Cosmsos node they can simply be assumed to be
-/// valid and signed off on.
+/// valid and signed off on, unless a local signing policy is configured in which case batches
+/// and logic calls must pass it first.
+#[allow(clippy::too_many_arguments)]
 pub async fn eth_signer_main_loop(
-    cosmos_key: CosmosPrivateKey,
+    broadcaster: TxBroadcaster,
     ethereum_key: EthPrivateKey,
     web3: Web3,
-    contact: Contact,
-    grpc_client: GravityQueryClient<Channel>,
+    cosmos: CosmosConnectionPool,
     gravity_contract_address: EthAddress,
-    fee: Coin,
+    signing_policy: Option<SigningPolicy>,
+    signing_journal: Option<SigningJournal>,
+    slashing_warning_thresholds: Vec<u64>,
 ) {
-    let our_cosmos_address = cosmos_key.to_address(&contact.get_prefix()).unwrap();
+    let mut slashing_monitor = SlashingWindowMonitor::new(slashing_warning_thresholds);
+    let our_cosmos_address = broadcaster.get_address(&cosmos.get_contact().get_prefix());
     let our_ethereum_address = ethereum_key.to_public_key().unwrap();
-    let mut grpc_client = grpc_client;
     let gravity_id = get_gravity_id(gravity_contract_address, our_ethereum_address, &web3).await;
     if gravity_id.is_err() {
         error!("Failed to get GravityID, check your Eth node");
@@ -205,19 +224,24 @@ pub async fn eth_signer_main_loop(
 
     loop {
         let loop_start = Instant::now();
+        let endpoint = cosmos.get_endpoint();
+        let contact = cosmos.get_contact();
+        let mut grpc_client = cosmos.get_grpc();
 
         let latest_eth_block = web3.eth_block_number().await;
         let latest_cosmos_block = contact.get_chain_status().await;
-        match (latest_eth_block, latest_cosmos_block) {
+        let cosmos_block_height = match (latest_eth_block, latest_cosmos_block) {
             (Ok(latest_eth_block), Ok(ChainStatus::Moving { block_height })) => {
                 trace!(
                     "Latest Eth block {} Latest Cosmos block {}",
                     latest_eth_block,
                     block_height,
                 );
+                block_height
             }
             (Ok(_latest_eth_block), Ok(ChainStatus::Syncing)) => {
                 warn!("Cosmos node syncing, Eth signer paused");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
@@ -228,6 +252,7 @@ pub async fn eth_signer_main_loop(
             }
             (Ok(_), Err(_)) => {
                 warn!("Could not contact Cosmos grpc, trying again");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
@@ -238,13 +263,21 @@ pub async fn eth_signer_main_loop(
             }
             (Err(_), Err(_)) => {
                 error!("Could not reach Ethereum or Cosmos rpc!");
+                cosmos.failover(endpoint).await;
                 delay_for(DELAY).await;
                 continue;
             }
-        }
+        };
+
+        // everything found unsigned this iteration, before any filtering, is handed to the
+        // slashing monitor. If any query fails we don't know what is pending and skip it
+        let mut scanned = true;
+        let mut unsigned_valsets = Vec::new();
+        let mut unsigned_batches = Vec::new();
+        let mut unsigned_calls = Vec::new();
 
         // sign the last unsigned valsets
-        match get_oldest_unsigned_valsets(
+        match get_oldest_unsigned_valsets_with_heights(
             &mut grpc_client,
             our_cosmos_address,
             contact.get_prefix(),
@@ -252,6 +285,12 @@ pub async fn eth_signer_main_loop(
         .await
         {
             Ok(valsets) => {
+                unsigned_valsets = valsets.clone();
+                let valsets: Vec<Valset> = valsets.into_iter().map(|(valset, _)| valset).collect();
+                let (valsets, journal_entries) =
+                    record_in_journal(&signing_journal, valsets, |v| {
+                        JournalEntry::for_valset(v, &gravity_id, ethereum_key)
+                    });
                 if valsets.is_empty() {
                     trace!("No validator sets to sign, node is caught up!")
                 } else {
@@ -260,89 +299,157 @@ pub async fn eth_signer_main_loop(
                         valsets.len(),
                         valsets[0].nonce
                     );
-                    let res = send_valset_confirms(
-                        &contact,
+                    let msgs = build_valset_confirm_msgs(
+                        our_cosmos_address,
                         ethereum_key,
-                        fee.clone(),
                         valsets,
-                        cosmos_key,
                         gravity_id.clone(),
-                    )
-                    .await;
+                    );
+                    let res = broadcaster
+                        .send_messages(&contact, msgs, Some(TIMEOUT))
+                        .await;
                     trace!("Valset confirm result is {:?}", res);
-                    check_for_fee_error(res, &fee);
+                    record_submission(&signing_journal, &journal_entries, &res);
+                    check_for_fee_error(res, &broadcaster.get_fee());
                 }
             }
-            Err(e) => trace!(
-                "Failed to get unsigned valsets, check your Cosmos gRPC {:?}",
-                e
-            ),
+            Err(e) => {
+                scanned = false;
+                warn!(
+                    "Failed to get unsigned valsets, check your Cosmos gRPC {:?}",
+                    e
+                )
+            }
         }
 
-        // sign the last unsigned batch, TODO check if we already have signed this
-        match get_oldest_unsigned_transaction_batch(
+        // sign every batch and logic call we have not yet confirmed in a single transaction,
+        // anything we already confirmed is filtered out so one duplicate can't fail the rest
+        let mut msgs = Vec::new();
+        let mut journal_entries = Vec::new();
+        match get_all_unsigned_transaction_batches(
             &mut grpc_client,
             our_cosmos_address,
             contact.get_prefix(),
         )
         .await
         {
-            Ok(Some(last_unsigned_batch)) => {
-                info!(
-                    "Sending batch confirm for {}:{} with {} in fees",
-                    last_unsigned_batch.token_contract,
-                    last_unsigned_batch.nonce,
-                    last_unsigned_batch.total_fee.amount
-                );
-                let res = send_batch_confirm(
-                    &contact,
+            Ok(batches) => {
+                unsigned_batches = batches.clone();
+                let batches = match &signing_policy {
+                    Some(policy) => {
+                        policy
+                            .filter_batches(
+                                &mut grpc_client,
+                                batches,
+                                &gravity_id,
+                                cosmos_block_height,
+                            )
+                            .await
+                    }
+                    None => batches,
+                };
+                let (batches, entries) = record_in_journal(&signing_journal, batches, |b| {
+                    JournalEntry::for_batch(b, &gravity_id, ethereum_key)
+                });
+                journal_entries.extend(entries);
+                if batches.is_empty() {
+                    trace!("No unsigned batches! Everything good!")
+                }
+                for batch in batches.iter() {
+                    info!(
+                        "Sending batch confirm for {}:{} with {} in fees",
+                        batch.token_contract, batch.nonce, batch.total_fee.amount
+                    );
+                }
+                msgs.extend(build_batch_confirm_msgs(
+                    our_cosmos_address,
                     ethereum_key,
-                    fee.clone(),
-                    vec![last_unsigned_batch],
-                    cosmos_key,
+                    batches,
                     gravity_id.clone(),
+                ));
+            }
+            Err(e) => {
+                scanned = false;
+                warn!(
+                    "Failed to get unsigned Batches, check your Cosmos gRPC {:?}",
+                    e
                 )
-                .await;
-                trace!("Batch confirm result is {:?}", res);
-                check_for_fee_error(res, &fee);
             }
-            Ok(None) => trace!("No unsigned batches! Everything good!"),
-            Err(e) => trace!(
-                "Failed to get unsigned Batches, check your Cosmos gRPC {:?}",
-                e
-            ),
         }
-
-        match get_oldest_unsigned_logic_call(
+        match get_all_unsigned_logic_calls(
             &mut grpc_client,
             our_cosmos_address,
             contact.get_prefix(),
         )
         .await
         {
-            Ok(Some(last_unsigned_call)) => {
-                info!(
-                    "Sending Logic call confirm for {}:{}",
-                    bytes_to_hex_str(&last_unsigned_call.invalidation_id),
-                    last_unsigned_call.invalidation_nonce
-                );
-                let res = send_logic_call_confirm(
-                    &contact,
+            Ok(calls) => {
+                unsigned_calls = calls.clone();
+                let calls = match &signing_policy {
+                    Some(policy) => {
+                        policy
+                            .filter_logic_calls(
+                                &mut grpc_client,
+                                calls,
+                                &gravity_id,
+                                cosmos_block_height,
+                            )
+                            .await
+                    }
+                    None => calls,
+                };
+                let (calls, entries) = record_in_journal(&signing_journal, calls, |c| {
+                    JournalEntry::for_logic_call(c, &gravity_id, ethereum_key)
+                });
+                journal_entries.extend(entries);
+                if calls.is_empty() {
+                    trace!("No unsigned logic call! Everything good!")
+                }
+                for call in calls.iter() {
+                    info!(
+                        "Sending Logic call confirm for {}:{}",
+                        bytes_to_hex_str(&call.invalidation_id),
+                        call.invalidation_nonce
+                    );
+                }
+                msgs.extend(build_logic_call_confirm_msgs(
+                    our_cosmos_address,
                     ethereum_key,
-                    fee.clone(),
-                    vec![last_unsigned_call],
-                    cosmos_key,
+                    calls,
                     gravity_id.clone(),
+                ));
+            }
+            Err(e) => {
+                scanned = false;
+                warn!(
+                    "Failed to get unsigned Logic Calls, check your Cosmos gRPC {:?}",
+                    e
                 )
-                .await;
-                trace!("call confirm result is {:?}", res);
-                check_for_fee_error(res, &fee);
             }
-            Ok(None) => trace!("No unsigned logic call! Everything good!"),
-            Err(e) => info!(
-                "Failed to get unsigned Logic Calls, check your Cosmos gRPC {:?}",
-                e
-            ),
+        }
+        if !msgs.is_empty() {
+            send_confirms(
+                &broadcaster,
+                &contact,
+                msgs,
+                &signing_journal,
+                &journal_entries,
+            )
+            .await;
+        }
+
+        // anything we signed in the last iteration is already gone from these lists, so what
+        // remains was held, failed or is new. Let the operator know if the slashing window is closing
+        if scanned {
+            slashing_monitor
+                .check(
+                    &mut grpc_client,
+                    cosmos_block_height,
+                    &unsigned_valsets,
+                    &unsigned_batches,
+                    &unsigned_calls,
+                )
+                .await;
         }
 
         // a bit of logic that tires to keep things running every LOOP_SPEED seconds exactly
@@ -355,23 +462,107 @@ pub async fn eth_signer_main_loop(
     }
 }
 
+/// Records the signatures we are about to produce in the signing journal, returning only
+/// the items the journal agreed to sign along with their journal entries
+fn record_in_journal<T>(
+    journal: &Option<SigningJournal>,
+    items: Vec<T>,
+    to_entry: impl Fn(&T) -> JournalEntry,
+) -> (Vec<T>, Vec<JournalEntry>) {
+    let journal = match journal {
+        Some(journal) => journal,
+        None => return (items, Vec::new()),
+    };
+    let entries = items.iter().map(to_entry).collect();
+    let mut allowed = Vec::new();
+    let mut recorded = Vec::new();
+    for (item, result) in items.into_iter().zip(journal.record_signatures(entries)) {
+        if let Ok(entry) = result {
+            allowed.push(item);
+            recorded.push(entry);
+        }
+    }
+    (allowed, recorded)
+}
+
+/// Sends the batch and logic call confirms in a single transaction, if that transaction fails
+/// the confirms are sent one at a time so that a single bad confirm can't hold back the rest.
+/// The journal entries, if any, are in the same order as the messages
+async fn send_confirms(
+    broadcaster: &TxBroadcaster,
+    contact: &Contact,
+    msgs: Vec<Msg>,
+    journal: &Option<SigningJournal>,
+    journal_entries: &[JournalEntry],
+) {
+    let res = broadcaster
+        .send_messages(contact, msgs.clone(), Some(TIMEOUT))
+        .await;
+    trace!("Batch and logic call confirm result is {:?}", res);
+    match res {
+        Err(CosmosGrpcError::InsufficientFees { .. }) => {}
+        Err(e) if msgs.len() > 1 => {
+            warn!(
+                "Failed to send {} confirms together {:?}, sending them one at a time",
+                msgs.len(),
+                e
+            );
+            for (i, msg) in msgs.into_iter().enumerate() {
+                let res = broadcaster
+                    .send_messages(contact, vec![msg], Some(TIMEOUT))
+                    .await;
+                trace!("Confirm result is {:?}", res);
+                let entries = journal_entries.get(i..=i).unwrap_or(&[]);
+                record_submission(journal, entries, &res);
+                check_for_fee_error(res, &broadcaster.get_fee());
+            }
+            return;
+        }
+        _ => {}
+    }
+    record_submission(journal, journal_entries, &res);
+    check_for_fee_error(res, &broadcaster.get_fee());
+}
+
+/// Records the txhash of a successful confirm submission in the signing journal
+fn record_submission(
+    journal: &Option<SigningJournal>,
+    entries: &[JournalEntry],
+    res: &Result<TxResponse, CosmosGrpcError>,
+) {
+    if let (Some(journal), Ok(response)) = (journal, res) {
+        journal.record_submission(entries, &response.txhash);
+    }
+}
+
 /// Checks for fee errors on our confirm submission transactions, a failure here
 /// can be fatal and cause slashing so we want to warn the user and exit. There is
-/// no point in running if we can't perform our most important function
-fn check_for_fee_error(res: Result<TxResponse, CosmosGrpcError>, fee: &Coin) {
+/// no point in running if we can't perform our most important function. Insufficient
+/// fees only reach this point if the broadcaster could not raise the gas price within
+/// the configured max_gas_price
+fn check_for_fee_error(res: Result<TxResponse, CosmosGrpcError>, fee: &FeeSettings) {
     if let Err(CosmosGrpcError::InsufficientFees { fee_info }) = res {
         match fee_info {
             FeeInfo::InsufficientFees { min_fees } => {
                 error!(
-                    "Your specified fee value {} is too small please use at least {}",
-                    fee,
+                    "Your gas price {} is too small the node requires at least {} for this transaction",
+                    fee.gas_price,
                     Coin::display_list(&min_fees)
                 );
-                error!("Correct fee argument immediately! You will be slashed within a few hours if you fail to do so");
+                match &fee.max_gas_price {
+                    Some(max) => error!("The required gas price is above your max_gas_price {}, raise it or correct your gas price", max),
+                    None => error!("Set max_gas_price in the [fees] section of your config to allow automatic fee adjustment"),
+                }
+                error!("Correct gas price immediately! You will be slashed within a few hours if you fail to do so");
                 exit(1);
             }
             FeeInfo::InsufficientGas { .. } => {
-                panic!("Hardcoded gas amounts insufficient!");
+                // the gas limit is derived from simulation so this only happens if state
+                // changed between simulation and execution, the next attempt will re-simulate
+                error!(
+                    "Transaction ran out of gas, consider increasing gas_adjustment from {}",
+                    fee.gas_adjustment
+                );
             }
         }
     }
@@ -0,0 +1,261 @@
+//! The signing journal is an append-only record of every Ethereum signature the Eth Signer
+//! produces. If our delegate key is ever accused of a bad signature through
+//! MsgSubmitBadSignatureEvidence the journal shows exactly what was signed and when. The journal
+//! is also a last line of defense, it refuses to sign a second, different, payload for a nonce we
+//! have already signed.
+
+use clarity::utils::{bytes_to_hex_str, get_ethereum_msg_hash};
+use clarity::PrivateKey as EthPrivateKey;
+use ethereum_gravity::message_signatures::{
+    encode_logic_call_confirm, encode_tx_batch_confirm, encode_valset_confirm,
+};
+use gravity_utils::types::{LogicCall, TransactionBatch, Valset};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedObjectType {
+    Valset,
+    Batch,
+    LogicCall,
+}
+
+/// A single line of the journal. Entries are written when a signature is produced, once the
+/// signature has been submitted a second entry with the same checkpoint and the Cosmos txhash
+/// is appended.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// unix time in seconds when this entry was written
+    pub time: u64,
+    pub object_type: SignedObjectType,
+    /// the token contract for batches and the invalidation id for logic calls,
+    /// nonces are only unique within this scope. Empty for valsets
+    pub scope: String,
+    pub nonce: u64,
+    /// the hash of the encoded checkpoint that was signed, as produced by
+    /// the encode_*_confirm_hashed functions in message_signatures
+    pub checkpoint: String,
+    pub signature: String,
+    pub eth_signer: String,
+    pub txhash: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn for_valset(
+        valset: &Valset,
+        gravity_id: &str,
+        ethereum_key: EthPrivateKey,
+    ) -> JournalEntry {
+        let message = encode_valset_confirm(gravity_id.to_string(), valset.clone());
+        JournalEntry::new(
+            SignedObjectType::Valset,
+            String::new(),
+            valset.nonce,
+            &message,
+            ethereum_key,
+        )
+    }
+
+    pub fn for_batch(
+        batch: &TransactionBatch,
+        gravity_id: &str,
+        ethereum_key: EthPrivateKey,
+    ) -> JournalEntry {
+        let message = encode_tx_batch_confirm(gravity_id.to_string(), batch.clone());
+        JournalEntry::new(
+            SignedObjectType::Batch,
+            batch.token_contract.to_string(),
+            batch.nonce,
+            &message,
+            ethereum_key,
+        )
+    }
+
+    pub fn for_logic_call(
+        call: &LogicCall,
+        gravity_id: &str,
+        ethereum_key: EthPrivateKey,
+    ) -> JournalEntry {
+        let message = encode_logic_call_confirm(gravity_id.to_string(), call.clone());
+        JournalEntry::new(
+            SignedObjectType::LogicCall,
+            bytes_to_hex_str(&call.invalidation_id),
+            call.invalidation_nonce,
+            &message,
+            ethereum_key,
+        )
+    }
+
+    /// Ethereum signatures are deterministic so signing the message here produces the
+    /// exact signature that is placed in the confirm message
+    fn new(
+        object_type: SignedObjectType,
+        scope: String,
+        nonce: u64,
+        message: &[u8],
+        ethereum_key: EthPrivateKey,
+    ) -> JournalEntry {
+        let signature = ethereum_key.sign_ethereum_msg(message);
+        JournalEntry {
+            time: now(),
+            object_type,
+            scope,
+            nonce,
+            checkpoint: bytes_to_hex_str(&get_ethereum_msg_hash(message)),
+            signature: bytes_to_hex_str(&signature.to_bytes()),
+            eth_signer: ethereum_key.to_public_key().unwrap().to_string(),
+            txhash: None,
+        }
+    }
+
+    fn key(&self) -> (SignedObjectType, String, u64) {
+        (self.object_type, self.scope.to_lowercase(), self.nonce)
+    }
+}
+
+/// Handle to the journal file, clones share the same file and index
+#[derive(Clone)]
+pub struct SigningJournal {
+    path: PathBuf,
+    /// the checkpoint signed for every nonce in the journal
+    signed: Arc<Mutex<HashMap<(SignedObjectType, String, u64), String>>>,
+}
+
+impl SigningJournal {
+    /// Opens the journal at the given path, creating it if it does not exist
+    pub fn open(path: &Path) -> Result<SigningJournal, String> {
+        let mut signed = HashMap::new();
+        for entry in read_journal(path)? {
+            signed.insert(entry.key(), entry.checkpoint.clone());
+        }
+        Ok(SigningJournal {
+            path: path.to_path_buf(),
+            signed: Arc::new(Mutex::new(signed)),
+        })
+    }
+
+    /// Records entries for signatures we are about to produce, any entry that conflicts
+    /// with a previously signed payload for the same nonce is not recorded and is returned
+    /// as an error, the caller must not sign it. Entries are returned in the order given
+    pub fn record_signatures(
+        &self,
+        entries: Vec<JournalEntry>,
+    ) -> Vec<Result<JournalEntry, JournalEntry>> {
+        let mut signed = self.signed.lock().unwrap();
+        let mut out = Vec::new();
+        for entry in entries {
+            match signed.get(&entry.key()) {
+                Some(checkpoint) if *checkpoint != entry.checkpoint => {
+                    error!(
+                        "Refusing to sign {:?} {} nonce {} checkpoint {}, we already signed checkpoint {} for this nonce!",
+                        entry.object_type, entry.scope, entry.nonce, entry.checkpoint, checkpoint
+                    );
+                    out.push(Err(entry));
+                }
+                // already recorded, only a submission adds another line for this nonce
+                Some(_) => out.push(Ok(entry)),
+                None => {
+                    if let Err(e) = self.append(&entry) {
+                        error!("Failed to write signing journal, refusing to sign {}", e);
+                        out.push(Err(entry));
+                        continue;
+                    }
+                    signed.insert(entry.key(), entry.checkpoint.clone());
+                    out.push(Ok(entry));
+                }
+            }
+        }
+        out
+    }
+
+    /// Records the txhash of the transaction that submitted the given signatures
+    pub fn record_submission(&self, entries: &[JournalEntry], txhash: &str) {
+        let _lock = self.signed.lock().unwrap();
+        for entry in entries {
+            let mut entry = entry.clone();
+            entry.time = now();
+            entry.txhash = Some(txhash.to_string());
+            if let Err(e) = self.append(&entry) {
+                error!("Failed to write signing journal {}", e);
+            }
+        }
+    }
+
+    fn append(&self, entry: &JournalEntry) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("{:?}", e))?;
+        let line = serde_json::to_string(entry).unwrap();
+        writeln!(file, "{}", line).map_err(|e| format!("{:?}", e))?;
+        file.sync_data().map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Reads every entry in the journal, a missing journal is empty
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = File::open(path).map_err(|e| format!("{:?}", e))?;
+    let mut out = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("{:?}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // a crash may leave a partial last line, everything before it is still valid
+        match serde_json::from_str(&line) {
+            Ok(entry) => out.push(entry),
+            Err(e) => warn!("Skipping unreadable journal line {} {:?}", i + 1, e),
+        }
+    }
+    Ok(out)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_refuse_conflicting_signature() {
+        let path = temp_dir().join(format!("signing-journal-test-{}.jsonl", now()));
+        let key = EthPrivateKey::from_slice(&[1u8; 32]).unwrap();
+        let journal = SigningJournal::open(&path).unwrap();
+        let valset = Valset {
+            nonce: 1,
+            ..Default::default()
+        };
+        let mut other_valset = valset.clone();
+        other_valset.reward_amount = 1u8.into();
+
+        let first = JournalEntry::for_valset(&valset, "foo", key);
+        assert!(journal.record_signatures(vec![first.clone()])[0].is_ok());
+        // the same payload may be signed again
+        assert!(journal.record_signatures(vec![first.clone()])[0].is_ok());
+        assert_eq!(read_journal(&path).unwrap().len(), 1);
+        journal.record_submission(&[first], "abcd");
+        assert_eq!(read_journal(&path).unwrap().len(), 2);
+        let conflict = JournalEntry::for_valset(&other_valset, "foo", key);
+        assert!(journal.record_signatures(vec![conflict.clone()])[0].is_err());
+
+        // the index is rebuilt from the file
+        let reopened = SigningJournal::open(&path).unwrap();
+        assert!(reopened.record_signatures(vec![conflict])[0].is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,476 @@
+//! The local signing policy checks batches and logic calls against operator configured rules
+//! before the Eth Signer produces a signature for them. Anything that breaks a rule is held
+//! until the operator approves it's checkpoint hash with `gbt orchestrator approve`. Holding
+//! something does not stop the slashing clock, so every hold is logged with the number of
+//! blocks left before the signature is required.
+
+use clarity::{utils::bytes_to_hex_str, Address as EthAddress, Uint256};
+use cosmos_gravity::query::{
+    get_gravity_params, get_latest_logic_calls, get_transaction_batch_by_nonce,
+};
+use ethereum_gravity::message_signatures::{
+    encode_logic_call_confirm_hashed, encode_tx_batch_confirm_hashed,
+};
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::types::{Erc20Token, LogicCall, SigningPolicyConfig, TransactionBatch};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tonic::transport::Channel;
+
+/// Loads the checkpoint hashes the operator has approved, as lowercase hex without a 0x prefix
+pub type ApprovalLoader = Arc<dyn Fn() -> Vec<String> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct SigningPolicy {
+    /// A connection to a second Cosmos node, batches and logic calls are fetched
+    /// again from it to make sure our node is not feeding us something else
+    verifier: Option<GravityQueryClient<Channel>>,
+    max_amounts: HashMap<EthAddress, Uint256>,
+    allowed_logic_contracts: Vec<EthAddress>,
+    approvals: Option<ApprovalLoader>,
+}
+
+impl SigningPolicy {
+    /// Builds the policy from the config, returns None if the policy is disabled. The verifier
+    /// must be connected to the node configured as verification_grpc
+    pub fn new(
+        config: &SigningPolicyConfig,
+        approvals: Option<ApprovalLoader>,
+        verifier: Option<GravityQueryClient<Channel>>,
+    ) -> Result<Option<SigningPolicy>, String> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let mut max_amounts = HashMap::new();
+        for (contract, amount) in config.max_amounts.iter() {
+            let contract: EthAddress = contract
+                .parse()
+                .map_err(|e| format!("Invalid max_amounts contract {} {:?}", contract, e))?;
+            let amount: Uint256 = amount
+                .parse()
+                .map_err(|e| format!("Invalid max_amounts amount {} {:?}", amount, e))?;
+            max_amounts.insert(contract, amount);
+        }
+        let mut allowed_logic_contracts = Vec::new();
+        for contract in config.allowed_logic_contracts.iter() {
+            allowed_logic_contracts.push(
+                contract
+                    .parse()
+                    .map_err(|e| format!("Invalid logic contract {} {:?}", contract, e))?,
+            );
+        }
+        if config.verification_grpc.is_some() && verifier.is_none() {
+            return Err("No connection to the verification_grpc node".to_string());
+        }
+        Ok(Some(SigningPolicy {
+            verifier,
+            max_amounts,
+            allowed_logic_contracts,
+            approvals,
+        }))
+    }
+
+    /// Returns the batches that may be signed, every other batch is held and logged
+    pub async fn filter_batches(
+        &self,
+        grpc_client: &mut GravityQueryClient<Channel>,
+        batches: Vec<TransactionBatch>,
+        gravity_id: &str,
+        current_height: u64,
+    ) -> Vec<TransactionBatch> {
+        let mut out = Vec::new();
+        for batch in batches {
+            let checkpoint = encode_tx_batch_confirm_hashed(gravity_id.to_string(), batch.clone());
+            let mut problems = self.check_batch(&batch);
+            if let Some(verifier) = &self.verifier {
+                let independent = match get_transaction_batch_by_nonce(
+                    &mut verifier.clone(),
+                    batch.nonce,
+                    batch.token_contract,
+                )
+                .await
+                {
+                    Ok(stored) => stored.map(|stored| {
+                        encode_tx_batch_confirm_hashed(gravity_id.to_string(), stored)
+                    }),
+                    Err(e) => {
+                        // we'll check again next loop, don't bother the operator with this
+                        warn!(
+                            "Could not fetch batch {} from the verification node {:?}",
+                            batch.nonce, e
+                        );
+                        continue;
+                    }
+                };
+                problems.extend(compare_checkpoints(&checkpoint, independent));
+            }
+
+            if problems.is_empty() || self.is_approved(&checkpoint) {
+                out.push(batch)
+            } else {
+                let description = format!("batch {}:{}", batch.token_contract, batch.nonce);
+                let window = get_gravity_params(grpc_client)
+                    .await
+                    .map(|p| p.signed_batches_window)
+                    .ok();
+                log_hold(
+                    &description,
+                    &problems,
+                    &checkpoint,
+                    batch.block,
+                    current_height,
+                    window,
+                );
+            }
+        }
+        out
+    }
+
+    /// Returns the logic calls that may be signed, every other logic call is held and logged
+    pub async fn filter_logic_calls(
+        &self,
+        grpc_client: &mut GravityQueryClient<Channel>,
+        calls: Vec<LogicCall>,
+        gravity_id: &str,
+        current_height: u64,
+    ) -> Vec<LogicCall> {
+        let mut out = Vec::new();
+        for call in calls {
+            let checkpoint = encode_logic_call_confirm_hashed(gravity_id.to_string(), call.clone());
+            let mut problems = self.check_logic_call(&call);
+            if let Some(verifier) = &self.verifier {
+                let independent = match get_latest_logic_calls(&mut verifier.clone()).await {
+                    Ok(calls) => calls
+                        .into_iter()
+                        .find(|c| {
+                            c.invalidation_id == call.invalidation_id
+                                && c.invalidation_nonce == call.invalidation_nonce
+                        })
+                        .map(|c| encode_logic_call_confirm_hashed(gravity_id.to_string(), c)),
+                    Err(e) => {
+                        warn!(
+                            "Could not fetch logic call {} from the verification node {:?}",
+                            call.invalidation_nonce, e
+                        );
+                        continue;
+                    }
+                };
+                problems.extend(compare_checkpoints(&checkpoint, independent));
+            }
+
+            if problems.is_empty() || self.is_approved(&checkpoint) {
+                out.push(call)
+            } else {
+                let description = format!(
+                    "logic call {}:{}",
+                    bytes_to_hex_str(&call.invalidation_id),
+                    call.invalidation_nonce
+                );
+                let window = get_gravity_params(grpc_client)
+                    .await
+                    .map(|p| p.signed_logic_calls_window)
+                    .ok();
+                log_hold(
+                    &description,
+                    &problems,
+                    &checkpoint,
+                    call.block,
+                    current_height,
+                    window,
+                );
+            }
+        }
+        out
+    }
+
+    /// Checks a batch against the local rules
+    fn check_batch(&self, batch: &TransactionBatch) -> Vec<String> {
+        let mut problems = Vec::new();
+        for tx in batch.transactions.iter() {
+            if tx.erc20_token.token_contract_address != batch.token_contract
+                || tx.erc20_fee.token_contract_address != batch.token_contract
+            {
+                problems.push(format!(
+                    "transaction {} does not match the batch token contract",
+                    tx.id
+                ));
+            }
+        }
+        // fees are paid out of the contract to the relayer, so they count towards the limit
+        let sent: Vec<Erc20Token> = batch
+            .transactions
+            .iter()
+            .flat_map(|tx| vec![tx.erc20_token.clone(), tx.erc20_fee.clone()])
+            .collect();
+        problems.extend(self.check_amounts(&sent));
+        problems
+    }
+
+    /// Checks a logic call against the local rules
+    fn check_logic_call(&self, call: &LogicCall) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self
+            .allowed_logic_contracts
+            .contains(&call.logic_contract_address)
+        {
+            problems.push(format!(
+                "logic contract {} is not in allowed_logic_contracts",
+                call.logic_contract_address
+            ));
+        }
+        let sent: Vec<Erc20Token> = call
+            .transfers
+            .iter()
+            .chain(call.fees.iter())
+            .cloned()
+            .collect();
+        problems.extend(self.check_amounts(&sent));
+        problems
+    }
+
+    /// Sums the amounts sent per token, including fees, and compares them to the configured limits
+    fn check_amounts(&self, sent: &[Erc20Token]) -> Vec<String> {
+        let mut totals: HashMap<EthAddress, Uint256> = HashMap::new();
+        for token in sent {
+            let total = totals
+                .entry(token.token_contract_address)
+                .or_insert_with(|| 0u8.into());
+            *total = total.clone() + token.amount.clone();
+        }
+        let mut problems = Vec::new();
+        for (contract, total) in totals {
+            if let Some(max) = self.max_amounts.get(&contract) {
+                if total > *max {
+                    problems.push(format!(
+                        "sends {} of {} including fees which is over the limit of {}",
+                        total, contract, max
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    fn is_approved(&self, checkpoint: &[u8]) -> bool {
+        match &self.approvals {
+            Some(loader) => {
+                let checkpoint = bytes_to_hex_str(checkpoint).to_lowercase();
+                loader().iter().any(|a| *a == checkpoint)
+            }
+            None => false,
+        }
+    }
+}
+
+/// Compares the checkpoint we are about to sign with the one produced by the copy fetched
+/// from the verification node
+fn compare_checkpoints(checkpoint: &[u8], independent: Option<Vec<u8>>) -> Option<String> {
+    match independent {
+        Some(independent) if independent == checkpoint => None,
+        Some(_) => {
+            Some("the checkpoint does not match the one from the verification node".to_string())
+        }
+        None => Some("the verification node does not know about it".to_string()),
+    }
+}
+
+fn log_hold(
+    description: &str,
+    problems: &[String],
+    checkpoint: &[u8],
+    created_at: u64,
+    current_height: u64,
+    window: Option<u64>,
+) {
+    warn!(
+        "Signing policy is holding {}: {}",
+        description,
+        problems.join(", ")
+    );
+    match window {
+        Some(window) => {
+            let deadline = created_at + window;
+            if current_height < deadline {
+                warn!(
+                    "You will be slashed if this is not signed within {} blocks (by block {})",
+                    deadline - current_height,
+                    deadline
+                );
+            } else {
+                error!(
+                    "The slashing window for this ended at block {}, sign it now to limit the damage",
+                    deadline
+                );
+            }
+        }
+        None => warn!(
+            "Could not get the slashing window, you will be slashed if this is not signed in time"
+        ),
+    }
+    warn!(
+        "Run `gbt orchestrator approve {}` to sign it anyway",
+        bytes_to_hex_str(checkpoint)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+    use gravity_utils::types::BatchTransaction;
+
+    const CONTRACT: &str = "0xD50c0953a99325d01cca655E57070F1be4983b6b";
+    const OTHER_CONTRACT: &str = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5";
+
+    fn policy(config: SigningPolicyConfig, approvals: Option<ApprovalLoader>) -> SigningPolicy {
+        let config = SigningPolicyConfig {
+            enabled: true,
+            ..config
+        };
+        SigningPolicy::new(&config, approvals, None)
+            .unwrap()
+            .unwrap()
+    }
+
+    fn token(contract: &str, amount: u64) -> Erc20Token {
+        Erc20Token {
+            amount: amount.into(),
+            token_contract_address: contract.parse().unwrap(),
+        }
+    }
+
+    fn batch(fee_contract: &str) -> TransactionBatch {
+        let tx = BatchTransaction {
+            id: 1,
+            sender: CosmosPrivateKey::from_secret(&[1u8; 32])
+                .to_address("cosmos")
+                .unwrap(),
+            destination: OTHER_CONTRACT.parse().unwrap(),
+            erc20_token: token(CONTRACT, 60),
+            erc20_fee: token(fee_contract, 1),
+        };
+        TransactionBatch {
+            nonce: 1,
+            batch_timeout: 1000,
+            transactions: vec![tx],
+            total_fee: token(fee_contract, 1),
+            token_contract: CONTRACT.parse().unwrap(),
+            block: 10,
+        }
+    }
+
+    #[test]
+    fn test_check_amounts() {
+        let mut config = SigningPolicyConfig::default();
+        config
+            .max_amounts
+            .insert(CONTRACT.to_string(), "100".to_string());
+        let policy = policy(config, None);
+        let token = token(CONTRACT, 60);
+        assert!(policy.check_amounts(&[token.clone()]).is_empty());
+        assert_eq!(policy.check_amounts(&[token.clone(), token]).len(), 1);
+    }
+
+    #[test]
+    fn test_check_batch_and_logic_call() {
+        let policy = policy(SigningPolicyConfig::default(), None);
+        assert!(policy.check_batch(&batch(CONTRACT)).is_empty());
+        // a fee in another token than the batch can't be paid out by the contract
+        assert_eq!(policy.check_batch(&batch(OTHER_CONTRACT)).len(), 1);
+
+        let mut call = LogicCall {
+            transfers: vec![token(CONTRACT, 60)],
+            fees: vec![token(CONTRACT, 1)],
+            logic_contract_address: OTHER_CONTRACT.parse().unwrap(),
+            payload: vec![1, 2, 3],
+            timeout: 1000,
+            invalidation_id: vec![7u8; 32],
+            invalidation_nonce: 1,
+            block: 10,
+        };
+        assert_eq!(policy.check_logic_call(&call).len(), 1);
+        let policy = self::policy(
+            SigningPolicyConfig {
+                allowed_logic_contracts: vec![OTHER_CONTRACT.to_string()],
+                ..Default::default()
+            },
+            None,
+        );
+        assert!(policy.check_logic_call(&call).is_empty());
+        call.logic_contract_address = CONTRACT.parse().unwrap();
+        assert_eq!(policy.check_logic_call(&call).len(), 1);
+    }
+
+    #[test]
+    fn test_fees_count_towards_max_amounts() {
+        let mut config = SigningPolicyConfig {
+            allowed_logic_contracts: vec![OTHER_CONTRACT.to_string()],
+            ..Default::default()
+        };
+        config
+            .max_amounts
+            .insert(CONTRACT.to_string(), "100".to_string());
+        let policy = policy(config, None);
+
+        // 60 sent and a fee of 1 is within the limit
+        let mut batch = batch(CONTRACT);
+        assert!(policy.check_batch(&batch).is_empty());
+        // a small amount with a huge fee is not
+        batch.transactions[0].erc20_token = token(CONTRACT, 1);
+        batch.transactions[0].erc20_fee = token(CONTRACT, 1000);
+        assert_eq!(policy.check_batch(&batch).len(), 1);
+
+        let mut call = LogicCall {
+            transfers: vec![token(CONTRACT, 1)],
+            fees: vec![token(CONTRACT, 99)],
+            logic_contract_address: OTHER_CONTRACT.parse().unwrap(),
+            payload: vec![1, 2, 3],
+            timeout: 1000,
+            invalidation_id: vec![7u8; 32],
+            invalidation_nonce: 1,
+            block: 10,
+        };
+        assert!(policy.check_logic_call(&call).is_empty());
+        call.fees = vec![token(CONTRACT, 50), token(CONTRACT, 50)];
+        assert_eq!(policy.check_logic_call(&call).len(), 1);
+    }
+
+    #[test]
+    fn test_compare_checkpoints_and_approvals() {
+        let checkpoint = vec![0xABu8; 32];
+        assert_eq!(
+            compare_checkpoints(&checkpoint, Some(checkpoint.clone())),
+            None
+        );
+        assert!(compare_checkpoints(&checkpoint, Some(vec![0u8; 32])).is_some());
+        assert!(compare_checkpoints(&checkpoint, None).is_some());
+
+        let approved = bytes_to_hex_str(&checkpoint).to_lowercase();
+        let approvals: ApprovalLoader = Arc::new(move || vec![approved.clone()]);
+        let policy = policy(SigningPolicyConfig::default(), Some(approvals));
+        assert!(policy.is_approved(&checkpoint));
+        assert!(!policy.is_approved(&[0u8; 32]));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = SigningPolicyConfig {
+            enabled: true,
+            allowed_logic_contracts: vec!["not an address".to_string()],
+            ..Default::default()
+        };
+        assert!(SigningPolicy::new(&config, None, None).is_err());
+        // a verification node must be connected when one is configured
+        let config = SigningPolicyConfig {
+            enabled: true,
+            verification_grpc: Some("http://localhost:9090".to_string()),
+            ..Default::default()
+        };
+        assert!(SigningPolicy::new(&config, None, None).is_err());
+        assert!(
+            SigningPolicy::new(&SigningPolicyConfig::default(), None, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
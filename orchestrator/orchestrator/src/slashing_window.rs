@@ -0,0 +1,295 @@
+//! Tracks how close every object we have not yet signed is to the slashing windows set in the
+//! Gravity module params. A validator that has not confirmed a valset within signed_valsets_window
+//! blocks of it's creation, or a batch or logic call within signed_batches_window or
+//! signed_logic_calls_window blocks, is slashed.
+
+use crate::signing_journal::SignedObjectType;
+use clarity::utils::bytes_to_hex_str;
+use cosmos_gravity::query::{
+    get_all_unsigned_logic_calls, get_all_unsigned_transaction_batches, get_gravity_params,
+    get_oldest_unsigned_valsets_with_heights,
+};
+use deep_space::Address as CosmosAddress;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_proto::gravity::Params;
+use gravity_utils::error::GravityError;
+use gravity_utils::types::{LogicCall, TransactionBatch, Valset};
+use log::Level;
+use std::collections::HashMap;
+use tonic::transport::Channel;
+
+/// An object our validator is expected to sign but has not yet
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingSignature {
+    pub object_type: SignedObjectType,
+    /// A human readable identifier for the object
+    pub description: String,
+    /// The Cosmos block this object was created at
+    pub created_at: u64,
+    /// The block after which we may be slashed for not having signed this object
+    pub deadline: u64,
+}
+
+impl PendingSignature {
+    /// The number of blocks left until we are slashable, zero if we already are
+    pub fn blocks_remaining(&self, current_height: u64) -> u64 {
+        self.deadline.saturating_sub(current_height)
+    }
+}
+
+/// Builds the signing deadline of every unsigned object from the slashing windows
+/// in the Gravity params, valsets are paired with the height they were created at
+pub fn pending_signatures(
+    params: &Params,
+    valsets: &[(Valset, u64)],
+    batches: &[TransactionBatch],
+    calls: &[LogicCall],
+) -> Vec<PendingSignature> {
+    let mut out = Vec::new();
+    for (valset, height) in valsets {
+        out.push(PendingSignature {
+            object_type: SignedObjectType::Valset,
+            description: format!("valset {}", valset.nonce),
+            created_at: *height,
+            deadline: height + params.signed_valsets_window,
+        })
+    }
+    for batch in batches {
+        out.push(PendingSignature {
+            object_type: SignedObjectType::Batch,
+            description: format!("batch {}:{}", batch.token_contract, batch.nonce),
+            created_at: batch.block,
+            deadline: batch.block + params.signed_batches_window,
+        })
+    }
+    for call in calls {
+        out.push(PendingSignature {
+            object_type: SignedObjectType::LogicCall,
+            description: format!(
+                "logic call {}:{}",
+                bytes_to_hex_str(&call.invalidation_id),
+                call.invalidation_nonce
+            ),
+            created_at: call.block,
+            deadline: call.block + params.signed_logic_calls_window,
+        })
+    }
+    out.sort_by_key(|p| p.deadline);
+    out
+}
+
+/// Gets every valset, batch and logic call the given orchestrator address has not
+/// yet signed along with the block by which it must be signed
+pub async fn get_pending_signatures(
+    client: &mut GravityQueryClient<Channel>,
+    address: CosmosAddress,
+    prefix: String,
+) -> Result<Vec<PendingSignature>, GravityError> {
+    let params = get_gravity_params(client).await?;
+    let valsets = get_oldest_unsigned_valsets_with_heights(client, address, prefix.clone()).await?;
+    let batches = get_all_unsigned_transaction_batches(client, address, prefix.clone()).await?;
+    let calls = get_all_unsigned_logic_calls(client, address, prefix).await?;
+    Ok(pending_signatures(&params, &valsets, &batches, &calls))
+}
+
+/// Warns the operator as objects we have not signed approach their slashing deadline,
+/// each object is warned about once per threshold it crosses
+pub struct SlashingWindowMonitor {
+    /// blocks remaining at which to warn, largest first
+    thresholds: Vec<u64>,
+    /// the smallest threshold we have already warned about for each object
+    warned: HashMap<String, u64>,
+    /// the slashing windows only change by governance, so they are fetched once
+    params: Option<Params>,
+}
+
+impl SlashingWindowMonitor {
+    pub fn new(mut thresholds: Vec<u64>) -> SlashingWindowMonitor {
+        thresholds.sort_unstable();
+        thresholds.reverse();
+        SlashingWindowMonitor {
+            thresholds,
+            warned: HashMap::new(),
+            params: None,
+        }
+    }
+
+    /// Logs a warning for anything in the unsigned objects the signer loop found this
+    /// iteration that is close to being slashable
+    pub async fn check(
+        &mut self,
+        client: &mut GravityQueryClient<Channel>,
+        current_height: u64,
+        valsets: &[(Valset, u64)],
+        batches: &[TransactionBatch],
+        calls: &[LogicCall],
+    ) {
+        if self.params.is_none() {
+            match get_gravity_params(client).await {
+                Ok(params) => self.params = Some(params),
+                Err(e) => {
+                    trace!("Failed to get slashing windows {:?}", e);
+                    return;
+                }
+            }
+        }
+        let pending = pending_signatures(self.params.as_ref().unwrap(), valsets, batches, calls);
+        for (level, message) in self.warnings(&pending, current_height) {
+            log!(level, "{}", message);
+        }
+    }
+
+    /// Returns the log level and message for every threshold newly crossed by the given
+    /// pending objects, objects not in the list are assumed signed and forgotten
+    fn warnings(
+        &mut self,
+        pending: &[PendingSignature],
+        current_height: u64,
+    ) -> Vec<(Level, String)> {
+        let mut out = Vec::new();
+        // forget anything that has been signed since
+        self.warned
+            .retain(|description, _| pending.iter().any(|p| p.description == *description));
+
+        for item in pending {
+            let remaining = item.blocks_remaining(current_height);
+            // being slashable is treated as crossing a final threshold of zero
+            let crossed = if remaining == 0 {
+                0
+            } else {
+                match self.thresholds.iter().filter(|t| remaining <= **t).min() {
+                    Some(t) => *t,
+                    None => continue,
+                }
+            };
+            if let Some(warned) = self.warned.get(&item.description) {
+                if *warned <= crossed {
+                    continue;
+                }
+            }
+            self.warned.insert(item.description.clone(), crossed);
+            if remaining == 0 {
+                out.push((
+                    Level::Error,
+                    format!(
+                        "We did not sign {} by block {} and are now slashable!",
+                        item.description, item.deadline
+                    ),
+                ));
+            } else {
+                let level = if Some(&crossed) == self.thresholds.last() {
+                    Level::Error
+                } else {
+                    Level::Warn
+                };
+                out.push((
+                    level,
+                    format!(
+                        "{} is still unsigned, we will be slashed in {} blocks at block {}",
+                        item.description, remaining, item.deadline
+                    ),
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> Params {
+        Params {
+            signed_valsets_window: 100,
+            signed_batches_window: 200,
+            signed_logic_calls_window: 300,
+            ..Default::default()
+        }
+    }
+
+    fn valset(nonce: u64) -> Valset {
+        Valset {
+            nonce,
+            ..Default::default()
+        }
+    }
+
+    fn batch(nonce: u64, block: u64) -> TransactionBatch {
+        TransactionBatch {
+            nonce,
+            block,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pending_signatures() {
+        let call = LogicCall {
+            invalidation_id: vec![1],
+            invalidation_nonce: 2,
+            block: 10,
+            ..Default::default()
+        };
+        let pending = pending_signatures(&params(), &[(valset(5), 50)], &[batch(3, 20)], &[call]);
+        let deadlines: Vec<(SignedObjectType, u64)> = pending
+            .iter()
+            .map(|p| (p.object_type, p.deadline))
+            .collect();
+        // sorted by deadline, earliest first
+        assert_eq!(
+            deadlines,
+            vec![
+                (SignedObjectType::Valset, 150),
+                (SignedObjectType::Batch, 220),
+                (SignedObjectType::LogicCall, 310)
+            ]
+        );
+        assert_eq!(pending[0].created_at, 50);
+        assert_eq!(pending[0].blocks_remaining(140), 10);
+        assert_eq!(pending[0].blocks_remaining(200), 0);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut monitor = SlashingWindowMonitor::new(vec![10, 50]);
+        let pending = pending_signatures(&params(), &[(valset(1), 0)], &[], &[]);
+        let levels = |w: Vec<(Level, String)>| w.into_iter().map(|(l, _)| l).collect::<Vec<_>>();
+
+        // nothing until the largest threshold is crossed
+        assert!(monitor.warnings(&pending, 0).is_empty());
+        assert!(monitor.warnings(&pending, 49).is_empty());
+        // each threshold is warned about once
+        assert_eq!(levels(monitor.warnings(&pending, 50)), vec![Level::Warn]);
+        assert!(monitor.warnings(&pending, 60).is_empty());
+        // the last threshold is an error
+        assert_eq!(levels(monitor.warnings(&pending, 95)), vec![Level::Error]);
+        assert!(monitor.warnings(&pending, 99).is_empty());
+        // as is the deadline passing
+        let warnings = monitor.warnings(&pending, 100);
+        assert_eq!(levels(warnings.clone()), vec![Level::Error]);
+        assert!(warnings[0].1.contains("slashable"));
+        assert!(monitor.warnings(&pending, 120).is_empty());
+    }
+
+    #[test]
+    fn test_warnings_skip_to_smallest_threshold() {
+        let mut monitor = SlashingWindowMonitor::new(vec![50, 10]);
+        let pending = pending_signatures(&params(), &[(valset(1), 0)], &[], &[]);
+        // first seen past both thresholds, only the closest one is reported
+        assert_eq!(monitor.warnings(&pending, 95).len(), 1);
+        assert!(monitor.warnings(&pending, 96).is_empty());
+    }
+
+    #[test]
+    fn test_warnings_forget_signed() {
+        let mut monitor = SlashingWindowMonitor::new(vec![50]);
+        let pending = pending_signatures(&params(), &[(valset(1), 0)], &[], &[]);
+        assert_eq!(monitor.warnings(&pending, 60).len(), 1);
+        // once signed the object is dropped
+        assert!(monitor.warnings(&[], 61).is_empty());
+        assert!(monitor.warned.is_empty());
+        // and warned about again if it ever shows up unsigned
+        assert_eq!(monitor.warnings(&pending, 62).len(), 1);
+    }
+}
@@ -13,11 +13,12 @@ use std::time::Duration;
 use tonic::transport::Channel;
 use web30::amm::WETH_CONTRACT_ADDRESS;
 use web30::client::Web3;
+use web30::jsonrpc::error::Web3Error;
 
 #[derive(Debug, Clone)]
-struct SubmittableBatch {
-    batch: TransactionBatch,
-    sigs: Vec<BatchConfirmResponse>,
+pub(crate) struct SubmittableBatch {
+    pub(crate) batch: TransactionBatch,
+    pub(crate) sigs: Vec<BatchConfirmResponse>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -65,7 +66,7 @@ pub async fn relay_batches(
 /// set on Ethereum. In both the later and the former case the correct solution is to wait
 /// through timeouts, new signatures, or a later valid batch being submitted old batches will
 /// always be resolved.
-async fn get_batches_and_signatures(
+pub(crate) async fn get_batches_and_signatures(
     current_valset: Valset,
     grpc_client: &mut GravityQueryClient<Channel>,
     gravity_id: String,
@@ -124,27 +125,9 @@ async fn should_relay_batch(
 ) -> bool {
     let batch_reward_amount = batch.total_fee.amount.clone();
     let batch_reward_token = batch.total_fee.token_contract_address;
-    // If we're given WETH, we just want to know if the reward covers gas
-    if batch_reward_token == *WETH_CONTRACT_ADDRESS {
-        // TODO: Allow users to specify what sort of margin they want on rewards, to account for
-        return batch_reward_amount > cost;
-    }
-
-    // Otherwise we need to see how much WETH we can get for the reward token amount,
-    // and compare that value to the gas cost
     // TODO: Allow users to specify what sort of margin they want on rewards, to account for
     // cost of electricity, etc.
-    let price = web3
-        .get_uniswap_price(
-            pubkey,
-            batch_reward_token,
-            *WETH_CONTRACT_ADDRESS,
-            None,
-            batch_reward_amount.clone(),
-            None,
-            None,
-        )
-        .await;
+    let price = get_weth_value(web3, batch_reward_token, batch_reward_amount, pubkey).await;
     if price.is_err() {
         info!(
             "Unable to determine swap price of token {} for WETH due to \
@@ -159,6 +142,29 @@ async fn should_relay_batch(
     price > cost
 }
 
+/// Gets how much WETH `amount` of `token` is worth on Uniswap, this is what relayers compare
+/// to the gas cost of a batch. WETH is worth itself
+pub async fn get_weth_value(
+    web3: &Web3,
+    token: EthAddress,
+    amount: Uint256,
+    caller: EthAddress,
+) -> Result<Uint256, Web3Error> {
+    if token == *WETH_CONTRACT_ADDRESS {
+        return Ok(amount);
+    }
+    web3.get_uniswap_price(
+        caller,
+        token,
+        *WETH_CONTRACT_ADDRESS,
+        None,
+        amount,
+        None,
+        None,
+    )
+    .await
+}
+
 #[allow(clippy::too_many_arguments)]
 /// Attempts to submit batches with valid signatures, checking the state
 /// of the Ethereum chain to ensure that it is valid to submit a given batch
@@ -234,7 +240,7 @@ async fn submit_batches(
                     web3,
                     gravity_contract_address,
                     gravity_id.clone(),
-                    ethereum_key,
+                    our_ethereum_address,
                 )
                 .await;
                 if cost.is_err() {
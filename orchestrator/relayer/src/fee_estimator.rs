@@ -0,0 +1,397 @@
+//! Estimates the bridge fee a MsgSendToEth should pay to be relayed. Relayers submit a batch once
+//! its fees, priced in WETH on Uniswap, are worth more than the Ethereum gas to submit it, so the
+//! estimate simulates submitting a signed batch the way relayers do and searches for the amount
+//! of the transferred token that relayers would value above that cost
+
+use crate::batch_relaying::{get_batches_and_signatures, get_weth_value, SubmittableBatch};
+use crate::find_latest_valset::find_latest_valset;
+use clarity::Address as EthAddress;
+use clarity::Uint256;
+use cosmos_gravity::query::{get_batch_fees, get_current_valset, get_gravity_params};
+use ethereum_gravity::submit_batch::estimate_tx_batch_cost;
+use ethereum_gravity::utils::GasCost;
+use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::error::GravityError;
+use std::cmp::max;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tonic::transport::Channel;
+use web30::amm::WETH_CONTRACT_ADDRESS;
+use web30::client::Web3;
+use web30::jsonrpc::error::Web3Error;
+
+/// Gas used by submitBatch regardless of the validator set or batch size, only used when
+/// there is no signed batch to simulate
+const SUBMIT_BATCH_BASE_GAS: u64 = 100_000;
+/// Gas used to check the signature of one validator, an upper bound as the contract stops
+/// checking once enough power has signed
+const SUBMIT_BATCH_GAS_PER_SIGNER: u64 = 10_000;
+/// Gas used to send one transfer in a batch
+const SUBMIT_BATCH_GAS_PER_TRANSFER: u64 = 40_000;
+/// The batch size the next batch is priced at when there is no signed batch to simulate
+const NEXT_BATCH_TRANSFERS: u64 = 10;
+/// Added to the cost of the next batch so small price moves do not leave it unprofitable
+const NEXT_BATCH_MARGIN_PERCENT: u64 = 10;
+/// How much the token amount grows between quotes until it is worth enough
+const SEARCH_GROWTH: u64 = 16;
+/// Gives up on a pool too shallow to ever be worth the cost after this many quotes
+const SEARCH_MAX_GROWTH_STEPS: u32 = 32;
+/// The search stops once the answer is known to within this percentage
+const SEARCH_PRECISION_PERCENT: u64 = 1;
+
+/// How quickly the transfer should be relayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeTarget {
+    /// Pay whatever the pool is missing for the next batch of this token to be profitable
+    NextBatch,
+    /// Pay this transfer's share of the next batch and wait for the pool to fill up
+    Cheapest,
+}
+
+impl FromStr for FeeTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "next-batch" => Ok(FeeTarget::NextBatch),
+            "cheapest" => Ok(FeeTarget::Cheapest),
+            _ => Err(format!(
+                "Unknown fee target {}, use next-batch or cheapest",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BridgeFeeEstimate {
+    /// The suggested bridge fee in base units of the transferred token, None if relayers can
+    /// not price the token because it has no Uniswap pool with WETH
+    pub fee: Option<Uint256>,
+    /// Fees already waiting in the pool for this token
+    pub pending_fees: Uint256,
+    /// The estimated gas and gas price to submit the next batch
+    pub next_batch_cost: GasCost,
+    /// The number of transfers the next batch was priced with
+    pub next_batch_transfers: u64,
+}
+
+/// Estimates the bridge fee for a transfer of `erc20` to Ethereum. `caller` is used as the
+/// sender of the simulated batch and of the Uniswap price queries
+pub async fn estimate_bridge_fee(
+    web3: &Web3,
+    grpc_client: &mut GravityQueryClient<Channel>,
+    erc20: EthAddress,
+    caller: EthAddress,
+    target: FeeTarget,
+) -> Result<BridgeFeeEstimate, GravityError> {
+    let pending_fees = get_batch_fees(grpc_client)
+        .await?
+        .remove(&erc20)
+        .unwrap_or_else(|| 0u8.into());
+    let (next_batch_cost, next_batch_transfers) =
+        match simulate_next_batch(web3, grpc_client, erc20, caller).await? {
+            Some(simulated) => simulated,
+            None => {
+                let signers = get_current_valset(grpc_client).await?.members.len() as u64;
+                let cost = GasCost {
+                    gas: submit_batch_gas(signers, NEXT_BATCH_TRANSFERS),
+                    gas_price: web3.eth_gas_price().await?,
+                };
+                (cost, NEXT_BATCH_TRANSFERS)
+            }
+        };
+    let needed = with_margin(next_batch_cost.get_total());
+    let share = needed.clone() / Uint256::from(next_batch_transfers);
+    trace!(
+        "Next batch of {} costs {} wei with {} transfers, {} in fees pending",
+        erc20,
+        next_batch_cost.get_total(),
+        next_batch_transfers,
+        pending_fees
+    );
+
+    let fee = match priced_fee(web3, erc20, caller, target, needed, share, &pending_fees).await {
+        Ok(fee) => fee,
+        Err(e) => {
+            warn!(
+                "Unable to determine swap price of token {} for WETH, relayers will not be able to price the fee {:?}",
+                erc20, e
+            );
+            None
+        }
+    };
+
+    Ok(BridgeFeeEstimate {
+        fee,
+        pending_fees,
+        next_batch_cost,
+        next_batch_transfers,
+    })
+}
+
+/// Prices the next batch the way relayers do, by simulating the newest signed batch of `erc20`,
+/// or of any token if there is none, as `caller`. Returns None if there is no batch to simulate
+async fn simulate_next_batch(
+    web3: &Web3,
+    grpc_client: &mut GravityQueryClient<Channel>,
+    erc20: EthAddress,
+    caller: EthAddress,
+) -> Result<Option<(GasCost, u64)>, GravityError> {
+    let params = get_gravity_params(grpc_client).await?;
+    let gravity_contract_address: EthAddress = match params.bridge_ethereum_address.parse() {
+        Ok(address) => address,
+        Err(e) => {
+            warn!(
+                "Invalid bridge_ethereum_address {}, can not simulate a batch {:?}",
+                params.bridge_ethereum_address, e
+            );
+            return Ok(None);
+        }
+    };
+    let current_valset = find_latest_valset(grpc_client, gravity_contract_address, web3).await?;
+    let batches = get_batches_and_signatures(
+        current_valset.clone(),
+        grpc_client,
+        params.gravity_id.clone(),
+    )
+    .await;
+    let batch = match newest_batch(batches, erc20) {
+        Some(batch) => batch,
+        None => {
+            trace!("No signed batch to simulate, using the gas estimate");
+            return Ok(None);
+        }
+    };
+    let transfers = max(batch.batch.transactions.len() as u64, 1);
+    match estimate_tx_batch_cost(
+        current_valset,
+        batch.batch,
+        &batch.sigs,
+        web3,
+        gravity_contract_address,
+        params.gravity_id,
+        caller,
+    )
+    .await
+    {
+        Ok(cost) => Ok(Some((cost, transfers))),
+        Err(e) => {
+            warn!("Batch cost estimate failed, using the gas estimate {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// The newest signed batch of `erc20` if there is one, otherwise the newest of any token
+fn newest_batch(
+    mut batches: HashMap<EthAddress, Vec<SubmittableBatch>>,
+    erc20: EthAddress,
+) -> Option<SubmittableBatch> {
+    // the lists are ordered oldest first
+    if let Some(batch) = batches.get_mut(&erc20).and_then(|b| b.pop()) {
+        return Some(batch);
+    }
+    batches
+        .into_iter()
+        .filter_map(|(_, mut b)| b.pop())
+        .max_by_key(|b| b.batch.nonce)
+}
+
+fn submit_batch_gas(signers: u64, transfers: u64) -> Uint256 {
+    (SUBMIT_BATCH_BASE_GAS
+        + SUBMIT_BATCH_GAS_PER_SIGNER * signers
+        + SUBMIT_BATCH_GAS_PER_TRANSFER * transfers)
+        .into()
+}
+
+fn with_margin(cost: Uint256) -> Uint256 {
+    cost * Uint256::from(100 + NEXT_BATCH_MARGIN_PERCENT) / Uint256::from(100u8)
+}
+
+/// Finds the fees, in `erc20`, that are worth `needed` for the next batch and `share` for this
+/// transfer's share of it. None if the pool is too shallow to ever be worth that much
+async fn priced_fee(
+    web3: &Web3,
+    erc20: EthAddress,
+    caller: EthAddress,
+    target: FeeTarget,
+    needed: Uint256,
+    share: Uint256,
+    pending_fees: &Uint256,
+) -> Result<Option<Uint256>, Web3Error> {
+    let start = max(pending_fees.clone(), 1u8.into());
+    let share = match amount_worth(web3, erc20, caller, share, start.clone()).await? {
+        Some(share) => share,
+        None => return Ok(None),
+    };
+    let needed = match target {
+        FeeTarget::Cheapest => 0u8.into(),
+        FeeTarget::NextBatch => match amount_worth(web3, erc20, caller, needed, start).await? {
+            Some(needed) => needed,
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(suggested_fee(
+        target,
+        needed,
+        share,
+        pending_fees.clone(),
+    )))
+}
+
+/// The smallest amount of `erc20` that relayers value at `weth` or more
+async fn amount_worth(
+    web3: &Web3,
+    erc20: EthAddress,
+    caller: EthAddress,
+    weth: Uint256,
+    start: Uint256,
+) -> Result<Option<Uint256>, Web3Error> {
+    if erc20 == *WETH_CONTRACT_ADDRESS {
+        return Ok(Some(weth));
+    }
+    let mut search = AmountSearch::new(start);
+    while let Some(amount) = search.next_amount() {
+        let value = get_weth_value(web3, erc20, amount.clone(), caller).await?;
+        search.update(amount, value >= weth);
+    }
+    Ok(search.result())
+}
+
+/// Searches for the smallest token amount that is worth enough when only told whether a given
+/// amount is, the price impact of Uniswap makes the value grow slower than the amount. The
+/// amount grows until it is enough and is then bisected, rounding up
+struct AmountSearch {
+    start: Uint256,
+    /// The largest amount known not to be enough
+    low: Uint256,
+    /// The smallest amount known to be enough
+    high: Option<Uint256>,
+    growth_steps: u32,
+}
+
+impl AmountSearch {
+    fn new(start: Uint256) -> Self {
+        AmountSearch {
+            start: max(start, 1u8.into()),
+            low: 0u8.into(),
+            high: None,
+            growth_steps: 0,
+        }
+    }
+
+    /// The next amount to price, None once the search is over
+    fn next_amount(&self) -> Option<Uint256> {
+        match &self.high {
+            None if self.growth_steps >= SEARCH_MAX_GROWTH_STEPS => None,
+            None if self.low == Uint256::from(0u8) => Some(self.start.clone()),
+            None => Some(self.low.clone() * Uint256::from(SEARCH_GROWTH)),
+            Some(high) => {
+                let gap = high.clone() - self.low.clone();
+                if gap <= Uint256::from(1u8)
+                    || gap * Uint256::from(100u8)
+                        <= high.clone() * Uint256::from(SEARCH_PRECISION_PERCENT)
+                {
+                    None
+                } else {
+                    Some((self.low.clone() + high.clone()) / Uint256::from(2u8))
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, amount: Uint256, enough: bool) {
+        if enough {
+            self.high = Some(amount);
+        } else {
+            if self.high.is_none() {
+                self.growth_steps += 1;
+            }
+            self.low = amount;
+        }
+    }
+
+    /// The smallest amount found to be enough, None if no amount was
+    fn result(self) -> Option<Uint256> {
+        self.high
+    }
+}
+
+/// Picks the fee for `target` from the token amounts worth the whole next batch and this
+/// transfer's share of it, less what the pool already pays
+fn suggested_fee(
+    target: FeeTarget,
+    next_batch_amount: Uint256,
+    share_amount: Uint256,
+    pending_fees: Uint256,
+) -> Uint256 {
+    let cheapest = max(share_amount, 1u8.into());
+    match target {
+        FeeTarget::Cheapest => cheapest,
+        FeeTarget::NextBatch => {
+            if next_batch_amount > pending_fees {
+                max(next_batch_amount - pending_fees, cheapest)
+            } else {
+                cheapest
+            }
+        }
+    }
+}
+
+#[test]
+fn test_suggested_fee() {
+    let fee =
+        |target, pending: u64| suggested_fee(target, 1100u64.into(), 50u64.into(), pending.into());
+    assert_eq!(fee(FeeTarget::Cheapest, 0), Uint256::from(50u64));
+    // the next batch is worth 1100, less what the pool already pays
+    assert_eq!(fee(FeeTarget::NextBatch, 0), Uint256::from(1100u64));
+    assert_eq!(fee(FeeTarget::NextBatch, 600), Uint256::from(500u64));
+    // never less than the cheapest fee, even if the pool already covers the next batch
+    assert_eq!(fee(FeeTarget::NextBatch, 2000), Uint256::from(50u64));
+    assert_eq!(
+        suggested_fee(FeeTarget::Cheapest, 0u8.into(), 0u8.into(), 0u8.into()),
+        Uint256::from(1u8)
+    );
+    assert_eq!("next-batch".parse(), Ok(FeeTarget::NextBatch));
+    assert!("fastest".parse::<FeeTarget>().is_err());
+}
+
+#[test]
+fn test_amount_search() {
+    // runs the search against a pool that pays `value(amount)` in WETH
+    let search = |start: u64, weth: f64, value: &dyn Fn(f64) -> f64| {
+        let mut search = AmountSearch::new(start.into());
+        let mut quotes = 0;
+        while let Some(amount) = search.next_amount() {
+            let enough = value(amount.to_string().parse().unwrap()) >= weth;
+            search.update(amount, enough);
+            quotes += 1;
+        }
+        (search.result().map(|r| r.to_string()), quotes)
+    };
+    // one token is worth two WETH, an exact answer once the gap is a single unit
+    let (found, quotes) = search(1, 100.0, &|a| a * 2.0);
+    assert_eq!(found, Some("50".to_string()));
+    assert!(quotes < 20);
+    // with price impact the answer is within the precision of the true one and rounds up
+    let impact = |a: f64| a * 1e6 / (1e6 + a);
+    let (found, _) = search(5_000, 5e5, &impact);
+    let found: f64 = found.unwrap().parse().unwrap();
+    assert!(impact(found) >= 5e5);
+    assert!(found <= 1.01e6);
+    // a pool holding less WETH than needed is never worth enough
+    let (found, quotes) = search(1, 2e6, &impact);
+    assert_eq!(found, None);
+    assert_eq!(quotes, SEARCH_MAX_GROWTH_STEPS as usize);
+}
+
+#[test]
+fn test_with_margin() {
+    assert_eq!(with_margin(1000u64.into()), Uint256::from(1100u64));
+    let cost = GasCost {
+        gas: submit_batch_gas(4, NEXT_BATCH_TRANSFERS),
+        gas_price: 2u8.into(),
+    };
+    assert_eq!(cost.get_total(), Uint256::from(1_080_000u64));
+}
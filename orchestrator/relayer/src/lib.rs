@@ -1,4 +1,5 @@
 pub mod batch_relaying;
+pub mod fee_estimator;
 pub mod find_latest_valset;
 pub mod logic_call_relaying;
 pub mod main_loop;
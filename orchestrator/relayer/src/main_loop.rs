@@ -5,11 +5,10 @@ use crate::{
 use clarity::address::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
 use ethereum_gravity::utils::get_gravity_id;
-use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::connection_pool::CosmosConnectionPool;
 use gravity_utils::types::RelayerConfig;
 use std::time::{Duration, Instant};
 use tokio::time::sleep as delay_for;
-use tonic::transport::Channel;
 use web30::client::Web3;
 
 pub const LOOP_SPEED: Duration = Duration::from_secs(17);
@@ -19,14 +18,18 @@ pub const LOOP_SPEED: Duration = Duration::from_secs(17);
 pub async fn relayer_main_loop(
     ethereum_key: EthPrivateKey,
     web3: Web3,
-    grpc_client: GravityQueryClient<Channel>,
+    cosmos: CosmosConnectionPool,
     gravity_contract_address: EthAddress,
     relayer_config: &RelayerConfig,
 ) {
-    let mut grpc_client = grpc_client;
     loop {
         let loop_start = Instant::now();
 
+        // if our Cosmos node is syncing or down switch to a fallback node, if there are
+        // none we just continue with the one we have and let the queries below fail
+        cosmos.check_health().await;
+        let mut grpc_client = cosmos.get_grpc();
+
         let our_ethereum_address = ethereum_key.to_public_key().unwrap();
         let current_valset =
             find_latest_valset(&mut grpc_client, gravity_contract_address, &web3).await;
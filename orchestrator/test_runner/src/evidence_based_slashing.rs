@@ -5,7 +5,7 @@ use crate::utils::ValidatorKeys;
 use crate::STAKING_TOKEN;
 use crate::STARTING_STAKE_PER_VALIDATOR;
 use crate::TOTAL_TIMEOUT;
-use crate::{get_fee, utils::get_operator_address};
+use crate::{get_fee, get_fee_settings, utils::get_operator_address};
 use clarity::utils::bytes_to_hex_str;
 use clarity::Address as EthAddress;
 use cosmos_gravity::{send::submit_bad_signature_evidence, utils::BadSignatureEvidence};
@@ -74,7 +74,7 @@ pub async fn evidence_based_slashing(
     // submit the evidence
     let res = submit_bad_signature_evidence(
         submitter_private_key,
-        get_fee(),
+        &get_fee_settings(),
         contact,
         BadSignatureEvidence::Valset(false_valset),
         eth_signature,
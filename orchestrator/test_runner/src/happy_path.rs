@@ -1,4 +1,3 @@
-use crate::get_fee;
 use crate::utils::*;
 use crate::MINER_ADDRESS;
 use crate::MINER_PRIVATE_KEY;
@@ -6,9 +5,9 @@ use crate::OPERATION_TIMEOUT;
 use crate::STAKING_TOKEN;
 use crate::STARTING_STAKE_PER_VALIDATOR;
 use crate::TOTAL_TIMEOUT;
-use bytes::BytesMut;
+use crate::{get_fee, get_fee_settings, get_fee_settings_in};
 use clarity::{Address as EthAddress, Uint256};
-use cosmos_gravity::query::get_attestations;
+use cosmos_gravity::attestations::{get_typed_attestations, Claim};
 use cosmos_gravity::send::{send_request_batch, send_to_eth};
 use cosmos_gravity::{query::get_oldest_unsigned_transaction_batch, send::send_ethereum_claims};
 use deep_space::address::Address as CosmosAddress;
@@ -19,12 +18,8 @@ use ethereum_gravity::utils::get_event_nonce;
 use ethereum_gravity::utils::get_valset_nonce;
 use ethereum_gravity::{send_to_cosmos::send_to_cosmos, utils::get_tx_batch_nonce};
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
-use gravity_proto::gravity::MsgSendToCosmosClaim;
-use gravity_proto::gravity::MsgValsetUpdatedClaim;
 use gravity_utils::types::SendToCosmosEvent;
-use prost::Message;
 use rand::Rng;
-use std::any::type_name;
 use std::thread::sleep;
 use std::time::Duration;
 use std::time::Instant;
@@ -121,49 +116,14 @@ pub async fn happy_path_test(
     .await;
 }
 
-// Iterates each attestation known by the grpc endpoint by calling `get_attestations()`
-// Executes the input closure `f` against each attestation's decoded claim
-// This is useful for testing that certain attestations exist in the oracle,
-// see the consumers of this function (check_valset_update_attestation(),
-// check_send_to_cosmos_attestation(), etc.) for examples of usage
-//
-// `F` is the type of the closure, a state mutating function which may be called multiple times
-// `F` functions take a single parameter of type `T`, which is some sort of `Message`
-// The type `T` is very important as it dictates how we decode the message
-pub async fn iterate_attestations<F: FnMut(T), T: Message + Default>(
-    grpc_client: &mut GravityQueryClient<Channel>,
-    f: &mut F,
-) {
-    let attestations = get_attestations(grpc_client, None)
+// Gets every attestation known by the grpc endpoint with its claim decoded
+async fn get_claims(grpc_client: &mut GravityQueryClient<Channel>) -> Vec<Claim> {
+    get_typed_attestations(grpc_client, None)
         .await
-        .expect("Something happened while getting attestations after delegating to validator");
-    for (i, att) in attestations.into_iter().enumerate() {
-        let claim = att.clone().claim;
-        trace!("Processing attestation {}", i);
-        if claim.is_none() {
-            trace!("Attestation returned with no claim: {:?}", att);
-            continue;
-        }
-        let claim = claim.unwrap();
-        let mut buf = BytesMut::with_capacity(claim.value.len());
-        buf.extend_from_slice(&claim.value);
-
-        // Here we use the `T` type to decode whatever type of message this attestation holds
-        // for use in the `f` function
-        let decoded = T::decode(buf);
-
-        // Decoding errors indicate there is some other attestation we don't care about
-        if decoded.is_err() {
-            debug!(
-                "Found an attestation which is not a {}: {:?}",
-                type_name::<T>(),
-                att,
-            );
-            continue;
-        }
-        let decoded = decoded.unwrap();
-        f(decoded);
-    }
+        .expect("Something happened while getting attestations")
+        .into_iter()
+        .map(|a| a.claim)
+        .collect()
 }
 
 pub async fn wait_for_nonzero_valset(web30: &Web3, gravity_address: EthAddress) {
@@ -268,7 +228,11 @@ async fn check_valset_update_attestation(
     keys: &[ValidatorKeys],
 ) {
     let mut found = true;
-    iterate_attestations(grpc_client, &mut |decoded: MsgValsetUpdatedClaim| {
+    for claim in get_claims(grpc_client).await {
+        let decoded = match claim {
+            Claim::ValsetUpdated(decoded) => decoded,
+            _ => continue,
+        };
         // Check that each bridge validator is one of the addresses in our keys
         for bridge_val in decoded.members {
             let found_val = keys.iter().any(|key: &ValidatorKeys| {
@@ -283,8 +247,7 @@ async fn check_valset_update_attestation(
             }
             found &= found_val;
         }
-    })
-    .await;
+    }
     assert!(
         found,
         "Could not find the valset updated attestation we were looking for!"
@@ -381,13 +344,14 @@ async fn check_send_to_cosmos_attestation(
     let start = Instant::now();
     let mut found = false;
     loop {
-        iterate_attestations(grpc_client, &mut |decoded: MsgSendToCosmosClaim| {
-            let right_contract = decoded.token_contract == erc20_address.to_string();
-            let right_destination = decoded.cosmos_receiver == receiver.to_string();
-            let right_sender = decoded.ethereum_sender == sender.to_string();
-            found = right_contract && right_destination && right_sender;
-        })
-        .await;
+        for claim in get_claims(grpc_client).await {
+            if let Claim::SendToCosmos(decoded) = claim {
+                let right_contract = decoded.token_contract == erc20_address.to_string();
+                let right_destination = decoded.cosmos_receiver == receiver.to_string();
+                let right_sender = decoded.ethereum_sender == sender.to_string();
+                found |= right_contract && right_destination && right_sender;
+            }
+        }
         if found {
             break;
         } else if Instant::now() - start > TOTAL_TIMEOUT {
@@ -441,7 +405,7 @@ async fn test_batch(
             amount: amount.clone(),
         },
         bridge_denom_fee.clone(),
-        bridge_denom_fee.clone(),
+        &get_fee_settings_in(bridge_denom_fee.denom.clone()),
         contact,
     )
     .await
@@ -452,7 +416,7 @@ async fn test_batch(
     send_request_batch(
         requester_cosmos_private_key,
         token_name.clone(),
-        get_fee(),
+        &get_fee_settings(),
         contact,
         None,
     )
@@ -563,7 +527,7 @@ async fn submit_duplicate_erc20_send(
             vec![],
             vec![],
             vec![],
-            get_fee(),
+            &get_fee_settings(),
         )
         .await
         .unwrap();
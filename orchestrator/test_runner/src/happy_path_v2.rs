@@ -5,7 +5,7 @@ use crate::utils::get_user_key;
 use crate::utils::send_one_eth;
 use crate::utils::start_orchestrators;
 use crate::TOTAL_TIMEOUT;
-use crate::{get_fee, utils::ValidatorKeys};
+use crate::{get_fee, get_fee_settings, utils::ValidatorKeys};
 use clarity::Address as EthAddress;
 use clarity::Uint256;
 use cosmos_gravity::send::TIMEOUT;
@@ -97,7 +97,7 @@ pub async fn happy_path_test_v2(
         user.eth_address,
         send_to_eth_coin,
         get_fee(),
-        get_fee(),
+        &get_fee_settings(),
         contact,
     )
     .await
@@ -111,7 +111,7 @@ pub async fn happy_path_test_v2(
     let res = send_request_batch(
         keys[0].validator_key,
         token_to_send_to_eth.clone(),
-        get_fee(),
+        &get_fee_settings(),
         contact,
         Some(TIMEOUT),
     )
@@ -19,6 +19,7 @@ use deep_space::Address as CosmosAddress;
 use deep_space::Contact;
 use evidence_based_slashing::evidence_based_slashing;
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::types::FeeSettings;
 use happy_path::happy_path_test;
 use happy_path_v2::happy_path_test_v2;
 use lazy_static::lazy_static;
@@ -89,6 +90,23 @@ pub fn get_fee() -> Coin {
     }
 }
 
+/// Gets the fee settings used by the test runner for Cosmos transactions in the
+/// standard non-token fee denom, see get_fee_settings_in
+pub fn get_fee_settings() -> FeeSettings {
+    get_fee_settings_in(get_test_token_name())
+}
+
+/// Gets fee settings that pay Cosmos transaction fees in the provided denom. The test chain has no
+/// minimum gas price so the gas price is set such that the fee rounds up to a single token
+pub fn get_fee_settings_in(denom: String) -> FeeSettings {
+    FeeSettings {
+        gas_price: format!("0.000001{}", denom).parse().unwrap(),
+        gas_adjustment: 1.3,
+        max_gas_price: None,
+        fixed_fee: None,
+    }
+}
+
 pub fn get_test_token_name() -> String {
     "footoken".to_string()
 }
@@ -3,6 +3,7 @@
 //! and determine whether relays should happen or not
 use std::time::{Duration, Instant};
 
+use crate::get_fee_settings_in;
 use crate::happy_path::test_erc20_deposit;
 use crate::utils::{check_cosmos_balance, send_one_eth, start_orchestrators, ValidatorKeys};
 use crate::MINER_PRIVATE_KEY;
@@ -172,7 +173,7 @@ async fn setup_batch_test(
             amount: send_amount.clone(),
         },
         bridge_denom_fee.clone(),
-        bridge_denom_fee.clone(),
+        &get_fee_settings_in(bridge_denom_fee.denom.clone()),
         contact,
     )
     .await
@@ -266,15 +267,10 @@ async fn test_good_batch(
         .await;
 
     info!("Requesting transaction batch for 20 CosmosDai");
-    let request_batch_fee = Coin {
-        denom: cdai_held.denom.clone(),
-        amount: one_eth() * 20u64.into(),
-    };
-
     send_request_batch(
         requester_cosmos_private_key,
-        request_batch_fee.denom.clone(),
-        request_batch_fee,
+        cdai_held.denom.clone(),
+        &get_fee_settings_in(cdai_held.denom.clone()),
         contact,
         Some(TIMEOUT),
     )
@@ -349,15 +345,10 @@ async fn test_bad_batch(
         .await;
 
     info!("Requesting transaction batch for very little CosmosDAI");
-    let request_batch_fee = Coin {
-        denom: cdai_held.denom.clone(),
-        amount: 2_500u64.into(), // approximately 1 ETH wei in DAI
-    };
-
     send_request_batch(
         requester_cosmos_private_key,
-        request_batch_fee.denom.clone(),
-        request_batch_fee,
+        cdai_held.denom.clone(),
+        &get_fee_settings_in(cdai_held.denom.clone()),
         contact,
         Some(TIMEOUT),
     )
@@ -1,4 +1,6 @@
-use crate::{get_fee, one_eth, one_hundred_eth, utils::*, TOTAL_TIMEOUT};
+use crate::{
+    get_fee_settings, get_fee_settings_in, one_eth, one_hundred_eth, utils::*, TOTAL_TIMEOUT,
+};
 use clarity::Address as EthAddress;
 use cosmos_gravity::{
     query::get_pending_send_to_eth,
@@ -167,7 +169,7 @@ pub async fn transaction_stress_test(
                 e_dest_addr,
                 send_coin,
                 send_fee.clone(),
-                send_fee,
+                &get_fee_settings_in(send_fee.denom.clone()),
                 contact,
             );
             futs.push(res);
@@ -203,7 +205,7 @@ pub async fn transaction_stress_test(
     for tx in pending.unbatched_transfers {
         let res = cancel_send_to_eth(
             user_who_cancels.cosmos_key,
-            bridge_fee.clone(),
+            &get_fee_settings_in(bridge_fee.denom.clone()),
             contact,
             tx.id,
         )
@@ -237,7 +239,7 @@ pub async fn transaction_stress_test(
     for tx in pending.unbatched_transfers {
         let res = cancel_send_to_eth(
             user_who_cancels.cosmos_key,
-            bridge_fee.clone(),
+            &get_fee_settings_in(bridge_fee.denom.clone()),
             contact,
             tx.id,
         )
@@ -250,7 +252,7 @@ pub async fn transaction_stress_test(
         let res = send_request_batch(
             keys[0].validator_key,
             denom,
-            get_fee(),
+            &get_fee_settings(),
             contact,
             Some(TIMEOUT),
         )
@@ -1,3 +1,4 @@
+use crate::get_fee_settings_in;
 use crate::happy_path::test_erc20_deposit;
 use crate::one_eth;
 use crate::utils::*;
@@ -61,7 +62,7 @@ pub async fn send_to_eth_and_cancel(
             amount: amount.clone(),
         },
         bridge_denom_fee.clone(),
-        bridge_denom_fee.clone(),
+        &get_fee_settings_in(bridge_denom_fee.denom.clone()),
         contact,
     )
     .await
@@ -81,7 +82,7 @@ pub async fn send_to_eth_and_cancel(
 
     cancel_send_to_eth(
         user_keys.cosmos_key,
-        bridge_denom_fee,
+        &get_fee_settings_in(bridge_denom_fee.denom),
         contact,
         send_to_eth_id,
     )
@@ -1,8 +1,8 @@
-use crate::get_fee;
 use crate::ADDRESS_PREFIX;
 use crate::COSMOS_NODE_GRPC;
 use crate::ETH_NODE;
 use crate::TOTAL_TIMEOUT;
+use crate::{get_fee, get_fee_settings};
 use crate::{one_eth, MINER_PRIVATE_KEY};
 use crate::{MINER_ADDRESS, OPERATION_TIMEOUT};
 use actix::System;
@@ -17,6 +17,7 @@ use futures::future::join_all;
 use gravity_proto::cosmos_sdk_proto::cosmos::params::v1beta1::ParamChange;
 use gravity_proto::cosmos_sdk_proto::cosmos::params::v1beta1::ParameterChangeProposal;
 use gravity_proto::gravity::query_client::QueryClient as GravityQueryClient;
+use gravity_utils::connection_pool::CosmosConnectionPool;
 use gravity_utils::types::GravityBridgeToolsConfig;
 use orchestrator::main_loop::orchestrator_main_loop;
 use rand::Rng;
@@ -262,15 +263,24 @@ pub async fn start_orchestrators(
                 ADDRESS_PREFIX.as_str(),
             )
             .unwrap();
+            let cosmos = CosmosConnectionPool::new(
+                ADDRESS_PREFIX.to_string(),
+                COSMOS_NODE_GRPC.to_string(),
+                contact,
+                grpc_client,
+                Vec::new(),
+                OPERATION_TIMEOUT,
+            );
             let fut = orchestrator_main_loop(
                 k.orch_key,
                 k.eth_key,
                 web30,
-                contact,
-                grpc_client,
+                cosmos,
                 gravity_address,
-                get_fee(),
+                get_fee_settings(),
                 config,
+                None,
+                None,
             );
             let system = System::new();
             system.block_on(fut);